@@ -0,0 +1,43 @@
+use bevy::math::{bounding::Aabb2d, Vec2};
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use flappy_bird::broadphase::{overlapping_naive, pipe_field, Broadphase};
+
+// Birds spread over the whole field, so every part of the sorted list gets searched
+fn birds(count: usize) -> Vec<Aabb2d> {
+    (0..64)
+        .map(|step| {
+            let x = step as f32 / 64. * count as f32 * 150.;
+            Aabb2d::new(Vec2::new(x, 0.), Vec2::new(17., 12.))
+        })
+        .collect()
+}
+
+fn broadphase(c: &mut Criterion) {
+    let mut group = c.benchmark_group("collision");
+    for count in [50, 200, 500] {
+        let boxes = pipe_field(count, 0);
+        let birds = birds(count);
+
+        group.bench_with_input(BenchmarkId::new("naive", count), &boxes, |b, boxes| {
+            b.iter(|| {
+                for bird in &birds {
+                    black_box(overlapping_naive(boxes, bird));
+                }
+            })
+        });
+
+        // Includes building the sorted list, which happens once per frame in the game
+        group.bench_with_input(BenchmarkId::new("broadphase", count), &boxes, |b, boxes| {
+            b.iter(|| {
+                let broadphase = Broadphase::new(boxes);
+                for bird in &birds {
+                    black_box(broadphase.overlapping(boxes, bird));
+                }
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, broadphase);
+criterion_main!(benches);
@@ -0,0 +1,261 @@
+use bevy::{input::InputSystems, platform::collections::HashSet, prelude::*};
+
+use crate::{constants::*, resources::Settings};
+
+pub fn actions_plugin(app: &mut App) {
+    app.init_resource::<ActionState>()
+        .add_systems(PreUpdate, collect_actions.after(InputSystems));
+}
+
+// What the player wants to do, independent of the device they did it with
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Action {
+    Flap,
+    Pause,
+    Restart,
+    MenuUp,
+    MenuDown,
+    Confirm,
+    Back,
+}
+
+impl Action {
+    pub const ALL: [Action; 7] = [
+        Action::Flap,
+        Action::Pause,
+        Action::Restart,
+        Action::MenuUp,
+        Action::MenuDown,
+        Action::Confirm,
+        Action::Back,
+    ];
+
+    fn keys(self) -> &'static [KeyCode] {
+        match self {
+            Action::Flap => &[KeyCode::Space],
+            Action::Pause => &[KeyCode::Escape],
+            Action::Restart => &[KeyCode::KeyR],
+            Action::MenuUp => &[KeyCode::ArrowUp],
+            Action::MenuDown => &[KeyCode::ArrowDown],
+            Action::Confirm => &[KeyCode::Enter],
+            Action::Back => &[KeyCode::Escape],
+        }
+    }
+
+    fn gamepad_buttons(self) -> &'static [GamepadButton] {
+        match self {
+            Action::Flap => &[GamepadButton::South],
+            Action::Pause => &[GamepadButton::Start],
+            Action::Restart => &[GamepadButton::North],
+            Action::MenuUp => &[GamepadButton::DPadUp],
+            Action::MenuDown => &[GamepadButton::DPadDown],
+            Action::Confirm => &[GamepadButton::South],
+            Action::Back => &[GamepadButton::East],
+        }
+    }
+
+    // The mouse and touch screen only ever flap, menus take their clicks through the UI
+    fn uses_pointer(self) -> bool {
+        self == Action::Flap
+    }
+
+    // Pulling the right trigger or pushing the left stick up flaps as well
+    fn uses_analog(self) -> bool {
+        self == Action::Flap
+    }
+}
+
+fn analog_flap(gamepad: &Gamepad) -> f32 {
+    let trigger = gamepad.get(GamepadButton::RightTrigger2).unwrap_or(0.);
+    let stick = gamepad.get(GamepadAxis::LeftStickY).unwrap_or(0.);
+    trigger.max(stick)
+}
+
+// Edge detection with hysteresis, `held` only lets go once the value drops
+// GAMEPAD_RELEASE_MARGIN below the deadzone. Returns whether this is a fresh press
+pub fn analog_press(value: f32, deadzone: f32, held: &mut bool) -> bool {
+    if *held {
+        if value < deadzone - GAMEPAD_RELEASE_MARGIN {
+            *held = false;
+        }
+        return false;
+    }
+
+    *held = value > deadzone;
+    *held
+}
+
+// Rebuilt from every device at the start of each frame
+#[derive(Resource, Default)]
+pub struct ActionState {
+    pressed: HashSet<Action>,
+    just_pressed: HashSet<Action>,
+    // Gamepads whose trigger or stick is past the deadzone, kept across frames
+    analog_held: HashSet<Entity>,
+}
+
+impl ActionState {
+    pub fn pressed(&self, action: Action) -> bool {
+        self.pressed.contains(&action)
+    }
+
+    pub fn just_pressed(&self, action: Action) -> bool {
+        self.just_pressed.contains(&action)
+    }
+}
+
+// Run condition counterpart of `ActionState::just_pressed`
+pub fn action_just_pressed(action: Action) -> impl FnMut(Res<ActionState>) -> bool + Clone {
+    move |actions: Res<ActionState>| actions.just_pressed(action)
+}
+
+// Any bound input going down counts as a fresh press, even while another one is held
+fn collect_actions(
+    key_input: Res<ButtonInput<KeyCode>>,
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    touches: Res<Touches>,
+    gamepads: Query<(Entity, &Gamepad)>,
+    settings: Res<Settings>,
+    mut actions: ResMut<ActionState>,
+) {
+    let flap_button = settings.input_bindings.flap_mouse_button.mouse_button();
+    actions.pressed.clear();
+    actions.just_pressed.clear();
+
+    let (mut analog_pressed, mut analog_just_pressed) = (false, false);
+    actions
+        .analog_held
+        .retain(|&entity| gamepads.contains(entity));
+    for (entity, gamepad) in &gamepads {
+        let mut held = actions.analog_held.contains(&entity);
+        analog_just_pressed |=
+            analog_press(analog_flap(gamepad), settings.gamepad_deadzone, &mut held);
+        analog_pressed |= held;
+        if held {
+            actions.analog_held.insert(entity);
+        } else {
+            actions.analog_held.remove(&entity);
+        }
+    }
+
+    for action in Action::ALL {
+        let keys = action.keys().iter().copied();
+        let buttons = action.gamepad_buttons().iter().copied();
+        let pointer = action.uses_pointer();
+        let analog = action.uses_analog();
+
+        let just_pressed = key_input.any_just_pressed(keys.clone())
+            || gamepads
+                .iter()
+                .any(|(_, gamepad)| gamepad.any_just_pressed(buttons.clone()))
+            || pointer && (mouse_input.just_pressed(flap_button) || touches.any_just_pressed())
+            || analog && analog_just_pressed;
+        let pressed = just_pressed
+            || key_input.any_pressed(keys)
+            || gamepads
+                .iter()
+                .any(|(_, gamepad)| gamepad.any_pressed(buttons.clone()))
+            || pointer && (mouse_input.pressed(flap_button) || touches.iter().next().is_some())
+            || analog && analog_pressed;
+
+        if pressed {
+            actions.pressed.insert(action);
+        }
+        if just_pressed {
+            actions.just_pressed.insert(action);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::RunSystemOnce;
+
+    use crate::resources::FlapButton;
+
+    use super::*;
+
+    fn input_world() -> World {
+        let mut world = World::new();
+        world.init_resource::<ButtonInput<KeyCode>>();
+        world.init_resource::<ButtonInput<MouseButton>>();
+        world.init_resource::<Touches>();
+        world.init_resource::<Settings>();
+        world.init_resource::<ActionState>();
+        world
+    }
+
+    #[test]
+    fn keys_and_buttons_become_actions() {
+        let mut world = input_world();
+        world
+            .resource_mut::<ButtonInput<KeyCode>>()
+            .press(KeyCode::Space);
+        world
+            .resource_mut::<ButtonInput<KeyCode>>()
+            .press(KeyCode::Escape);
+        world
+            .resource_mut::<ButtonInput<MouseButton>>()
+            .press(MouseButton::Right);
+
+        world.run_system_once(collect_actions).unwrap();
+        let actions = world.resource::<ActionState>();
+        assert!(actions.just_pressed(Action::Flap));
+        assert!(actions.just_pressed(Action::Pause));
+        assert!(actions.just_pressed(Action::Back));
+        assert!(!actions.pressed(Action::Restart));
+
+        // Next frame Space is still held and Escape was let go
+        let mut key_input = world.resource_mut::<ButtonInput<KeyCode>>();
+        key_input.clear();
+        key_input.release(KeyCode::Escape);
+        world.run_system_once(collect_actions).unwrap();
+        let actions = world.resource::<ActionState>();
+        assert!(actions.pressed(Action::Flap));
+        assert!(!actions.just_pressed(Action::Flap));
+        assert!(!actions.pressed(Action::Pause));
+    }
+
+    #[test]
+    fn only_the_bound_mouse_button_flaps() {
+        let mut world = input_world();
+        world
+            .resource_mut::<ButtonInput<MouseButton>>()
+            .press(MouseButton::Right);
+
+        world.run_system_once(collect_actions).unwrap();
+        assert!(!world.resource::<ActionState>().pressed(Action::Flap));
+
+        world
+            .resource_mut::<Settings>()
+            .input_bindings
+            .flap_mouse_button = FlapButton::Right;
+        world.run_system_once(collect_actions).unwrap();
+        assert!(world.resource::<ActionState>().just_pressed(Action::Flap));
+    }
+
+    // A trigger jittering around the deadzone, two real pulls with a release in between
+    #[test]
+    fn a_noisy_trigger_flaps_once_per_pull() {
+        let mut world = input_world();
+        let gamepad = world.spawn(Gamepad::default()).id();
+        let signal = [
+            0.02, -0.03, 0.04, 0.1, 0.55, 0.48, 0.6, 0.46, 0.52, 0.9, 0.41, 0.5, 0.2, 0.03, 0.49,
+            0.51, 0.44, 0.56, 0.36, 0.1,
+        ];
+
+        let mut flaps = 0;
+        for value in signal {
+            world
+                .get_mut::<Gamepad>(gamepad)
+                .unwrap()
+                .analog_mut()
+                .set(GamepadButton::RightTrigger2, value);
+            world.run_system_once(collect_actions).unwrap();
+            if world.resource::<ActionState>().just_pressed(Action::Flap) {
+                flaps += 1;
+            }
+        }
+        assert_eq!(flaps, 2);
+    }
+}
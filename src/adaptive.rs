@@ -0,0 +1,55 @@
+use bevy::prelude::*;
+
+use crate::{
+    constants::*,
+    persistence,
+    resources::{AdaptiveState, GameConfig, GameMode, RunTimer},
+    AppState,
+};
+
+const ADAPTIVE_FILE: &str = "adaptive.ron";
+
+pub fn adaptive_plugin(app: &mut App) {
+    app.insert_resource(persistence::load::<AdaptiveState>(ADAPTIVE_FILE).clamped())
+        .add_systems(
+            OnEnter(AppState::GameStart),
+            apply_adaptive_difficulty.run_if(resource_equals(GameMode::Adaptive)),
+        )
+        .add_systems(
+            OnEnter(AppState::GameOver),
+            update_adaptive_level.run_if(resource_equals(GameMode::Adaptive)),
+        );
+}
+
+// Only applied between runs so the pace never changes mid-run
+fn apply_adaptive_difficulty(adaptive_state: Res<AdaptiveState>, mut config: ResMut<GameConfig>) {
+    let adjustment = adaptive_state.level * ADAPTIVE_MAX_ADJUSTMENT;
+    config.pipe_speed = PIPE_BASE_SPEED * (1. + adjustment);
+    config.pipe_gap = BASE_PIPE_SPACE * (1. - adjustment);
+}
+
+fn update_adaptive_level(run_timer: Res<RunTimer>, mut adaptive_state: ResMut<AdaptiveState>) {
+    let duration = run_timer.0.elapsed_secs();
+    adaptive_state.recent_durations.push_back(duration);
+    while adaptive_state.recent_durations.len() > ADAPTIVE_QUICK_DEATH_STREAK {
+        adaptive_state.recent_durations.pop_front();
+    }
+
+    let quick_death_streak = adaptive_state.recent_durations.len() == ADAPTIVE_QUICK_DEATH_STREAK
+        && adaptive_state
+            .recent_durations
+            .iter()
+            .all(|&duration| duration < ADAPTIVE_QUICK_DEATH_SECS);
+
+    if quick_death_streak {
+        adaptive_state.level -= ADAPTIVE_LEVEL_STEP;
+        adaptive_state.recent_durations.clear();
+    } else if duration > ADAPTIVE_LONG_RUN_SECS {
+        adaptive_state.level += ADAPTIVE_LEVEL_STEP;
+    }
+    adaptive_state.level = adaptive_state
+        .level
+        .clamp(-MAX_ADAPTIVE_LEVEL, MAX_ADAPTIVE_LEVEL);
+
+    persistence::save(ADAPTIVE_FILE, &*adaptive_state);
+}
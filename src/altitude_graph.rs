@@ -0,0 +1,101 @@
+use bevy::{
+    color::palettes::css::{GOLD, WHITE},
+    prelude::*,
+};
+
+use crate::{
+    components::LivingBird,
+    constants::*,
+    events::IncrementScoreEvent,
+    resources::{AltitudeHistory, GameConfig},
+    step::physics_running,
+    AppState,
+};
+
+pub fn altitude_graph_plugin(app: &mut App) {
+    app.init_resource::<AltitudeHistory>()
+        .add_systems(OnEnter(AppState::GameStart), clear_altitude_history)
+        .add_systems(
+            FixedUpdate,
+            record_altitude.run_if(in_state(AppState::InGame).and(physics_running)),
+        )
+        .add_systems(
+            Update,
+            draw_altitude_graph.run_if(in_state(AppState::GameOver)),
+        )
+        .add_observer(mark_score);
+}
+
+fn clear_altitude_history(mut history: ResMut<AltitudeHistory>) {
+    history.clear();
+}
+
+// One sample per tick keeps the graph readable with several birds, the earliest spawned bird that
+// is still alive is followed
+fn record_altitude(
+    player_query: Query<(Entity, &Transform), LivingBird>,
+    mut history: ResMut<AltitudeHistory>,
+) {
+    let Some((_, transform)) = player_query.iter().min_by_key(|(entity, _)| *entity) else {
+        return;
+    };
+    if history.samples.len() == MAX_ALTITUDE_SAMPLES {
+        history.samples.pop_front();
+    }
+    history.samples.push_back(transform.translation.y);
+    history.total_samples += 1;
+}
+
+fn mark_score(_increment_event: On<IncrementScoreEvent>, mut history: ResMut<AltitudeHistory>) {
+    let latest_sample = history.total_samples.saturating_sub(1);
+    history.score_marks.push(latest_sample);
+}
+
+fn draw_altitude_graph(mut gizmos: Gizmos, history: Res<AltitudeHistory>, config: Res<GameConfig>) {
+    if history.samples.len() < 2 {
+        return;
+    }
+
+    gizmos.rect_2d(
+        Isometry2d::from_translation(ALTITUDE_GRAPH_CENTER),
+        ALTITUDE_GRAPH_SIZE,
+        WHITE.with_alpha(0.5),
+    );
+
+    let graph_origin = ALTITUDE_GRAPH_CENTER - ALTITUDE_GRAPH_SIZE / 2.;
+    let step = ALTITUDE_GRAPH_SIZE.x / (history.samples.len() - 1) as f32;
+    let to_graph = |index: usize, altitude: f32| {
+        let normalized_altitude =
+            ((altitude + WINDOW_SIZE.y / 2.) / config.world_height).clamp(0., 1.);
+        graph_origin
+            + Vec2::new(
+                index as f32 * step,
+                normalized_altitude * ALTITUDE_GRAPH_SIZE.y,
+            )
+    };
+
+    gizmos.linestrip_2d(
+        history
+            .samples
+            .iter()
+            .enumerate()
+            .map(|(index, &altitude)| to_graph(index, altitude)),
+        WHITE,
+    );
+
+    let first_sample = history.first_sample();
+    for &mark in &history.score_marks {
+        let Some(index) = mark.checked_sub(first_sample) else {
+            continue;
+        };
+        let Some(&altitude) = history.samples.get(index) else {
+            continue;
+        };
+
+        gizmos.circle_2d(
+            Isometry2d::from_translation(to_graph(index, altitude)),
+            5.,
+            GOLD,
+        );
+    }
+}
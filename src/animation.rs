@@ -8,7 +8,11 @@ use crate::{
 pub fn animation_plugin(app: &mut App) {
     app.add_systems(
         Update,
-        animate_sprite.run_if(in_state(AppState::InGame).or(in_state(AppState::GameStart))),
+        animate_sprite.run_if(
+            in_state(AppState::InGame)
+                .or(in_state(AppState::GameStart))
+                .or(in_state(AppState::MainMenu)),
+        ),
     );
 }
 
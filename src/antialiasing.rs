@@ -0,0 +1,25 @@
+use bevy::prelude::*;
+
+use crate::resources::{Antialiasing, Settings};
+
+pub fn antialiasing_plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        apply_antialiasing.run_if(resource_changed::<Settings>),
+    );
+}
+
+// Msaa is a required component of every camera, the render pipelines are rebuilt when it changes
+fn apply_antialiasing(settings: Res<Settings>, mut query: Query<&mut Msaa, With<Camera>>) {
+    let msaa = match settings.antialiasing {
+        Antialiasing::Off => Msaa::Off,
+        Antialiasing::X2 => Msaa::Sample2,
+        Antialiasing::X4 => Msaa::Sample4,
+    };
+
+    for mut camera_msaa in query.iter_mut() {
+        if *camera_msaa != msaa {
+            *camera_msaa = msaa;
+        }
+    }
+}
@@ -0,0 +1,145 @@
+use bevy::{
+    asset::LoadState,
+    color::palettes::css::{GRAY, LIME, RED, YELLOW},
+    prelude::*,
+};
+
+use crate::{
+    asset_variants::image_variant,
+    components::{AssetStatusLine, AssetStatusPanel},
+    constants::*,
+    resources::{BirdSkin, DebugFlags, HiDpi},
+};
+
+// Everything the game loads besides the bird skin, which is looked up from BirdSkin. The UI
+// text uses Bevy's built-in font, there is no font file to load
+const GAME_ASSETS: &[&str] = &[
+    PIPE_IMAGE,
+    GROUND_IMAGE,
+    BACKGROUND_IMAGE,
+    HEART_IMAGE,
+    SHIELD_IMAGE,
+    PIPE_SPAWN_SOUND,
+    GOLDEN_PIPE_SOUND,
+    MILESTONE_SOUND,
+    PERFECT_SOUND,
+    SHIELD_PICKUP_SOUND,
+    SHIELD_BREAK_SOUND,
+    MUSIC_LAYERS[0],
+    MUSIC_LAYERS[1],
+    MUSIC_LAYERS[2],
+];
+
+#[derive(Resource)]
+struct AssetStatusRefresh(Timer);
+
+pub fn asset_status_plugin(app: &mut App) {
+    app.insert_resource(AssetStatusRefresh(Timer::from_seconds(
+        ASSET_STATUS_REFRESH,
+        TimerMode::Repeating,
+    )))
+    .add_systems(
+        Update,
+        (
+            toggle_asset_status.run_if(asset_status_enabled),
+            update_asset_status.run_if(any_with_component::<AssetStatusPanel>),
+        )
+            .chain(),
+    );
+}
+
+fn asset_status_enabled(flags: Res<DebugFlags>) -> bool {
+    flags.asset_status
+}
+
+// Images go through image_variant, so the panel checks the file this display actually asks for
+fn asset_paths(skin: &BirdSkin, hidpi: &HiDpi) -> Vec<String> {
+    std::iter::once(skin.0.image)
+        .chain(GAME_ASSETS.iter().copied())
+        .map(|name| image_variant(name, hidpi).path)
+        .collect()
+}
+
+fn toggle_asset_status(
+    key_input: Res<ButtonInput<KeyCode>>,
+    skin: Res<BirdSkin>,
+    hidpi: Res<HiDpi>,
+    mut refresh: ResMut<AssetStatusRefresh>,
+    panel: Query<Entity, With<AssetStatusPanel>>,
+    mut commands: Commands,
+) {
+    if !key_input.just_pressed(KeyCode::F6) {
+        return;
+    }
+
+    if let Ok(panel) = panel.single() {
+        commands.entity(panel).despawn();
+        return;
+    }
+
+    // Filled in on the next update rather than a quarter second later
+    let duration = refresh.0.duration();
+    refresh.0.set_elapsed(duration);
+    commands
+        .spawn((
+            AssetStatusPanel,
+            Node {
+                position_type: PositionType::Absolute,
+                top: px(5),
+                left: px(5),
+                padding: UiRect::all(px(8)),
+                flex_direction: FlexDirection::Column,
+                ..default()
+            },
+            BackgroundColor(Color::BLACK.with_alpha(0.7)),
+            GlobalZIndex(1),
+            Pickable::IGNORE,
+        ))
+        .with_children(|parent| {
+            for index in 0..asset_paths(&skin, &hidpi).len() {
+                parent.spawn((
+                    AssetStatusLine(index),
+                    Text::default(),
+                    TextFont {
+                        font_size: 16.0,
+                        ..default()
+                    },
+                    TextColor(GRAY.into()),
+                ));
+            }
+        });
+}
+
+fn update_asset_status(
+    time: Res<Time<Real>>,
+    asset_server: Res<AssetServer>,
+    skin: Res<BirdSkin>,
+    hidpi: Res<HiDpi>,
+    mut refresh: ResMut<AssetStatusRefresh>,
+    mut line_query: Query<(&AssetStatusLine, &mut Text, &mut TextColor)>,
+) {
+    if !refresh.0.tick(time.delta()).just_finished() {
+        return;
+    }
+
+    let paths = asset_paths(&skin, &hidpi);
+    for (line, mut text, mut color) in line_query.iter_mut() {
+        let Some(path) = paths.get(line.0) else {
+            continue;
+        };
+        // Sounds are only requested the first time they play
+        let state = asset_server
+            .get_path_id(path)
+            .and_then(|id| asset_server.get_load_state(id))
+            .unwrap_or(LoadState::NotLoaded);
+        let (status, status_color) = match state {
+            LoadState::NotLoaded => ("Not requested".to_string(), GRAY),
+            LoadState::Loading => ("Loading".to_string(), YELLOW),
+            LoadState::Loaded => ("Loaded".to_string(), LIME),
+            LoadState::Failed(error) => (format!("Failed: {error}"), RED),
+        };
+
+        text.0 = format!("{path}: {status}");
+        color.0 = status_color.into();
+    }
+}
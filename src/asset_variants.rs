@@ -0,0 +1,55 @@
+use bevy::{
+    prelude::*,
+    window::{PrimaryWindow, WindowScaleFactorChanged},
+};
+
+use crate::{
+    constants::{BACKGROUND_IMAGE, GROUND_IMAGE, HIDPI_SCALE_FACTOR},
+    resources::HiDpi,
+};
+
+// Images that ship an `@2x` file next to the standard one in `assets/`. The pipe is missing
+// because its slice inset is in texture pixels, see `pipes::pipe_slicer`
+const HIDPI_IMAGES: &[&str] = &[BACKGROUND_IMAGE, "bird_old.png", GROUND_IMAGE];
+
+pub struct ImageVariant {
+    pub path: String,
+    pub scale: u32,
+}
+
+pub fn asset_variants_plugin(app: &mut App) {
+    app.init_resource::<HiDpi>()
+        .add_systems(Startup, detect_hidpi)
+        .add_systems(Update, follow_scale_factor);
+}
+
+// The window only reports its real scale factor once winit has created it, after PreStartup
+fn detect_hidpi(window: Single<&Window, With<PrimaryWindow>>, mut hidpi: ResMut<HiDpi>) {
+    hidpi.set_if_neq(HiDpi(window.scale_factor() >= HIDPI_SCALE_FACTOR));
+}
+
+// Moving the window to a monitor with another scale factor switches the variants of anything
+// spawned from then on, the scenery swaps its images on its own
+fn follow_scale_factor(
+    mut scale_events: MessageReader<WindowScaleFactorChanged>,
+    mut hidpi: ResMut<HiDpi>,
+) {
+    if let Some(scale_event) = scale_events.read().last() {
+        hidpi.set_if_neq(HiDpi(
+            scale_event.scale_factor >= f64::from(HIDPI_SCALE_FACTOR),
+        ));
+    }
+}
+
+pub fn image_variant(name: &str, hidpi: &HiDpi) -> ImageVariant {
+    match name.rsplit_once('.') {
+        Some((stem, extension)) if hidpi.0 && HIDPI_IMAGES.contains(&name) => ImageVariant {
+            path: format!("{stem}@2x.{extension}"),
+            scale: 2,
+        },
+        _ => ImageVariant {
+            path: name.into(),
+            scale: 1,
+        },
+    }
+}
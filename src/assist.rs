@@ -0,0 +1,358 @@
+use std::f32::consts::PI;
+
+use bevy::{
+    color::palettes::css::{ORANGE, WHITE},
+    math::bounding::Aabb2d,
+    prelude::*,
+};
+
+use crate::{
+    components::{Airtime, ApproachWarning, ColliderType, LivingBird, Pipe, Velocity},
+    constants::*,
+    events::{JumpEvent, JumpSource},
+    gravity::fall_step,
+    pipes::world_top,
+    resources::{AssistLevel, FirstPipeSeen, GameConfig, Settings},
+    step::physics_running,
+    AppState, PauseState,
+};
+
+pub fn assist_plugin(app: &mut App) {
+    app.init_resource::<FirstPipeSeen>()
+        .add_systems(OnEnter(AppState::InGame), reset_first_pipe_seen)
+        .add_systems(
+            Update,
+            (
+                draw_gap_guide,
+                spawn_approach_warning,
+                draw_approach_warning,
+            )
+                .run_if(in_state(AppState::InGame).and(assist_guide_enabled)),
+        )
+        .add_systems(
+            Update,
+            draw_trajectory_guide.run_if(in_state(AppState::InGame).and(trajectory_guide_enabled)),
+        )
+        .add_systems(
+            FixedUpdate,
+            rescue_flap.run_if(
+                in_state(PauseState::Running)
+                    .and(physics_running)
+                    .and(rescue_flap_enabled),
+            ),
+        )
+        .add_observer(react_to_approach_warning);
+}
+
+fn assist_guide_enabled(settings: Res<Settings>) -> bool {
+    settings.assist_guide
+}
+
+fn trajectory_guide_enabled(settings: Res<Settings>) -> bool {
+    settings.trajectory_guide
+}
+
+fn rescue_flap_enabled(settings: Res<Settings>) -> bool {
+    settings.rescue_lookahead > 0.
+}
+
+// True when a falling bird would sink below `floor` within `lookahead` seconds. A flap sends
+// it upwards again, so this stays false until the bird falls back into danger
+pub fn rescue_flap_needed(
+    height: f32,
+    velocity: f32,
+    floor: f32,
+    gravity: f32,
+    lookahead: f32,
+) -> bool {
+    let predicted = height + velocity * lookahead + 0.5 * gravity * lookahead.powi(2);
+    velocity < 0. && predicted < floor
+}
+
+pub fn nearest_upcoming_pipe<'a>(
+    player_x: f32,
+    pipe_positions: impl IntoIterator<Item = &'a Transform>,
+) -> Option<Vec2> {
+    pipe_positions
+        .into_iter()
+        .map(|transform| transform.translation.truncate())
+        .filter(|position| position.x + PIPE_WIDTH / 2. >= player_x - PLAYER_SIZE.x as f32 / 2.)
+        .min_by(|a, b| a.x.total_cmp(&b.x))
+}
+
+// Only the collision boxes change, the sprites keep the normal pipe and gap size
+pub fn pipe_collider_box(
+    kind: &ColliderType,
+    center: Vec2,
+    size: Vec2,
+    gap_center_y: f32,
+    level: AssistLevel,
+) -> Aabb2d {
+    match kind {
+        ColliderType::Good => Aabb2d::new(
+            center,
+            Vec2::new(size.x * level.gate_width_scale(), size.y) / 2.,
+        ),
+        ColliderType::Bad => {
+            let mut aabb = Aabb2d::new(
+                center,
+                Vec2::new(size.x * level.pipe_width_scale(), size.y) / 2.,
+            );
+            if center.y > gap_center_y {
+                aabb.min.y += level.gap_inset();
+            } else {
+                aabb.max.y -= level.gap_inset();
+            }
+            aabb
+        }
+    }
+}
+
+// The demo autopilot's gap prediction, but it only flaps when a bird is about to hit the
+// ground or the lower edge of the pipe it is flying into
+fn rescue_flap(
+    settings: Res<Settings>,
+    config: Res<GameConfig>,
+    player_query: Query<(&Transform, &Velocity), LivingBird>,
+    pipe_query: Query<&Transform, With<Pipe>>,
+    mut commands: Commands,
+) {
+    let lookahead = settings.rescue_lookahead;
+    let ground_top = -WINDOW_SIZE.y / 2. + config.ground_height;
+
+    let needs_rescue = player_query.iter().any(|(transform, velocity)| {
+        let position = transform.translation.truncate();
+        let half_size =
+            PLAYER_SIZE.as_vec2() * config.hitbox_scale * transform.scale.truncate() / 2.;
+
+        let mut floor = ground_top + half_size.y;
+        if let Some(gap_center) = nearest_upcoming_pipe(position.x, pipe_query.iter()) {
+            let distance = gap_center.x - PIPE_WIDTH / 2. - (position.x + half_size.x);
+            if distance <= config.pipe_speed * lookahead {
+                floor = floor.max(gap_center.y - config.pipe_gap / 2. + half_size.y);
+            }
+        }
+
+        rescue_flap_needed(position.y, velocity.0, floor, config.gravity, lookahead)
+    });
+
+    if needs_rescue {
+        commands.trigger(JumpEvent {
+            source: JumpSource::Rescue,
+        });
+    }
+}
+
+// Where the bird goes if the player does nothing, as seen against the scrolling pipes. It steps
+// through the same kinematics as apply_gravity and ends where it meets the ground or the top of
+// the world
+pub fn predict_trajectory(
+    start: Vec2,
+    velocity: f32,
+    airtime: f32,
+    config: &GameConfig,
+    floor: f32,
+    ceiling: f32,
+) -> Vec<Vec2> {
+    let mut points = vec![start];
+    let (mut position, mut velocity) = (start, velocity);
+    let steps = (TRAJECTORY_GUIDE_DURATION / TRAJECTORY_GUIDE_STEP).round() as usize;
+
+    for step in 0..steps {
+        let curve_factor = config
+            .gravity_curve
+            .factor(airtime + step as f32 * TRAJECTORY_GUIDE_STEP);
+        let acceleration = config.gravity * curve_factor;
+        let previous = position;
+        (position.y, velocity) =
+            fall_step(position.y, velocity, acceleration, TRAJECTORY_GUIDE_STEP);
+        position.x += config.pipe_speed * TRAJECTORY_GUIDE_STEP;
+
+        let limit = if position.y < floor {
+            Some(floor)
+        } else if position.y > ceiling {
+            Some(ceiling)
+        } else {
+            None
+        };
+        if let Some(limit) = limit {
+            // Cut the last segment where it crosses the limit
+            let along = (limit - previous.y) / (position.y - previous.y);
+            points.push(previous.lerp(position, along));
+            break;
+        }
+        points.push(position);
+    }
+    points
+}
+
+// Dashes march along the arc, with reduced motion it is a row of still dots instead
+fn draw_trajectory_guide(
+    mut gizmos: Gizmos,
+    time: Res<Time>,
+    settings: Res<Settings>,
+    config: Res<GameConfig>,
+    player_query: Query<(&Transform, &Velocity, &Airtime), LivingBird>,
+) {
+    let floor = -WINDOW_SIZE.y / 2. + config.ground_height;
+    let ceiling = world_top(config.world_height);
+    let dash_phase = (time.elapsed_secs() * TRAJECTORY_GUIDE_DASH_RATE) as usize % 3;
+
+    for (transform, velocity, airtime) in player_query.iter() {
+        let points = predict_trajectory(
+            transform.translation.truncate(),
+            velocity.0,
+            airtime.0,
+            &config,
+            floor,
+            ceiling,
+        );
+
+        if settings.reduced_motion {
+            for point in points.iter().step_by(2) {
+                gizmos.circle_2d(
+                    Isometry2d::from_translation(*point),
+                    2.,
+                    WHITE.with_alpha(0.6),
+                );
+            }
+            continue;
+        }
+
+        // Two of every three segments are drawn, the gap moves one segment forward per step
+        for (index, segment) in points.windows(2).enumerate() {
+            if !(index + 3 - dash_phase).is_multiple_of(3) {
+                gizmos.line_2d(segment[0], segment[1], WHITE.with_alpha(0.6));
+            }
+        }
+    }
+}
+
+fn draw_gap_guide(
+    mut gizmos: Gizmos,
+    player_query: Query<&Transform, LivingBird>,
+    pipe_query: Query<&Transform, With<Pipe>>,
+) {
+    for player_transform in player_query.iter() {
+        let player_position = player_transform.translation.truncate();
+        let Some(gap_center) = nearest_upcoming_pipe(player_position.x, pipe_query.iter()) else {
+            continue;
+        };
+
+        let alignment =
+            ((player_position.y - gap_center.y).abs() / GAP_GUIDE_FADE_DISTANCE).min(1.);
+        gizmos.line_2d(
+            Vec2::new(player_position.x, gap_center.y),
+            gap_center,
+            WHITE.with_alpha(0.1 + 0.5 * alignment),
+        );
+    }
+}
+
+fn reset_first_pipe_seen(mut first_pipe_seen: ResMut<FirstPipeSeen>) {
+    first_pipe_seen.0 = false;
+}
+
+fn spawn_approach_warning(
+    mut commands: Commands,
+    mut first_pipe_seen: ResMut<FirstPipeSeen>,
+    pipe_query: Query<&Transform, Added<Pipe>>,
+) {
+    if first_pipe_seen.0 {
+        return;
+    }
+    let Some(pipe_transform) = pipe_query.iter().next() else {
+        return;
+    };
+
+    first_pipe_seen.0 = true;
+    commands.spawn((
+        ApproachWarning {
+            reacted: false,
+            fade: Timer::from_seconds(APPROACH_WARNING_FADE, TimerMode::Once),
+        },
+        Transform::from_xyz(
+            WINDOW_SIZE.x / 2. - APPROACH_WARNING_MARGIN,
+            pipe_transform.translation.y,
+            0.,
+        ),
+        DespawnOnExit(AppState::InGame),
+    ));
+}
+
+fn react_to_approach_warning(_jump_event: On<JumpEvent>, mut query: Query<&mut ApproachWarning>) {
+    for mut warning in query.iter_mut() {
+        warning.reacted = true;
+    }
+}
+
+// Flashes until the player flaps, then fades out
+fn draw_approach_warning(
+    mut commands: Commands,
+    mut gizmos: Gizmos,
+    time: Res<Time>,
+    mut query: Query<(Entity, &Transform, &mut ApproachWarning)>,
+) {
+    for (entity, transform, mut warning) in query.iter_mut() {
+        let alpha = if warning.reacted {
+            warning.fade.tick(time.delta());
+            if warning.fade.just_finished() {
+                commands.entity(entity).despawn();
+                continue;
+            }
+            1. - warning.fade.fraction()
+        } else {
+            let wave_position = 2. * PI * APPROACH_WARNING_FLASH_FREQUENCY * time.elapsed_secs();
+            0.5 + 0.5 * wave_position.sin()
+        };
+
+        let tip = transform.translation.truncate();
+        gizmos
+            .arrow_2d(
+                tip + Vec2::X * APPROACH_WARNING_MARGIN / 2.,
+                tip - Vec2::X * APPROACH_WARNING_MARGIN / 2.,
+                ORANGE.with_alpha(alpha),
+            )
+            .with_tip_length(APPROACH_WARNING_MARGIN / 3.);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::resources::GravityCurve;
+
+    #[test]
+    fn rescue_flap_only_fires_for_a_fall_that_reaches_the_floor() {
+        // Falling fast just above the floor
+        assert!(rescue_flap_needed(20., -300., 0., GRAVITY, 0.25));
+        // Same fall but too far up to matter yet
+        assert!(!rescue_flap_needed(400., -300., 0., GRAVITY, 0.25));
+        // Still rising, even if the arc will come down later
+        assert!(!rescue_flap_needed(20., 100., 0., GRAVITY, 1.));
+        // A longer lookahead reacts earlier
+        assert!(rescue_flap_needed(200., -300., 0., GRAVITY, 0.5));
+        assert!(!rescue_flap_needed(200., -300., 0., GRAVITY, 0.25));
+    }
+
+    #[test]
+    fn trajectory_follows_the_fall_and_stops_at_the_ground() {
+        let floor = -200.;
+        let config = GameConfig {
+            gravity: GRAVITY,
+            gravity_curve: GravityCurve::Classic,
+            pipe_speed: PIPE_BASE_SPEED,
+            ..default()
+        };
+        let points = predict_trajectory(Vec2::ZERO, 0., 0., &config, floor, 1000.);
+
+        let t = TRAJECTORY_GUIDE_STEP;
+        assert_eq!(points[1].y, 0.5 * GRAVITY * t.powi(2));
+        assert_eq!(points[1].x, PIPE_BASE_SPEED * t);
+
+        let last = *points.last().unwrap();
+        assert!((last.y - floor).abs() < 1e-3);
+        assert!(points.iter().all(|point| point.y >= floor - 1e-3));
+        assert!(last.x < PIPE_BASE_SPEED * TRAJECTORY_GUIDE_DURATION);
+    }
+}
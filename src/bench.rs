@@ -0,0 +1,104 @@
+use std::time::{Duration, Instant};
+
+use bevy::{ecs::entity::Entities, prelude::*, time::TimeUpdateStrategy};
+
+use crate::{
+    assist::nearest_upcoming_pipe,
+    components::{LivingBird, Pipe, Velocity},
+    constants::*,
+    demo::autopilot_should_flap,
+    events::{JumpEvent, JumpSource},
+    resources::{BenchConfig, BenchStats, SeedOverride},
+    AppState,
+};
+
+pub fn bench_plugin(app: &mut App) {
+    app.init_resource::<BenchStats>()
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f64(
+            1. / BENCH_TICK_RATE,
+        )))
+        .insert_resource(SeedOverride(Some(BENCH_SEED)))
+        .add_systems(OnEnter(AppState::MainMenu), start_bench_run)
+        .add_systems(
+            Update,
+            (
+                start_bench_run.run_if(in_state(AppState::GameOver)),
+                skip_bench_death_replay.run_if(in_state(AppState::DeathReplay)),
+                launch_bench_bird.run_if(in_state(AppState::GameStart)),
+                bench_autopilot.run_if(in_state(AppState::InGame)),
+            ),
+        )
+        .add_systems(Last, (record_bench_frame, finish_bench).chain());
+}
+
+fn start_bench_run(mut next_state: ResMut<NextState<AppState>>, mut stats: ResMut<BenchStats>) {
+    stats.runs += 1;
+    next_state.set(AppState::GameStart);
+}
+
+// The slow motion replay runs on real time and would only pad the frame stats
+fn skip_bench_death_replay(mut next_state: ResMut<NextState<AppState>>) {
+    next_state.set(AppState::GameOver);
+}
+
+fn launch_bench_bird(mut next_state: ResMut<NextState<AppState>>, mut commands: Commands) {
+    commands.trigger(JumpEvent {
+        source: JumpSource::Autopilot,
+    });
+    next_state.set(AppState::InGame);
+}
+
+fn bench_autopilot(
+    player_query: Query<(&Transform, &Velocity), LivingBird>,
+    pipe_query: Query<&Transform, With<Pipe>>,
+    mut commands: Commands,
+) {
+    let wants_flap = player_query.iter().any(|(transform, velocity)| {
+        let position = transform.translation.truncate();
+        let gap_center = nearest_upcoming_pipe(position.x, pipe_query.iter())
+            .unwrap_or(Vec2::new(position.x, 0.));
+        autopilot_should_flap(position, velocity.0, gap_center)
+    });
+
+    if wants_flap {
+        commands.trigger(JumpEvent {
+            source: JumpSource::Autopilot,
+        });
+    }
+}
+
+fn record_bench_frame(
+    entities: &Entities,
+    mut stats: ResMut<BenchStats>,
+    mut frame_start: Local<Option<Instant>>,
+) {
+    let now = Instant::now();
+    if let Some(start) = *frame_start {
+        stats.frames += 1;
+        stats.total_frame_time += now - start;
+    }
+    *frame_start = Some(now);
+    stats.peak_entities = stats.peak_entities.max(entities.len());
+}
+
+fn finish_bench(
+    time: Res<Time<Virtual>>,
+    config: Res<BenchConfig>,
+    stats: Res<BenchStats>,
+    mut app_exit: MessageWriter<AppExit>,
+) {
+    if time.elapsed_secs() < config.duration {
+        return;
+    }
+
+    let average_frame_time = stats.total_frame_time / stats.frames.max(1);
+    info!(
+        "Bench finished: {:.0}s simulated, {} frames, {} runs, average frame {:?}, peak entities {}",
+        time.elapsed_secs(),
+        stats.frames,
+        stats.runs,
+        average_frame_time,
+        stats.peak_entities
+    );
+    app_exit.write(AppExit::Success);
+}
@@ -0,0 +1,147 @@
+use bevy::{color::palettes::css::GOLD, prelude::*};
+
+use crate::{
+    components::{BestRunMarker, Collider, NewBestBanner, PointGate},
+    constants::*,
+    events::IncrementScoreEvent,
+    persistence,
+    resources::{BestRunProgress, GameMode, PlayerStats, RunFlags},
+    tutorial::PLAYER_STATS_FILE,
+    AppState,
+};
+
+pub fn best_run_plugin(app: &mut App) {
+    app.init_resource::<BestRunProgress>()
+        .add_systems(OnEnter(AppState::GameStart), reset_best_run_progress)
+        .add_systems(OnEnter(AppState::GameOver), record_best_run)
+        .add_systems(Update, fade_new_best_banner)
+        .add_observer(mark_best_run_gate)
+        .add_observer(count_passed_gate);
+}
+
+// Without a previous best there is nothing to beat, the first run never gets a banner
+pub fn beats_best_run(gates_passed: u32, best_run_gates: u32) -> bool {
+    best_run_gates > 0 && gates_passed > best_run_gates
+}
+
+fn reset_best_run_progress(mut progress: ResMut<BestRunProgress>) {
+    *progress = BestRunProgress::default();
+}
+
+// Counts gates rather than points, golden pipes and handicap starts do not move the line
+fn mark_best_run_gate(
+    add: On<Add, PointGate>,
+    state: Res<State<AppState>>,
+    stats: Res<PlayerStats>,
+    mut progress: ResMut<BestRunProgress>,
+    gate_query: Query<&Collider>,
+    mut commands: Commands,
+) {
+    // The menu demo spawns pipes too
+    if *state.get() != AppState::InGame {
+        return;
+    }
+
+    let index = progress.gates_spawned;
+    progress.gates_spawned += 1;
+    if stats.best_run_gates == 0 || index != stats.best_run_gates {
+        return;
+    }
+
+    let Ok(gate) = gate_query.get(add.entity) else {
+        return;
+    };
+    commands.entity(add.entity).with_children(|parent| {
+        parent.spawn((
+            BestRunMarker,
+            Sprite::from_color(
+                Color::WHITE.with_alpha(BEST_RUN_MARKER_ALPHA),
+                Vec2::new(BEST_RUN_MARKER_WIDTH, gate.size.y),
+            ),
+            Transform::from_xyz(0., 0., 0.1),
+        ));
+    });
+}
+
+fn count_passed_gate(
+    _increment_event: On<IncrementScoreEvent>,
+    stats: Res<PlayerStats>,
+    mut progress: ResMut<BestRunProgress>,
+    mut commands: Commands,
+) {
+    progress.gates_passed += 1;
+    if progress.announced || !beats_best_run(progress.gates_passed, stats.best_run_gates) {
+        return;
+    }
+    progress.announced = true;
+
+    commands.spawn((
+        NewBestBanner(Timer::from_seconds(
+            NEW_BEST_BANNER_DURATION,
+            TimerMode::Once,
+        )),
+        Text::new("New best!"),
+        TextFont {
+            font_size: 48.0,
+            ..default()
+        },
+        TextColor(GOLD.into()),
+        Node {
+            position_type: PositionType::Absolute,
+            width: percent(100),
+            top: percent(25),
+            justify_content: JustifyContent::Center,
+            ..default()
+        },
+        TextLayout {
+            justify: Justify::Center,
+            ..default()
+        },
+        Pickable::IGNORE,
+        DespawnOnExit(AppState::InGame),
+    ));
+}
+
+fn fade_new_best_banner(
+    time: Res<Time<Real>>,
+    mut commands: Commands,
+    mut query: Query<(Entity, &mut NewBestBanner, &mut TextColor)>,
+) {
+    for (entity, mut banner, mut color) in query.iter_mut() {
+        if banner.0.tick(time.delta()).just_finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        color.0.set_alpha(1. - banner.0.fraction());
+    }
+}
+
+// Same rules as the high score, score attack runs end on the clock and are not comparable
+fn record_best_run(
+    progress: Res<BestRunProgress>,
+    run_flags: Res<RunFlags>,
+    mode: Res<GameMode>,
+    mut stats: ResMut<PlayerStats>,
+) {
+    if !run_flags.is_ranked() || *mode == GameMode::ScoreAttack {
+        return;
+    }
+    if progress.gates_passed <= stats.best_run_gates {
+        return;
+    }
+
+    stats.best_run_gates = progress.gates_passed;
+    persistence::save(PLAYER_STATS_FILE, &*stats);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_passing_a_previous_best_counts() {
+        assert!(!beats_best_run(5, 0));
+        assert!(!beats_best_run(3, 3));
+        assert!(beats_best_run(4, 3));
+    }
+}
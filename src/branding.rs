@@ -0,0 +1,109 @@
+use bevy::{
+    asset::LoadState, ecs::system::NonSendMarker, prelude::*, window::PrimaryWindow,
+    winit::WINIT_WINDOWS,
+};
+use serde::Deserialize;
+use winit::window::Icon;
+
+const BUILT_IN_BRANDING: &str = include_str!("../assets/branding.ron");
+
+#[derive(Resource, Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct Branding {
+    pub title: String,
+    pub icon: String,
+}
+
+impl Default for Branding {
+    fn default() -> Self {
+        Self {
+            title: "Flappy Bird".into(),
+            icon: "icon.png".into(),
+        }
+    }
+}
+
+impl Branding {
+    // Parsed before the app exists, since the window title is needed to build the WindowPlugin
+    pub fn built_in() -> Self {
+        ron::from_str(BUILT_IN_BRANDING).unwrap_or_else(|error| {
+            warn!("Failed to parse the built-in branding: {error}");
+            Self::default()
+        })
+    }
+}
+
+#[derive(Resource)]
+struct PendingWindowIcon(Handle<Image>);
+
+pub fn branding_plugin(app: &mut App) {
+    app.add_systems(Startup, load_window_icon).add_systems(
+        Update,
+        apply_window_icon.run_if(resource_exists::<PendingWindowIcon>),
+    );
+}
+
+fn load_window_icon(
+    mut commands: Commands,
+    branding: Res<Branding>,
+    asset_server: Res<AssetServer>,
+) {
+    commands.insert_resource(PendingWindowIcon(asset_server.load(&branding.icon)));
+}
+
+// The winit window only exists once the event loop has resumed, so this retries every frame
+// until the icon has loaded and the window is there to receive it
+fn apply_window_icon(
+    mut commands: Commands,
+    pending: Res<PendingWindowIcon>,
+    asset_server: Res<AssetServer>,
+    images: Res<Assets<Image>>,
+    branding: Res<Branding>,
+    window_query: Query<Entity, With<PrimaryWindow>>,
+    _main_thread: NonSendMarker,
+) {
+    if let LoadState::Failed(error) = asset_server.load_state(&pending.0) {
+        warn!(
+            "Window icon {} not loaded, keeping the default: {error}",
+            branding.icon
+        );
+        commands.remove_resource::<PendingWindowIcon>();
+        return;
+    }
+    let Some(image) = images.get(&pending.0) else {
+        return;
+    };
+    let Ok(window) = window_query.single() else {
+        commands.remove_resource::<PendingWindowIcon>();
+        return;
+    };
+
+    let icon = image
+        .clone()
+        .try_into_dynamic()
+        .map_err(|error| error.to_string())
+        .and_then(|dynamic| {
+            let rgba = dynamic.into_rgba8();
+            let (width, height) = rgba.dimensions();
+            Icon::from_rgba(rgba.into_raw(), width, height).map_err(|error| error.to_string())
+        });
+    let icon = match icon {
+        Ok(icon) => icon,
+        Err(error) => {
+            warn!("Window icon {} is not usable: {error}", branding.icon);
+            commands.remove_resource::<PendingWindowIcon>();
+            return;
+        }
+    };
+
+    let applied = WINIT_WINDOWS.with_borrow(|windows| {
+        let Some(winit_window) = windows.get_window(window) else {
+            return false;
+        };
+        winit_window.set_window_icon(Some(icon));
+        true
+    });
+    if applied {
+        commands.remove_resource::<PendingWindowIcon>();
+    }
+}
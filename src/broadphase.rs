@@ -0,0 +1,126 @@
+use bevy::math::{
+    bounding::{Aabb2d, IntersectsVolume},
+    Vec2,
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+// Extra reach on the lower search bound so float rounding in the box widths never drops a
+// candidate; the exact intersection test still runs on everything in the window
+const SEARCH_SLACK: f32 = 1.;
+
+// Collider boxes sorted by their left edge. Built once per frame, then every bird
+// binary-searches the window of boxes that can reach it instead of scanning all of them
+pub struct Broadphase {
+    entries: Vec<(f32, usize)>,
+    widest: f32,
+}
+
+impl Broadphase {
+    pub fn new(boxes: &[Aabb2d]) -> Self {
+        let mut entries: Vec<(f32, usize)> = boxes
+            .iter()
+            .enumerate()
+            .map(|(index, aabb)| (aabb.min.x, index))
+            .collect();
+        entries.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        let widest = boxes
+            .iter()
+            .map(|aabb| aabb.max.x - aabb.min.x)
+            .fold(0., f32::max);
+
+        Self { entries, widest }
+    }
+
+    // Indices into `boxes` in their original order, so callers see the same hits in the same
+    // order as the naive scan
+    pub fn overlapping(&self, boxes: &[Aabb2d], query: &Aabb2d) -> Vec<usize> {
+        let lowest_min_x = query.min.x - self.widest - SEARCH_SLACK;
+        let start = self
+            .entries
+            .partition_point(|&(min_x, _)| min_x < lowest_min_x);
+        let end = self
+            .entries
+            .partition_point(|&(min_x, _)| min_x <= query.max.x);
+
+        let mut hits: Vec<usize> = self.entries[start..end.max(start)]
+            .iter()
+            .map(|&(_, index)| index)
+            .filter(|&index| query.intersects(&boxes[index]))
+            .collect();
+        hits.sort_unstable();
+        hits
+    }
+}
+
+pub fn overlapping_naive(boxes: &[Aabb2d], query: &Aabb2d) -> Vec<usize> {
+    boxes
+        .iter()
+        .enumerate()
+        .filter(|(_, aabb)| query.intersects(*aabb))
+        .map(|(index, _)| index)
+        .collect()
+}
+
+// A row of pipe pairs 150 units apart, each made of a top and a bottom box around a
+// random gap, like the colliders `detect_collision` sees in a long run
+pub fn pipe_field(count: usize, seed: u64) -> Vec<Aabb2d> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut boxes = Vec::with_capacity(count * 2);
+    for pipe_index in 0..count {
+        let x = pipe_index as f32 * 150. + rng.gen_range(-20.0..20.);
+        let gap_center = rng.gen_range(-150.0..150.);
+        let gap = rng.gen_range(100.0..200.);
+        let half_size = Vec2::new(rng.gen_range(30.0..50.), 200.);
+        boxes.push(Aabb2d::new(
+            Vec2::new(x, gap_center + gap / 2. + half_size.y),
+            half_size,
+        ));
+        boxes.push(Aabb2d::new(
+            Vec2::new(x, gap_center - gap / 2. - half_size.y),
+            half_size,
+        ));
+    }
+    boxes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Differential test: the broadphase must report exactly what the naive scan reports,
+    // including boxes that only touch the bird on an edge
+    #[test]
+    fn broadphase_matches_the_naive_scan() {
+        let mut rng = StdRng::seed_from_u64(7);
+        for count in [0, 1, 2, 50, 200, 500] {
+            let boxes = pipe_field(count, count as u64);
+            let broadphase = Broadphase::new(&boxes);
+
+            let far_right = count as f32 * 150. + 100.;
+            for _ in 0..500 {
+                let center = Vec2::new(
+                    rng.gen_range(-100.0..far_right),
+                    rng.gen_range(-400.0..400.),
+                );
+                let bird = Aabb2d::new(center, Vec2::new(17., 12.));
+                assert_eq!(
+                    broadphase.overlapping(&boxes, &bird),
+                    overlapping_naive(&boxes, &bird),
+                    "{count} pipes, bird at {center}"
+                );
+            }
+
+            for aabb in &boxes {
+                let touching = Aabb2d {
+                    min: Vec2::new(aabb.max.x, aabb.min.y),
+                    max: Vec2::new(aabb.max.x + 10., aabb.min.y + 10.),
+                };
+                assert_eq!(
+                    broadphase.overlapping(&boxes, &touching),
+                    overlapping_naive(&boxes, &touching)
+                );
+            }
+        }
+    }
+}
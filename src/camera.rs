@@ -0,0 +1,61 @@
+use bevy::{prelude::*, window::PrimaryWindow};
+
+use crate::{
+    components::LivingBird,
+    constants::*,
+    pipes::world_top,
+    resources::{GameConfig, PlayArea},
+};
+
+pub fn camera_plugin(app: &mut App) {
+    app.init_resource::<PlayArea>()
+        .add_systems(Update, (track_play_area, follow_camera));
+}
+
+// The lowest camera position keeps the ground at the window bottom, the highest one the world
+// top at the window top. In a window-sized world both are 0, so the camera stays fixed there.
+pub fn camera_target(bird_y: Option<f32>, world_height: f32) -> f32 {
+    let max_y = (world_top(world_height) - WINDOW_SIZE.y / 2.).max(0.);
+    bird_y.unwrap_or(0.).clamp(0., max_y)
+}
+
+// The camera draws one world unit per logical pixel, so the window size is the visible size
+fn track_play_area(
+    window: Single<&Window, (With<PrimaryWindow>, Changed<Window>)>,
+    mut play_area: ResMut<PlayArea>,
+) {
+    let visible_size = Vec2::new(window.width(), window.height());
+    if play_area.visible_width != visible_size.x || play_area.visible_height != visible_size.y {
+        play_area.visible_width = visible_size.x;
+        play_area.visible_height = visible_size.y;
+    }
+}
+
+// UI nodes are laid out in screen space, so the HUD stays put while the world scrolls
+fn follow_camera(
+    time: Res<Time>,
+    config: Res<GameConfig>,
+    player_query: Query<&Transform, (LivingBird, Without<Camera2d>)>,
+    mut camera_query: Query<&mut Transform, With<Camera2d>>,
+) {
+    let living_birds = player_query.iter().count();
+    let bird_y = (living_birds > 0).then(|| {
+        player_query
+            .iter()
+            .map(|transform| transform.translation.y)
+            .sum::<f32>()
+            / living_birds as f32
+    });
+    let target = camera_target(bird_y, config.world_height);
+
+    // Same exponential approach as the score counter, so the frame rate does not change the lag
+    let blend = 1. - (-CAMERA_FOLLOW_SPEED * time.delta_secs()).exp();
+    for mut transform in camera_query.iter_mut() {
+        let remaining = target - transform.translation.y;
+        if remaining.abs() < 0.01 {
+            transform.translation.y = target;
+        } else {
+            transform.translation.y += remaining * blend;
+        }
+    }
+}
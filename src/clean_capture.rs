@@ -0,0 +1,68 @@
+use bevy::prelude::*;
+
+use crate::{components::CaptureHidden, resources::CleanCapture};
+
+pub fn clean_capture_plugin(app: &mut App) {
+    app.init_resource::<CleanCapture>().add_systems(
+        Update,
+        (
+            toggle_clean_capture,
+            hide_ui_roots.run_if(clean_capture_enabled),
+            restore_ui_roots
+                .run_if(resource_changed::<CleanCapture>.and(not(clean_capture_enabled))),
+            apply_gizmo_visibility.run_if(resource_changed::<CleanCapture>),
+        )
+            .chain(),
+    );
+}
+
+fn clean_capture_enabled(clean_capture: Res<CleanCapture>) -> bool {
+    clean_capture.0
+}
+
+fn toggle_clean_capture(
+    key_input: Res<ButtonInput<KeyCode>>,
+    mut clean_capture: ResMut<CleanCapture>,
+) {
+    if key_input.just_pressed(KeyCode::F5) {
+        clean_capture.0 = !clean_capture.0;
+    }
+}
+
+// Only UI roots are hidden, their children inherit it. Runs every frame so overlays spawned
+// while capturing are hidden as well
+#[allow(
+    clippy::type_complexity,
+    reason = "the filter spells out what a visible UI root is"
+)]
+fn hide_ui_roots(
+    mut commands: Commands,
+    mut query: Query<
+        (Entity, &mut Visibility),
+        (With<Node>, Without<ChildOf>, Without<CaptureHidden>),
+    >,
+) {
+    for (entity, mut visibility) in query.iter_mut() {
+        commands.entity(entity).insert(CaptureHidden(*visibility));
+        *visibility = Visibility::Hidden;
+    }
+}
+
+fn restore_ui_roots(
+    mut commands: Commands,
+    mut query: Query<(Entity, &mut Visibility, &CaptureHidden)>,
+) {
+    for (entity, mut visibility, hidden) in query.iter_mut() {
+        *visibility = hidden.0;
+        commands.entity(entity).remove::<CaptureHidden>();
+    }
+}
+
+// Debug and assist gizmos are overlays too
+fn apply_gizmo_visibility(
+    clean_capture: Res<CleanCapture>,
+    mut config_store: ResMut<GizmoConfigStore>,
+) {
+    let (config, _) = config_store.config_mut::<DefaultGizmoConfigGroup>();
+    config.enabled = !clean_capture.0;
+}
@@ -0,0 +1,23 @@
+use bevy::prelude::*;
+
+use crate::{
+    components::{Particle, Pipe, Player},
+    AppState,
+};
+
+pub fn cleanup_plugin(app: &mut App) {
+    app.add_systems(
+        OnExit(AppState::GameOver),
+        (
+            despawn_tagged::<Player>,
+            despawn_tagged::<Pipe>,
+            despawn_tagged::<Particle>,
+        ),
+    );
+}
+
+pub fn despawn_tagged<T: Component>(mut commands: Commands, query: Query<Entity, With<T>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn();
+    }
+}
@@ -0,0 +1,23 @@
+#[cfg(not(target_arch = "wasm32"))]
+pub fn get_text() -> Result<String, String> {
+    arboard::Clipboard::new()
+        .and_then(|mut clipboard| clipboard.get_text())
+        .map_err(|error| error.to_string())
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn set_text(text: String) -> Result<(), String> {
+    arboard::Clipboard::new()
+        .and_then(|mut clipboard| clipboard.set_text(text))
+        .map_err(|error| error.to_string())
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn get_text() -> Result<String, String> {
+    Err("the clipboard is not available in the browser".into())
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn set_text(_text: String) -> Result<(), String> {
+    Err("the clipboard is not available in the browser".into())
+}
@@ -0,0 +1,167 @@
+use bevy::{
+    math::bounding::{Aabb2d, BoundingVolume},
+    prelude::*,
+};
+
+use crate::{
+    components::{ColliderType, Dead, Velocity},
+    constants::DEATH_RECOIL_SPEED,
+    events::{GroundCollisionEvent, PipeCollisionEvent},
+    resources::GameConfig,
+};
+
+// The face of the obstacle the bird ran into
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CollisionSide {
+    Top,
+    Bottom,
+    Left,
+    Right,
+}
+
+pub fn collision_plugin(app: &mut App) {
+    app.add_observer(recoil_from_pipe)
+        .add_observer(recoil_from_ground);
+}
+
+// The axis with the smaller overlap is the one the boxes met on
+pub fn collision_side(bird: &Aabb2d, obstacle: &Aabb2d) -> CollisionSide {
+    let overlap = bird.max.min(obstacle.max) - bird.min.max(obstacle.min);
+    let offset = bird.center() - obstacle.center();
+
+    if overlap.x < overlap.y {
+        if offset.x < 0. {
+            CollisionSide::Left
+        } else {
+            CollisionSide::Right
+        }
+    } else if offset.y > 0. {
+        CollisionSide::Top
+    } else {
+        CollisionSide::Bottom
+    }
+}
+
+// `size` is the bird's drawn size. Gates and pipes each see their own share of it, so scoring
+// can be generous without making crashes any less fair
+pub fn player_hitbox(kind: &ColliderType, center: Vec2, size: Vec2, config: &GameConfig) -> Aabb2d {
+    let scale = match kind {
+        ColliderType::Good => config.gate_hitbox_scale,
+        ColliderType::Bad => config.hitbox_scale,
+    };
+    Aabb2d::new(center, size * scale / 2.)
+}
+
+// The gate only moves left, so the box it swept since the last check reaches back to the
+// right by however far it travelled. A bird it jumped over still overlaps this box
+pub fn swept_gate_box(gate: Aabb2d, travel: f32) -> Aabb2d {
+    Aabb2d {
+        min: gate.min,
+        max: Vec2::new(gate.max.x + travel.max(0.), gate.max.y),
+    }
+}
+
+pub fn recoil_direction(side: CollisionSide) -> Vec2 {
+    match side {
+        CollisionSide::Top => Vec2::Y,
+        CollisionSide::Bottom => Vec2::NEG_Y,
+        CollisionSide::Left => Vec2::NEG_X,
+        CollisionSide::Right => Vec2::X,
+    }
+}
+
+fn recoil_from_pipe(
+    collision_event: On<PipeCollisionEvent>,
+    query: Query<&mut Velocity, With<Dead>>,
+) {
+    recoil(collision_event.player, collision_event.side, query);
+}
+
+fn recoil_from_ground(
+    collision_event: On<GroundCollisionEvent>,
+    query: Query<&mut Velocity, With<Dead>>,
+) {
+    recoil(collision_event.player, collision_event.side, query);
+}
+
+// Birds only move vertically, so a side hit has no recoil yet
+fn recoil(player: Entity, side: CollisionSide, mut query: Query<&mut Velocity, With<Dead>>) {
+    let Ok(mut velocity) = query.get_mut(player) else {
+        return;
+    };
+    let direction = recoil_direction(side);
+    if direction.y != 0. {
+        velocity.0 = direction.y * DEATH_RECOIL_SPEED;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::math::bounding::IntersectsVolume;
+
+    use super::*;
+
+    fn bird_at(center: Vec2) -> Aabb2d {
+        Aabb2d::new(center, Vec2::splat(10.))
+    }
+
+    // A 200ms hitch at turbo speed moves the pipes further than the gate and bird are wide
+    #[test]
+    fn a_gate_that_skipped_past_the_bird_still_scores() {
+        let bird = Aabb2d::new(Vec2::ZERO, Vec2::new(27.2, 19.2));
+        let travel = 800. * 0.2;
+        let gate = Aabb2d::new(Vec2::new(-80., 0.), Vec2::new(5., 100.));
+
+        assert!(!bird.intersects(&gate));
+        assert!(bird.intersects(&swept_gate_box(gate, travel)));
+
+        let not_yet_reached = Aabb2d::new(Vec2::new(60., 0.), Vec2::new(5., 100.));
+        assert!(!bird.intersects(&swept_gate_box(not_yet_reached, travel)));
+    }
+
+    // A bird reaching 31 units past its center, between the pipe box at 27.2 and the full
+    // sprite at 34
+    #[test]
+    fn a_borderline_gate_scores_but_a_borderline_pipe_does_not_kill() {
+        let config = GameConfig::default();
+        let size = Vec2::new(68., 48.);
+        let edge = Aabb2d::new(Vec2::new(31. + 5., 0.), Vec2::new(5., 100.));
+
+        let gate_box = player_hitbox(&ColliderType::Good, Vec2::ZERO, size, &config);
+        let pipe_box = player_hitbox(&ColliderType::Bad, Vec2::ZERO, size, &config);
+        assert!(gate_box.intersects(&edge));
+        assert!(!pipe_box.intersects(&edge));
+
+        // Further in, the pipe hitbox is reached as well
+        let deeper = Aabb2d::new(Vec2::new(26. + 5., 0.), Vec2::new(5., 100.));
+        assert!(pipe_box.intersects(&deeper));
+    }
+
+    #[test]
+    fn every_side_maps_to_its_recoil() {
+        let obstacle = Aabb2d::new(Vec2::ZERO, Vec2::splat(50.));
+        let cases = [
+            (Vec2::new(0., 58.), CollisionSide::Top, Vec2::Y),
+            (Vec2::new(0., -58.), CollisionSide::Bottom, Vec2::NEG_Y),
+            (Vec2::new(-58., 0.), CollisionSide::Left, Vec2::NEG_X),
+            (Vec2::new(58., 0.), CollisionSide::Right, Vec2::X),
+        ];
+
+        for (bird_center, side, recoil) in cases {
+            assert_eq!(collision_side(&bird_at(bird_center), &obstacle), side);
+            assert_eq!(recoil_direction(side), recoil);
+        }
+    }
+
+    // At a corner the shallower overlap decides between a side and a top hit
+    #[test]
+    fn the_shallower_overlap_decides_the_side() {
+        let obstacle = Aabb2d::new(Vec2::ZERO, Vec2::splat(50.));
+
+        let side = collision_side(&bird_at(Vec2::new(-58., 52.)), &obstacle);
+        assert_eq!(side, CollisionSide::Left);
+
+        let side = collision_side(&bird_at(Vec2::new(-52., 58.)), &obstacle);
+        assert_eq!(side, CollisionSide::Top);
+    }
+}
@@ -1,7 +1,12 @@
 use bevy::{
-    ecs::component::Component,
-    math::Vec2,
-    prelude::{Deref, DerefMut},
+    asset::Handle,
+    ecs::{
+        component::Component,
+        query::{With, Without},
+    },
+    image::Image,
+    math::{Vec2, Vec3},
+    prelude::{Deref, DerefMut, Visibility},
     time::Timer,
 };
 
@@ -14,18 +19,113 @@ pub enum ColliderType {
 #[derive(Component)]
 pub struct Player;
 
+#[derive(Component)]
+pub struct Dead;
+
+// Query filter for the birds still flying
+pub type LivingBird = (With<Player>, Without<Dead>);
+
+// A dead bird that fell below the screen and is being despawned
+#[derive(Component)]
+pub struct Fallen;
+
+#[derive(Component)]
+pub struct Invincible(pub Timer);
+
+// Physics positions before and after the last fixed tick, see `interpolation`
+#[derive(Component, Default)]
+pub struct FixedInterpolation {
+    pub previous: Option<Vec3>,
+    pub current: Option<Vec3>,
+}
+
+// Absorbs the next lethal hit, see `shield::break_shield`
+#[derive(Component)]
+pub struct Shield;
+
+#[derive(Component)]
+pub struct ShieldPickup;
+
+// Drawn around a bird that carries a Shield
+#[derive(Component)]
+pub struct ShieldBubble;
+
+#[derive(Component)]
+pub struct ShieldFlash(pub Timer);
+
+// Collisions are ignored for the first InGame frame
+#[derive(Component)]
+pub struct FirstFrameGuard;
+
+#[derive(Component)]
+pub struct Lives(pub u32);
+
+// The x a bird is pulled back to, flock birds each keep their own lane behind the leader
+#[derive(Component)]
+pub struct Lane(pub f32);
+
+#[derive(Component)]
+pub struct IdleBob {
+    pub start: Vec2,
+    pub ease_from: f32,
+    pub ease: Timer,
+}
+
 #[derive(Component)]
 pub struct Pipe;
 
 #[derive(Component)]
 pub struct PointGate;
 
+#[derive(Component)]
+pub struct MissedGate;
+
+// Where the point gate was at the previous collision check
+#[derive(Component, Default)]
+pub struct GateTrail(pub Option<f32>);
+
+#[derive(Component)]
+pub struct GateFlash(pub Timer);
+
+#[derive(Component)]
+pub struct AssetStatusPanel;
+
+// One line of the asset status panel, the index into the listed assets
+#[derive(Component)]
+pub struct AssetStatusLine(pub usize);
+
+#[derive(Component)]
+pub struct PipeCap;
+
+// A freshly spawned pipe fading and growing into place
+#[derive(Component)]
+pub struct SpawnAnim(pub Timer);
+
+#[derive(Component)]
+pub struct GoldenPipe;
+
 #[derive(Component)]
 pub struct Velocity(pub f32);
 
+// Seconds since the bird last flapped, drives the GravityCurve
+#[derive(Component, Default)]
+pub struct Airtime(pub f32);
+
 #[derive(Component)]
 pub struct Ground;
 
+// A scrolling strip of scenery, `index` points into the ParallaxConfig layers
+#[derive(Component)]
+pub struct ParallaxLayer {
+    pub index: usize,
+    pub image: Handle<Image>,
+    // How far the row has scrolled into its first tile
+    pub offset: f32,
+}
+
+#[derive(Component)]
+pub struct ParallaxTile;
+
 #[derive(Component)]
 pub struct Collider {
     pub kind: ColliderType,
@@ -43,3 +143,263 @@ pub struct AnimationIndices {
 
 #[derive(Component, Deref, DerefMut)]
 pub struct AnimationTimer(pub Timer);
+
+#[derive(Component)]
+pub struct Ghost;
+
+#[derive(Component)]
+pub struct DemoBird;
+
+#[derive(Component)]
+pub struct DemoPipe;
+
+#[derive(Component)]
+pub struct GhostPlayback {
+    pub jumps: Vec<u32>,
+    pub next_jump: usize,
+}
+
+#[derive(Component)]
+pub struct ReplayMessageText;
+
+#[derive(Component)]
+pub struct DeathReplayText;
+
+#[derive(Component)]
+pub struct TapPrompt;
+
+#[derive(Component)]
+pub struct QuitPrompt;
+
+// A menu area that follows the mouse wheel
+#[derive(Component)]
+pub struct MenuScroll;
+
+// Full-screen gradient that darkens the edges of the play area
+#[derive(Component)]
+pub struct Vignette;
+
+// Dims the game behind the pause dialog, outlives the pause until it has faded out
+#[derive(Component, Default)]
+pub struct PauseDim {
+    pub fading_out: bool,
+}
+
+// A faint line through the gate the best run died at
+#[derive(Component)]
+pub struct BestRunMarker;
+
+#[derive(Component)]
+pub struct NewBestBanner(pub Timer);
+
+#[derive(Component)]
+pub struct PerfectBanner;
+
+#[derive(Component)]
+pub struct ApproachWarning {
+    pub reacted: bool,
+    pub fade: Timer,
+}
+
+#[derive(Component)]
+pub struct HudRoot;
+
+#[derive(Component, Clone, Copy)]
+pub enum MenuAction {
+    Play,
+    PlayCustom,
+    PlayFlock,
+    PlayAdaptive,
+    PlayPractice,
+    PlayPattern,
+    PlayScrolling,
+    PlayScoreAttack,
+    PlayScoreDecay,
+    CyclePattern,
+    Settings,
+    Custom,
+    Back,
+    Quit,
+    Resume,
+    Retry,
+    RetrySame,
+    ToggleInstantQuit,
+    DecreaseStartingScore,
+    IncreaseStartingScore,
+    DecreaseMinSavedScore,
+    IncreaseMinSavedScore,
+    ToggleAssistGuide,
+    ToggleTrajectoryGuide,
+    CycleAssistLevel,
+    DecreaseTutorialGames,
+    IncreaseTutorialGames,
+    DecreaseRescueLookahead,
+    IncreaseRescueLookahead,
+    ToggleReducedMotion,
+    ToggleAutoFlap,
+    DecreaseStartGrace,
+    IncreaseStartGrace,
+    DecreaseRetryCooldown,
+    IncreaseRetryCooldown,
+    ToggleAutoRestart,
+    DecreaseAutoRestartDelay,
+    IncreaseAutoRestartDelay,
+    DecreaseBirdScale,
+    IncreaseBirdScale,
+    CycleFeel,
+    CycleGravityCurve,
+    CycleJumpMode,
+    ToggleGlide,
+    CyclePerfectRun,
+    ToggleSmoothGaps,
+    ToggleShieldPickups,
+    CycleFlapButton,
+    DecreaseTurboSpeed,
+    IncreaseTurboSpeed,
+    DecreaseRumbleStrength,
+    IncreaseRumbleStrength,
+    DecreaseGamepadDeadzone,
+    IncreaseGamepadDeadzone,
+    DecreaseMusicVolume,
+    IncreaseMusicVolume,
+    ToggleQuietHours,
+    DecreaseQuietHoursStart,
+    IncreaseQuietHoursStart,
+    DecreaseQuietHoursEnd,
+    IncreaseQuietHoursEnd,
+    DecreaseLives,
+    IncreaseLives,
+    ToggleControllerPause,
+    ToggleSeedDisplay,
+    CycleGapDistribution,
+    CycleHudPosition,
+    CycleTheme,
+    TogglePipeDepthTint,
+    ToggleVignette,
+    DecreaseVignetteStrength,
+    IncreaseVignetteStrength,
+    CycleMonitor,
+    TogglePixelSnap,
+    CycleAntialiasing,
+    ToggleRunLog,
+    OpenRunLog,
+    DecreaseHudScale,
+    IncreaseHudScale,
+    DecreasePipeSpacing,
+    IncreasePipeSpacing,
+    DecreaseFlockSize,
+    IncreaseFlockSize,
+    DecreaseScoreDecayRate,
+    IncreaseScoreDecayRate,
+    DecreasePracticeStartPipe,
+    IncreasePracticeStartPipe,
+}
+
+#[derive(Component)]
+pub enum SettingLabel {
+    HudPosition,
+    HudScale,
+    InstantQuit,
+    StartingScore,
+    MinSavedScore,
+    AssistGuide,
+    TrajectoryGuide,
+    AssistLevel,
+    TutorialGames,
+    RescueLookahead,
+    ReducedMotion,
+    Theme,
+    PipeDepthTint,
+    Vignette,
+    VignetteStrength,
+    Monitor,
+    PixelSnap,
+    Antialiasing,
+    RunLog,
+    AutoFlap,
+    StartGrace,
+    RetryCooldown,
+    AutoRestart,
+    AutoRestartDelay,
+    BirdScale,
+    Feel,
+    GravityCurve,
+    JumpMode,
+    Glide,
+    PerfectRun,
+    SmoothGaps,
+    ShieldPickups,
+    FlapButton,
+    RumbleStrength,
+    GamepadDeadzone,
+    MusicVolume,
+    QuietHours,
+    QuietHoursStart,
+    QuietHoursEnd,
+    Lives,
+    ControllerPause,
+    SeedDisplay,
+    GapDistribution,
+    TurboSpeed,
+    PipeSpacing,
+    FlockSize,
+    ScoreDecayRate,
+    PracticeStartPipe,
+    Pattern,
+}
+
+#[derive(Component)]
+pub struct HudPanel;
+
+#[derive(Component)]
+pub struct ScoreFlash(pub Timer);
+
+#[derive(Component)]
+pub struct HudFontSize(pub f32);
+
+// HUD stats driven by HudState, shown or hidden per mode by the HudLayout
+#[derive(Component, Clone, Copy, PartialEq, Eq)]
+pub enum HudElement {
+    Flock,
+    Lives,
+    Clock,
+    DecayBar,
+    Shield,
+}
+
+#[derive(Component)]
+pub struct DecayBarFill;
+
+#[derive(Component)]
+pub struct ModeText;
+
+#[derive(Component)]
+pub struct CarefulPrompt(pub Timer);
+
+#[derive(Component)]
+pub struct StepModeText;
+
+#[derive(Component)]
+pub struct SeedText;
+
+#[derive(Component)]
+pub struct QuietHoursText;
+
+#[derive(Component)]
+pub struct KioskText;
+
+// A UI root hidden for a clean capture, with the visibility it had before
+#[derive(Component)]
+pub struct CaptureHidden(pub Visibility);
+
+#[derive(Component)]
+pub struct Heart(pub u32);
+
+#[derive(Component)]
+pub struct MusicLayer(pub usize);
+
+#[derive(Component)]
+pub struct Particle {
+    pub velocity: Vec2,
+    pub lifetime: Timer,
+}
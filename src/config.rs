@@ -0,0 +1,61 @@
+use bevy::prelude::*;
+
+use crate::{
+    constants::{
+        BASE_PIPE_SPACE, BASE_PIPE_SPACING, GLIDE_GRAVITY_FACTOR, GRAVITY, PIPE_BASE_SPEED,
+        PLAYER_JUMP_VELOCITY, SCROLLING_WORLD_HEIGHT, WINDOW_SIZE,
+    },
+    persistence,
+    resources::{CustomModeSettings, GameConfig, GameMode, Settings},
+    AppState,
+};
+
+const CUSTOM_MODE_FILE: &str = "custom_mode.ron";
+
+pub fn config_plugin(app: &mut App) {
+    app.init_resource::<GameConfig>()
+        .init_resource::<GameMode>()
+        .insert_resource(persistence::load::<CustomModeSettings>(CUSTOM_MODE_FILE).clamped())
+        .add_systems(OnExit(AppState::MainMenu), apply_game_mode)
+        .add_systems(
+            Update,
+            save_custom_mode_settings.run_if(
+                resource_changed::<CustomModeSettings>
+                    .and(not(resource_added::<CustomModeSettings>)),
+            ),
+        );
+}
+
+fn apply_game_mode(
+    mode: Res<GameMode>,
+    custom_mode: Res<CustomModeSettings>,
+    settings: Res<Settings>,
+    mut config: ResMut<GameConfig>,
+) {
+    config.pipe_speed = PIPE_BASE_SPEED;
+    config.pipe_gap = BASE_PIPE_SPACE;
+    config.pipe_spacing = match *mode {
+        GameMode::Custom => custom_mode.pipe_spacing,
+        _ => BASE_PIPE_SPACING,
+    };
+    config.world_height = match *mode {
+        GameMode::Scrolling => SCROLLING_WORLD_HEIGHT,
+        _ => WINDOW_SIZE.y,
+    };
+    config.bird_count = match *mode {
+        GameMode::Flock => custom_mode.flock_size,
+        _ => 1,
+    };
+
+    let feel = settings.feel.factor();
+    config.gravity = GRAVITY * feel * feel;
+    config.jump_velocity = PLAYER_JUMP_VELOCITY * feel;
+    config.gravity_curve = settings.gravity_curve;
+    config.jump_mode = settings.jump_mode;
+    config.glide_factor = settings.glide.then_some(GLIDE_GRAVITY_FACTOR);
+    config.max_gap_delta = settings.smooth_gaps.then(|| settings.feel.max_gap_delta());
+}
+
+fn save_custom_mode_settings(custom_mode: Res<CustomModeSettings>) {
+    persistence::save(CUSTOM_MODE_FILE, &*custom_mode);
+}
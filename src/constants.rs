@@ -2,20 +2,195 @@ use bevy::math::{UVec2, Vec2};
 
 pub const PLAYER_SIZE: UVec2 = UVec2::new(68, 48);
 pub const PLAYER_START_POSITION: Vec2 = Vec2::new(-500., 0.);
+// How quickly a bird pushed out of its lane drifts back, per second
+pub const LANE_RETURN_RATE: f32 = 8.;
+pub const LANE_SNAP_DISTANCE: f32 = 0.05;
 pub const PLAYER_JUMP_VELOCITY: f32 = 700.;
+pub const PLAYER_HITBOX_SCALE: f32 = 0.8;
+// Gates see the whole sprite, so a bird clipping the gap edge still scores
+pub const PLAYER_GATE_HITBOX_SCALE: f32 = 1.;
+pub const AUTO_FLAP_INTERVAL: f32 = 0.3;
+pub const IDLE_BOB_AMPLITUDE: f32 = 10.;
+pub const IDLE_BOB_FREQUENCY: f32 = 0.5;
+pub const IDLE_BOB_EASE_DURATION: f32 = 0.3;
+pub const INVINCIBLE_FLASH_FREQUENCY: f32 = 8.;
+// Measured in fixed ticks (64 Hz by default)
+pub const REWIND_TICKS: usize = 128;
+pub const REWIND_BUFFER_SIZE: usize = 192;
+pub const DEATH_REPLAY_TICKS: usize = 96;
+pub const DEATH_REPLAY_SPEED: f32 = 0.3;
+pub const FLOCK_SPACING: f32 = 90.;
+pub const MIN_FLOCK_SIZE: u32 = 2;
+pub const MAX_FLOCK_SIZE: u32 = 5;
+pub const MAX_PRACTICE_START_PIPE: u32 = 99;
 pub const PIPE_BASE_SPEED: f32 = 400.;
 pub const GRAVITY: f32 = -2500.;
 pub const BASE_PIPE_SPAWN_RATE: f32 = 1.1;
+pub const BASE_PIPE_SPACING: f32 = PIPE_BASE_SPEED * BASE_PIPE_SPAWN_RATE;
+pub const MIN_PIPE_SPACING: f32 = 250.;
+// Far more than ever fit on screen, only reached if offscreen pipes stop being despawned
+pub const MAX_LIVE_PIPES: usize = 20;
+pub const MAX_PIPE_SPACING: f32 = 1000.;
+pub const PIPE_SPACING_STEP: f32 = 50.;
 pub const BASE_PIPE_SPACE: f32 = 225.;
 pub const PIPE_WIDTH: f32 = 132.;
 pub const PIPE_HEIGHT: f32 = 796.;
+// Texture rows of the lip at the open end of pipe.png, the rest of the texture is plain body
+pub const PIPE_LIP_HEIGHT: f32 = 52.;
+pub const PIPE_CAP_WIDTH: f32 = PIPE_WIDTH + 16.;
+pub const PIPE_CAP_HEIGHT: f32 = 44.;
+pub const PIPE_SPAWN_ANIM_DURATION: f32 = 0.1;
+pub const PIPE_SPAWN_START_SCALE: f32 = 0.8;
+pub const PIPE_SPAWN_VOLUME: f32 = 0.3;
+// Lightness the lowest possible gap loses against the highest one
+pub const PIPE_DEPTH_TINT: f32 = 0.12;
+pub const VIGNETTE_CLEAR_RADIUS: f32 = 60.;
+pub const MIN_VIGNETTE_STRENGTH: f32 = 0.1;
+pub const MAX_VIGNETTE_STRENGTH: f32 = 0.8;
+pub const VIGNETTE_STRENGTH_STEP: f32 = 0.05;
 pub const GROUND_HEIGHT: f32 = 100.;
 pub const GROUND_SPRITE_HEIGHT: f32 = 176.;
 pub const WINDOW_SIZE: Vec2 = Vec2::new(1920., 1080.);
+pub const SCROLLING_WORLD_HEIGHT: f32 = WINDOW_SIZE.y * 1.5;
+// Pipes enter just past the right edge and are dropped once fully past the left one
+pub const PIPE_SPAWN_X: f32 = WINDOW_SIZE.x / 2. + PIPE_WIDTH;
+pub const PIPE_DESPAWN_X: f32 = -PIPE_SPAWN_X;
+pub const CAMERA_FOLLOW_SPEED: f32 = 5.;
 pub const MINIMUM_PIPE_HEIGHT: f32 = 100.;
+pub const GOLDEN_PIPE_CHANCE: f64 = 0.05;
+pub const GOLDEN_PIPE_POINTS: i32 = 3;
+pub const PARTICLE_LIFETIME: f32 = 0.6;
+pub const ADAPTIVE_LEVEL_STEP: f32 = 0.1;
+pub const MAX_ADAPTIVE_LEVEL: f32 = 1.;
+pub const ADAPTIVE_MAX_ADJUSTMENT: f32 = 0.15;
+pub const ADAPTIVE_QUICK_DEATH_SECS: f32 = 5.;
+pub const ADAPTIVE_LONG_RUN_SECS: f32 = 30.;
+pub const ADAPTIVE_QUICK_DEATH_STREAK: usize = 3;
+pub const GAP_GUIDE_FADE_DISTANCE: f32 = 150.;
+pub const TRAJECTORY_GUIDE_DURATION: f32 = 1.;
+pub const TRAJECTORY_GUIDE_STEP: f32 = 1. / 30.;
+// Dashes per second marching along the guide, reduced motion keeps it still
+pub const TRAJECTORY_GUIDE_DASH_RATE: f32 = 8.;
+pub const PIPE_PREVIEW_COUNT: usize = 3;
+pub const PIPE_PREVIEW_SPACING: f32 = 60.;
+pub const PIPE_PREVIEW_WIDTH: f32 = 30.;
+pub const AUTOPILOT_FLAP_MARGIN: f32 = 40.;
+pub const FLAP_ARC_STEPS: usize = 90;
+pub const FLAP_ARC_STEP: f32 = 1. / 60.;
+// Additive flaps stop stacking at this multiple of the jump velocity
+pub const MAX_JUMP_STACK: f32 = 1.5;
+pub const GLIDE_GRAVITY_FACTOR: f32 = 0.35;
+pub const BENCH_SEED: u64 = 0x5eed;
+pub const BENCH_TICK_RATE: f64 = 64.;
+pub const BENCH_DEFAULT_SECONDS: f32 = 60.;
+pub const DEMO_GAP_RANGE: f32 = 150.;
+pub const APPROACH_WARNING_MARGIN: f32 = 80.;
+pub const APPROACH_WARNING_FLASH_FREQUENCY: f32 = 3.;
+pub const APPROACH_WARNING_FADE: f32 = 0.5;
+pub const MAX_ALTITUDE_SAMPLES: usize = 4096;
+pub const ALTITUDE_GRAPH_SIZE: Vec2 = Vec2::new(600., 200.);
+pub const ALTITUDE_GRAPH_CENTER: Vec2 = Vec2::new(0., 250.);
 pub const BACKGROUND_SPRITE_HEIGHT: f32 = 1080.;
+pub const BACKGROUND_SCROLL_FACTOR: f32 = 0.2;
+pub const HIDPI_SCALE_FACTOR: f32 = 1.5;
+pub const SLOW_MOTION_SPEED: f32 = 0.4;
+pub const MIN_TURBO_SPEED: f32 = 1.5;
+pub const MAX_TURBO_SPEED: f32 = 2.;
+pub const TURBO_SPEED_STEP: f32 = 0.25;
+pub const TAP_PROMPT_PULSE_FREQUENCY: f32 = 1.2;
+pub const HUD_FONT_SIZE: f32 = 50.;
+pub const MILESTONE_INTERVAL: i32 = 10;
+pub const MILESTONE_FLASH_DURATION: f32 = 0.6;
+pub const SCORE_COUNT_DURATION: f32 = 0.2;
+pub const GATE_FLASH_DURATION: f32 = 0.3;
+pub const ASSET_STATUS_REFRESH: f32 = 0.25;
+pub const HUD_MARGIN: f32 = 5.;
+pub const MIN_HUD_SCALE: f32 = 0.5;
+pub const MAX_HUD_SCALE: f32 = 3.;
+pub const HUD_SCALE_STEP: f32 = 0.25;
+pub const MAX_STARTING_SCORE: i32 = 100;
+pub const STARTING_SCORE_STEP: i32 = 5;
+pub const MAX_MIN_SAVED_SCORE: i32 = 50;
+pub const MAX_LIVES: u32 = 5;
+pub const LIFE_LOST_INVINCIBLE_DURATION: f32 = 1.5;
+pub const SCORE_ATTACK_DURATION: f32 = 60.;
+pub const SCORE_ATTACK_CRASH_PENALTY: f32 = 5.;
+pub const SCORE_DECAY_RATE_STEP: f32 = 0.25;
+pub const MIN_SCORE_DECAY_RATE: f32 = 0.25;
+pub const MAX_SCORE_DECAY_RATE: f32 = 2.;
+pub const DECAY_BAR_SIZE: Vec2 = Vec2::new(120., 8.);
+pub const MAX_TUTORIAL_GAMES: u32 = 10;
+pub const CAREFUL_PROMPT_DURATION: f32 = 1.5;
+pub const NEW_BEST_BANNER_DURATION: f32 = 2.;
+pub const BEST_RUN_MARKER_WIDTH: f32 = 6.;
+pub const BEST_RUN_MARKER_ALPHA: f32 = 0.35;
+// Fewer gates than this is too short a run to call perfect
+pub const PERFECT_RUN_MIN_GATES: u32 = 10;
+// Share of the half gap the bird may be off the gap center for a centered pass
+pub const PERFECT_CENTER_TOLERANCE: f32 = 0.25;
+pub const HEART_SIZE: f32 = 24.;
+// Every this many gates carries a shield pickup while the bird has none
+pub const SHIELD_PICKUP_INTERVAL: u32 = 15;
+pub const SHIELD_PICKUP_SIZE: f32 = 28.;
+pub const SHIELD_BUBBLE_SIZE: f32 = 64.;
+pub const SHIELD_ICON_EMPTY_ALPHA: f32 = 0.25;
+pub const SHIELD_BROKEN_INVINCIBLE_DURATION: f32 = 1.;
+pub const SHIELD_FLASH_DURATION: f32 = 0.15;
+// Sprite colors above 1 wash the texture out towards white
+pub const SHIELD_FLASH_BRIGHTNESS: f32 = 6.;
+pub const RUMBLE_STRENGTH_STEP: f32 = 0.25;
+pub const GAMEPAD_DEADZONE_STEP: f32 = 0.05;
+pub const MIN_GAMEPAD_DEADZONE: f32 = 0.2;
+pub const MAX_GAMEPAD_DEADZONE: f32 = 0.9;
+// An analog flap is released this far below the deadzone, so noise around it is one press
+pub const GAMEPAD_RELEASE_MARGIN: f32 = 0.15;
+pub const RESCUE_LOOKAHEAD_STEP: f32 = 0.25;
+pub const MAX_RESCUE_LOOKAHEAD: f32 = 1.;
+pub const START_GRACE_STEP: f32 = 0.25;
+pub const MAX_START_GRACE: f32 = 1.5;
+pub const RETRY_COOLDOWN_STEP: f32 = 0.25;
+pub const MAX_RETRY_COOLDOWN: f32 = 2.;
+pub const AUTO_RESTART_DELAY_STEP: f32 = 1.;
+pub const MIN_AUTO_RESTART_DELAY: f32 = 1.;
+pub const MAX_AUTO_RESTART_DELAY: f32 = 30.;
+pub const QUIT_CONFIRM_WINDOW: f32 = 2.;
+pub const PAUSE_DIM_ALPHA: f32 = 0.6;
+pub const PAUSE_FADE_DURATION: f32 = 0.15;
+pub const MENU_SCROLL_LINE_HEIGHT: f32 = 40.;
+pub const BIRD_SCALE_STEP: f32 = 0.25;
+pub const MIN_BIRD_SCALE: f32 = 0.5;
+pub const MAX_BIRD_SCALE: f32 = 1.5;
+pub const DEATH_RUMBLE_DURATION: f32 = 0.4;
+pub const DEATH_RECOIL_SPEED: f32 = 300.;
+pub const SCORE_RUMBLE_DURATION: f32 = 0.08;
+pub const SCORE_RUMBLE_STRENGTH: f32 = 0.3;
+pub const MUSIC_VOLUME_STEP: f32 = 0.1;
+pub const MUSIC_BLEND_SPEED: f32 = 2.;
+pub const MUSIC_INTENSITY_THRESHOLDS: [i32; 2] = [10, 25];
 
+// Default sprite depths, read through the ZLayers resource at spawn time
 pub const BACKGROUND_Z: f32 = 0.;
 pub const PIPE_Z: f32 = 1.;
 pub const GROUND_Z: f32 = 2.;
+pub const GHOST_Z: f32 = 2.5;
 pub const PLAYER_Z: f32 = 3.;
+pub const PARTICLE_Z: f32 = 4.;
+
+// Asset paths, the debug asset status panel lists the same ones
+pub const PIPE_IMAGE: &str = "pipe.png";
+pub const GROUND_IMAGE: &str = "ground.png";
+pub const BACKGROUND_IMAGE: &str = "background.png";
+pub const HEART_IMAGE: &str = "heart.png";
+pub const SHIELD_IMAGE: &str = "shield.png";
+pub const PIPE_SPAWN_SOUND: &str = "sounds/pipe_spawn.ogg";
+pub const GOLDEN_PIPE_SOUND: &str = "sounds/golden_pipe.ogg";
+pub const MILESTONE_SOUND: &str = "sounds/milestone.ogg";
+pub const PERFECT_SOUND: &str = "sounds/perfect.ogg";
+pub const SHIELD_PICKUP_SOUND: &str = "sounds/shield_pickup.ogg";
+pub const SHIELD_BREAK_SOUND: &str = "sounds/shield_break.ogg";
+// Calm to energetic
+pub const MUSIC_LAYERS: [&str; 3] = [
+    "sounds/music_calm.ogg",
+    "sounds/music_drive.ogg",
+    "sounds/music_peak.ogg",
+];
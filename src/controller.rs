@@ -0,0 +1,90 @@
+use bevy::{
+    input::gamepad::{GamepadConnection, GamepadConnectionEvent},
+    prelude::*,
+};
+
+use crate::{
+    resources::{ActiveGamepad, PauseReason, Settings},
+    AppState, PauseState,
+};
+
+pub fn controller_plugin(app: &mut App) {
+    app.init_resource::<ActiveGamepad>()
+        .init_resource::<PauseReason>()
+        .add_systems(OnExit(PauseState::Paused), clear_pause_reason)
+        .add_systems(
+            Update,
+            (
+                track_active_gamepad,
+                pause_on_disconnect.run_if(in_state(PauseState::Running)),
+                resume_after_disconnect.run_if(
+                    in_state(PauseState::Paused)
+                        .and(resource_equals(PauseReason::ControllerDisconnected)),
+                ),
+            )
+                .run_if(in_state(AppState::InGame)),
+        );
+}
+
+// The player counts as using a pad from its last button press until they touch the keyboard or
+// mouse again
+fn track_active_gamepad(
+    key_input: Res<ButtonInput<KeyCode>>,
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    gamepads: Query<(Entity, &Gamepad)>,
+    mut active_gamepad: ResMut<ActiveGamepad>,
+) {
+    if let Some((entity, _)) = gamepads
+        .iter()
+        .find(|(_, gamepad)| gamepad.get_just_pressed().next().is_some())
+    {
+        active_gamepad.0 = Some(entity);
+    } else if key_input.get_just_pressed().next().is_some()
+        || mouse_input.get_just_pressed().next().is_some()
+    {
+        active_gamepad.0 = None;
+    }
+}
+
+fn pause_on_disconnect(
+    settings: Res<Settings>,
+    mut connection_events: MessageReader<GamepadConnectionEvent>,
+    mut active_gamepad: ResMut<ActiveGamepad>,
+    mut pause_reason: ResMut<PauseReason>,
+    mut next_pause: ResMut<NextState<PauseState>>,
+) {
+    for connection_event in connection_events.read() {
+        let is_active = active_gamepad.0 == Some(connection_event.gamepad);
+        if !is_active || connection_event.connection != GamepadConnection::Disconnected {
+            continue;
+        }
+
+        active_gamepad.0 = None;
+        if settings.pause_on_controller_disconnect {
+            *pause_reason = PauseReason::ControllerDisconnected;
+            next_pause.set(PauseState::Paused);
+        }
+    }
+}
+
+// Any pad coming back, or a key or click, carries on with the run
+fn resume_after_disconnect(
+    key_input: Res<ButtonInput<KeyCode>>,
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    mut connection_events: MessageReader<GamepadConnectionEvent>,
+    mut next_pause: ResMut<NextState<PauseState>>,
+) {
+    let reconnected = connection_events
+        .read()
+        .any(|connection_event| connection_event.connection != GamepadConnection::Disconnected);
+    if reconnected
+        || key_input.get_just_pressed().next().is_some()
+        || mouse_input.get_just_pressed().next().is_some()
+    {
+        next_pause.set(PauseState::Running);
+    }
+}
+
+fn clear_pause_reason(mut pause_reason: ResMut<PauseReason>) {
+    *pause_reason = PauseReason::Menu;
+}
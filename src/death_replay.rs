@@ -0,0 +1,167 @@
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+
+use crate::{
+    actions::{Action, ActionState},
+    components::{DeathReplayText, Pipe, Player, Velocity},
+    constants::*,
+    resources::{DeathReplayBuffer, PipeSpawnTimer, Settings, WorldSnapshot},
+    step::physics_running,
+    AppState, PauseState,
+};
+
+pub fn death_replay_plugin(app: &mut App) {
+    app.init_resource::<DeathReplayBuffer>()
+        .add_systems(OnEnter(AppState::InGame), clear_death_replay)
+        .add_systems(
+            FixedUpdate,
+            record_death_replay.run_if(in_state(PauseState::Running).and(physics_running)),
+        )
+        .add_systems(OnEnter(AppState::DeathReplay), start_death_replay)
+        .add_systems(
+            Update,
+            (skip_death_replay, play_death_replay)
+                .chain()
+                .run_if(in_state(AppState::DeathReplay)),
+        );
+}
+
+fn clear_death_replay(mut buffer: ResMut<DeathReplayBuffer>) {
+    buffer.snapshots.clear();
+    buffer.playhead = 0.;
+}
+
+// Dead birds are recorded too, the replay has to show the crash itself
+fn record_death_replay(
+    mut buffer: ResMut<DeathReplayBuffer>,
+    spawn_timer: Res<PipeSpawnTimer>,
+    player_query: Query<(Entity, &Transform, &Velocity), With<Player>>,
+    pipe_query: Query<(Entity, &Transform), With<Pipe>>,
+) {
+    if buffer.snapshots.len() == DEATH_REPLAY_TICKS {
+        buffer.snapshots.pop_front();
+    }
+
+    buffer.snapshots.push_back(WorldSnapshot {
+        players: player_query
+            .iter()
+            .map(|(entity, transform, velocity)| (entity, transform.translation, velocity.0))
+            .collect(),
+        pipes: pipe_query
+            .iter()
+            .map(|(entity, transform)| (entity, transform.translation))
+            .collect(),
+        pipe_spawn_elapsed: spawn_timer.0.elapsed(),
+    });
+}
+
+fn start_death_replay(
+    mut commands: Commands,
+    settings: Res<Settings>,
+    mut buffer: ResMut<DeathReplayBuffer>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    if settings.reduced_motion || buffer.snapshots.is_empty() {
+        next_state.set(AppState::GameOver);
+        return;
+    }
+
+    buffer.playhead = 0.;
+    commands.spawn((
+        DeathReplayText,
+        Text::new("Replay - flap to skip"),
+        TextFont {
+            font_size: 30.0,
+            ..default()
+        },
+        TextColor(Color::WHITE.with_alpha(0.8)),
+        Node {
+            position_type: PositionType::Absolute,
+            top: px(10),
+            right: px(10),
+            ..default()
+        },
+        Pickable::IGNORE,
+        DespawnOnExit(AppState::DeathReplay),
+    ));
+}
+
+fn skip_death_replay(actions: Res<ActionState>, mut buffer: ResMut<DeathReplayBuffer>) {
+    if actions.just_pressed(Action::Flap) {
+        buffer.playhead = buffer.snapshots.len() as f32;
+    }
+}
+
+// Real time, so the replay speed does not depend on the slow motion or turbo setting of the
+// run. Ends on the last recorded tick, which is where the crash left everything
+#[allow(
+    clippy::type_complexity,
+    reason = "pipes exclude Player so both queries can move their Transform"
+)]
+fn play_death_replay(
+    real_time: Res<Time<Real>>,
+    fixed_time: Res<Time<Fixed>>,
+    mut buffer: ResMut<DeathReplayBuffer>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut player_query: Query<&mut Transform, With<Player>>,
+    mut pipe_query: Query<(Entity, &mut Transform, &mut Visibility), (With<Pipe>, Without<Player>)>,
+) {
+    buffer.playhead +=
+        real_time.delta_secs() / fixed_time.timestep().as_secs_f32() * DEATH_REPLAY_SPEED;
+
+    let Some(last) = buffer.snapshots.len().checked_sub(1) else {
+        next_state.set(AppState::GameOver);
+        return;
+    };
+    let finished = buffer.playhead as usize >= last;
+    let Some(snapshot) = buffer.snapshots.get((buffer.playhead as usize).min(last)) else {
+        return;
+    };
+
+    for &(entity, translation, _) in &snapshot.players {
+        if let Ok(mut transform) = player_query.get_mut(entity) {
+            transform.translation = translation;
+        }
+    }
+
+    // Pipes that only spawned later in the replay stay hidden until their first recorded tick
+    let pipe_positions: HashMap<Entity, Vec3> = snapshot.pipes.iter().copied().collect();
+    for (entity, mut transform, mut visibility) in pipe_query.iter_mut() {
+        match pipe_positions.get(&entity) {
+            Some(&translation) => {
+                transform.translation = translation;
+                *visibility = Visibility::Inherited;
+            }
+            None => *visibility = Visibility::Hidden,
+        }
+    }
+
+    if finished {
+        next_state.set(AppState::GameOver);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::RunSystemOnce;
+
+    use super::*;
+
+    // start_death_replay skips an empty buffer, but the replay must not underflow if it runs anyway
+    #[test]
+    fn an_empty_replay_goes_straight_to_game_over() {
+        let mut world = World::new();
+        world.init_resource::<Time<Real>>();
+        world.init_resource::<Time<Fixed>>();
+        world.init_resource::<DeathReplayBuffer>();
+        world.init_resource::<NextState<AppState>>();
+
+        world.run_system_once(play_death_replay).unwrap();
+
+        assert!(matches!(
+            world.resource::<NextState<AppState>>(),
+            NextState::Pending(AppState::GameOver)
+        ));
+    }
+}
@@ -0,0 +1,182 @@
+use bevy::{
+    math::bounding::{Aabb2d, IntersectsVolume},
+    prelude::*,
+};
+use rand::Rng;
+
+use crate::{
+    asset_variants::image_variant,
+    assist::nearest_upcoming_pipe,
+    components::{AnimationTimer, Collider, ColliderType, DemoBird, DemoPipe, Pipe, Velocity},
+    constants::*,
+    layers::ZLayers,
+    pipes::{pipe_body_length, spawn_pipe_pair},
+    resources::{BirdSkin, DemoPipeTimer, HiDpi, PlayArea},
+    AppState,
+};
+
+pub fn demo_plugin(app: &mut App) {
+    app.insert_resource(DemoPipeTimer(Timer::from_seconds(
+        BASE_PIPE_SPAWN_RATE,
+        TimerMode::Repeating,
+    )))
+    .add_systems(OnEnter(AppState::MainMenu), spawn_demo_bird)
+    .add_systems(
+        FixedUpdate,
+        (
+            demo_autopilot,
+            demo_gravity,
+            demo_pipe_spawner,
+            demo_pipe_movement,
+            demo_collision,
+        )
+            .chain()
+            .run_if(in_state(AppState::MainMenu)),
+    );
+}
+
+// Flap whenever the bird sinks below the next gap while falling
+pub fn autopilot_should_flap(position: Vec2, velocity: f32, gap_center: Vec2) -> bool {
+    velocity < 0. && position.y < gap_center.y - AUTOPILOT_FLAP_MARGIN
+}
+
+fn spawn_demo_bird(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    hidpi: Res<HiDpi>,
+    skin: Res<BirdSkin>,
+    layers: Res<ZLayers>,
+    mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
+    mut spawn_timer: ResMut<DemoPipeTimer>,
+) {
+    spawn_timer.0.reset();
+
+    let variant = image_variant(skin.0.image, &hidpi);
+    let texture: Handle<Image> = asset_server.load(variant.path);
+    let layout = skin.0.atlas_layout(variant.scale);
+    let animation_indices = skin.0.animation_indices();
+
+    commands.spawn((
+        DemoBird,
+        Velocity(0.0),
+        AnimationTimer(Timer::from_seconds(0.15, TimerMode::Repeating)),
+        Transform::from_translation(PLAYER_START_POSITION.extend(layers.player)),
+        Sprite {
+            image: texture,
+            texture_atlas: Some(TextureAtlas {
+                layout: texture_atlas_layouts.add(layout),
+                index: animation_indices.first,
+            }),
+            custom_size: Some(PLAYER_SIZE.as_vec2()),
+            flip_x: skin.0.flip_x,
+            ..default()
+        },
+        animation_indices,
+        DespawnOnExit(AppState::MainMenu),
+    ));
+}
+
+fn demo_autopilot(
+    mut bird_query: Query<(&Transform, &mut Velocity), With<DemoBird>>,
+    pipe_query: Query<&Transform, With<DemoPipe>>,
+) {
+    for (transform, mut velocity) in bird_query.iter_mut() {
+        let position = transform.translation.truncate();
+        let gap_center = nearest_upcoming_pipe(position.x, pipe_query.iter())
+            .unwrap_or(Vec2::new(position.x, 0.));
+
+        if autopilot_should_flap(position, velocity.0, gap_center) {
+            velocity.0 = PLAYER_JUMP_VELOCITY;
+        }
+    }
+}
+
+fn demo_gravity(
+    time: Res<Time>,
+    mut query: Query<(&mut Transform, &mut Velocity), With<DemoBird>>,
+) {
+    for (mut transform, mut velocity) in &mut query {
+        transform.translation.y +=
+            velocity.0 * time.delta_secs() + 0.5 * GRAVITY * time.delta_secs().powi(2);
+        velocity.0 += GRAVITY * time.delta_secs();
+    }
+}
+
+// Uses thread_rng so the menu does not consume the seeded run layout
+fn demo_pipe_spawner(
+    mut commands: Commands,
+    mut spawn_timer: ResMut<DemoPipeTimer>,
+    time: Res<Time>,
+    asset_server: Res<AssetServer>,
+    hidpi: Res<HiDpi>,
+    layers: Res<ZLayers>,
+    play_area: Res<PlayArea>,
+) {
+    if !spawn_timer.0.tick(time.delta()).just_finished() {
+        return;
+    }
+
+    let gap_center_y = rand::thread_rng().gen_range(-DEMO_GAP_RANGE..=DEMO_GAP_RANGE);
+    let texture_handle = asset_server.load(image_variant(PIPE_IMAGE, &hidpi).path);
+    let pipe = spawn_pipe_pair(
+        &mut commands,
+        texture_handle,
+        Vec2::new(PIPE_SPAWN_X, gap_center_y),
+        BASE_PIPE_SPACE,
+        pipe_body_length(WINDOW_SIZE.y, play_area.visible_height),
+        Color::WHITE,
+        layers.pipe,
+    );
+    commands
+        .entity(pipe)
+        .insert((DemoPipe, DespawnOnExit(AppState::MainMenu)));
+}
+
+fn demo_pipe_movement(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut Transform), With<DemoPipe>>,
+) {
+    for (entity, mut transform) in &mut query {
+        transform.translation.x -= PIPE_BASE_SPEED * time.delta_secs();
+        if transform.translation.x < PIPE_DESPAWN_X {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+// The demo never shows a game over, a crash just starts it over
+fn demo_collision(
+    mut commands: Commands,
+    mut spawn_timer: ResMut<DemoPipeTimer>,
+    layers: Res<ZLayers>,
+    mut bird_query: Query<(&mut Transform, &mut Velocity), With<DemoBird>>,
+    collider_query: Query<(&GlobalTransform, &Collider), Without<DemoBird>>,
+    pipe_query: Query<Entity, (With<Pipe>, With<DemoPipe>)>,
+) {
+    for (mut transform, mut velocity) in bird_query.iter_mut() {
+        let bird_aabb = Aabb2d::new(
+            transform.translation.truncate(),
+            PLAYER_SIZE.as_vec2() * PLAYER_HITBOX_SCALE / 2.,
+        );
+        let is_hit = transform.translation.y > WINDOW_SIZE.y / 2.
+            || collider_query.iter().any(|(collider_transform, collider)| {
+                collider.kind == ColliderType::Bad
+                    && bird_aabb.intersects(&Aabb2d::new(
+                        collider_transform.translation().truncate(),
+                        collider.size / 2.,
+                    ))
+            });
+
+        if !is_hit {
+            continue;
+        }
+
+        transform.translation = PLAYER_START_POSITION.extend(layers.player);
+        velocity.0 = 0.;
+        spawn_timer.0.reset();
+        for pipe in pipe_query.iter() {
+            commands.entity(pipe).despawn();
+        }
+    }
+}
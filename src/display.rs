@@ -0,0 +1,48 @@
+use bevy::{
+    prelude::*,
+    window::{Monitor, PrimaryWindow, WindowMode},
+};
+
+use crate::resources::Settings;
+
+pub fn display_plugin(app: &mut App) {
+    app.add_systems(Update, apply_preferred_monitor);
+}
+
+// Reapplies when the setting changes or a monitor is connected or disconnected
+fn apply_preferred_monitor(
+    settings: Res<Settings>,
+    monitors: Query<(Entity, &Monitor)>,
+    added_monitors: Query<(), Added<Monitor>>,
+    mut removed_monitors: RemovedComponents<Monitor>,
+    mut window: Single<&mut Window, With<PrimaryWindow>>,
+    mut applied_monitor: Local<Option<Option<String>>>,
+) {
+    let monitors_changed = !added_monitors.is_empty() || removed_monitors.read().count() > 0;
+    if !monitors_changed && applied_monitor.as_ref() == Some(&settings.preferred_monitor) {
+        return;
+    }
+    *applied_monitor = Some(settings.preferred_monitor.clone());
+
+    let selection = match &settings.preferred_monitor {
+        None => MonitorSelection::Primary,
+        Some(name) => match monitors
+            .iter()
+            .find(|(_, monitor)| monitor.name.as_ref() == Some(name))
+        {
+            Some((entity, _)) => MonitorSelection::Entity(entity),
+            None => {
+                warn!("Monitor {name} is not connected, falling back to the primary monitor");
+                MonitorSelection::Primary
+            }
+        },
+    };
+
+    window.position = WindowPosition::Centered(selection);
+    match &mut window.mode {
+        WindowMode::BorderlessFullscreen(target) | WindowMode::Fullscreen(target, _) => {
+            *target = selection;
+        }
+        WindowMode::Windowed => {}
+    }
+}
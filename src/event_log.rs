@@ -0,0 +1,138 @@
+use bevy::prelude::*;
+
+use crate::{
+    collision::CollisionSide,
+    events::{
+        GroundCollisionEvent, IncrementScoreEvent, JumpEvent, JumpSource, PipeCollisionEvent,
+    },
+    AppState,
+};
+
+pub fn event_log_plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        log_state_transitions.run_if(resource_exists::<EventLog>),
+    )
+    .add_observer(log_jump)
+    .add_observer(log_score)
+    .add_observer(log_pipe_collision)
+    .add_observer(log_ground_collision);
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum LoggedEvent {
+    Jump(JumpSource),
+    Score(i32),
+    PipeCollision(CollisionSide),
+    GroundCollision(CollisionSide),
+    StateChanged(AppState),
+}
+
+// A timeline of what happened in a run, for tests that drive the app headless and check the
+// order of events. Nothing is recorded until a test inserts the resource, the game never does
+#[derive(Resource, Default)]
+pub struct EventLog {
+    // Seconds on the virtual clock
+    pub entries: Vec<(f32, LoggedEvent)>,
+}
+
+impl EventLog {
+    pub fn events(&self) -> Vec<LoggedEvent> {
+        self.entries
+            .iter()
+            .map(|(_, event)| event.clone())
+            .collect()
+    }
+}
+
+fn record(log: Option<ResMut<EventLog>>, time: &Time, event: LoggedEvent) {
+    if let Some(mut log) = log {
+        log.entries.push((time.elapsed_secs(), event));
+    }
+}
+
+fn log_jump(jump_event: On<JumpEvent>, log: Option<ResMut<EventLog>>, time: Res<Time>) {
+    record(log, &time, LoggedEvent::Jump(jump_event.source));
+}
+
+fn log_score(
+    increment_event: On<IncrementScoreEvent>,
+    log: Option<ResMut<EventLog>>,
+    time: Res<Time>,
+) {
+    record(log, &time, LoggedEvent::Score(increment_event.points));
+}
+
+fn log_pipe_collision(
+    collision_event: On<PipeCollisionEvent>,
+    log: Option<ResMut<EventLog>>,
+    time: Res<Time>,
+) {
+    record(log, &time, LoggedEvent::PipeCollision(collision_event.side));
+}
+
+fn log_ground_collision(
+    collision_event: On<GroundCollisionEvent>,
+    log: Option<ResMut<EventLog>>,
+    time: Res<Time>,
+) {
+    record(
+        log,
+        &time,
+        LoggedEvent::GroundCollision(collision_event.side),
+    );
+}
+
+fn log_state_transitions(
+    mut transitions: MessageReader<StateTransitionEvent<AppState>>,
+    mut log: ResMut<EventLog>,
+    time: Res<Time>,
+) {
+    for transition in transitions.read() {
+        if let Some(entered) = &transition.entered {
+            let elapsed = time.elapsed_secs();
+            log.entries
+                .push((elapsed, LoggedEvent::StateChanged(entered.clone())));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::state::app::StatesPlugin;
+
+    use super::*;
+
+    #[test]
+    fn records_events_in_order() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, StatesPlugin))
+            .init_state::<AppState>()
+            .init_resource::<EventLog>();
+        event_log_plugin(&mut app);
+        app.update();
+
+        app.world_mut()
+            .resource_mut::<NextState<AppState>>()
+            .set(AppState::InGame);
+        app.update();
+        app.world_mut().trigger(JumpEvent::default());
+        app.world_mut().trigger(IncrementScoreEvent { points: 1 });
+        app.world_mut().trigger(GroundCollisionEvent {
+            player: Entity::PLACEHOLDER,
+            side: CollisionSide::Top,
+        });
+        app.update();
+
+        assert_eq!(
+            app.world().resource::<EventLog>().events(),
+            [
+                LoggedEvent::StateChanged(AppState::MainMenu),
+                LoggedEvent::StateChanged(AppState::InGame),
+                LoggedEvent::Jump(JumpSource::Flap),
+                LoggedEvent::Score(1),
+                LoggedEvent::GroundCollision(CollisionSide::Top),
+            ]
+        );
+    }
+}
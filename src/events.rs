@@ -1,24 +1,91 @@
-use bevy::ecs::event::Event;
+use bevy::{
+    ecs::{entity::Entity, event::Event},
+    math::Vec2,
+};
+
+use crate::collision::CollisionSide;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum JumpSource {
+    // The player's flap action, from whichever device
+    #[default]
+    Flap,
+    AutoFlap,
+    Autopilot,
+    Rescue,
+}
 
 #[derive(Event, Default)]
-pub struct JumpEvent;
+pub struct JumpEvent {
+    pub source: JumpSource,
+}
 
 #[derive(Event)]
-pub struct IncrementScoreEvent;
+pub struct IncrementScoreEvent {
+    pub points: i32,
+}
 
+// Sent once at the start of every run, anything tracking per-run progress resets on it
 #[derive(Event)]
-pub struct ResetScoreEvent;
+pub struct ResetScoreEvent {
+    pub starting_score: i32,
+}
 
 #[derive(Event)]
-pub struct UpdateScoreEvent {
-    pub new_score: i32,
+pub struct ScoreChangedEvent;
+
+// A shield took a hit that would otherwise have cost a life or the run
+#[derive(Event)]
+pub struct ShieldBrokenEvent {
+    pub player: Entity,
+    pub position: Vec2,
 }
 
 #[derive(Event)]
-pub struct ScoreChangedEvent;
+pub struct PipeCollisionEvent {
+    pub player: Entity,
+    pub side: CollisionSide,
+}
+
+#[derive(Event)]
+pub struct GroundCollisionEvent {
+    pub player: Entity,
+    pub side: CollisionSide,
+}
+
+// A bird touched a point gate; logged and shown with `--log-scoring`, and checked for centered
+// passes by the perfect run detector
+#[derive(Event)]
+pub struct PointGateReachedEvent {
+    pub pipe: Option<Entity>,
+    pub player_x: f32,
+    pub player_y: f32,
+    pub gate_x: f32,
+    pub gate_y: f32,
+    pub gate_size: Vec2,
+    pub side: CollisionSide,
+}
+
+// A bird used up one of its spare lives
+#[derive(Event)]
+pub struct LivesChangedEvent {
+    pub player: Entity,
+}
 
+// A new player's first lethal crash of the run was forgiven
 #[derive(Event)]
-pub struct PipeCollisionEvent;
+pub struct TutorialSaveEvent;
 
+// A crash in score attack costs clock time instead of ending the run
 #[derive(Event)]
-pub struct GroundCollisionEvent;
+pub struct TimePenaltyEvent {
+    pub seconds: f32,
+}
+
+#[derive(Event)]
+pub struct RewindEvent;
+
+#[derive(Event)]
+pub struct GoldenPipeScoredEvent {
+    pub position: Vec2,
+}
@@ -0,0 +1,71 @@
+use std::f32::consts::PI;
+
+use bevy::prelude::*;
+
+use crate::{components::TapPrompt, constants::*, resources::Settings, AppState};
+
+pub fn get_ready_plugin(app: &mut App) {
+    app.add_systems(OnEnter(AppState::GameStart), spawn_get_ready)
+        .add_systems(
+            Update,
+            pulse_tap_prompt.run_if(in_state(AppState::GameStart)),
+        );
+}
+
+fn spawn_get_ready(mut commands: Commands) {
+    commands.spawn((
+        Node {
+            width: percent(100),
+            height: percent(100),
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::Center,
+            ..default()
+        },
+        // Purely decorative, the click underneath still has to start the game
+        Pickable::IGNORE,
+        DespawnOnExit(AppState::GameStart),
+        children![
+            (
+                Text::new("Get Ready"),
+                TextFont {
+                    font_size: 100.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+                Node {
+                    margin: UiRect::bottom(px(30)),
+                    ..default()
+                },
+                Pickable::IGNORE,
+            ),
+            (
+                TapPrompt,
+                Text::new("Click to flap"),
+                TextFont {
+                    font_size: 40.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+                Pickable::IGNORE,
+            ),
+        ],
+    ));
+}
+
+fn pulse_tap_prompt(
+    time: Res<Time<Real>>,
+    settings: Res<Settings>,
+    mut query: Query<&mut TextColor, With<TapPrompt>>,
+) {
+    let alpha = if settings.reduced_motion {
+        1.
+    } else {
+        let wave_position = 2. * PI * TAP_PROMPT_PULSE_FREQUENCY * time.elapsed_secs();
+        0.65 + 0.35 * wave_position.sin()
+    };
+
+    for mut color in query.iter_mut() {
+        color.0.set_alpha(alpha);
+    }
+}
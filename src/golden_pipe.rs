@@ -0,0 +1,24 @@
+use bevy::{color::palettes::css::GOLD, prelude::*};
+
+use crate::{
+    constants::GOLDEN_PIPE_SOUND, events::GoldenPipeScoredEvent, layers::ZLayers,
+    particles::spawn_particle_burst,
+};
+
+pub fn golden_pipe_plugin(app: &mut App) {
+    app.add_observer(celebrate_golden_pipe);
+}
+
+fn celebrate_golden_pipe(
+    scored_event: On<GoldenPipeScoredEvent>,
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    layers: Res<ZLayers>,
+) {
+    let position = scored_event.position.extend(layers.particle);
+    spawn_particle_burst(&mut commands, position, GOLD.into(), 24);
+    commands.spawn((
+        AudioPlayer::new(asset_server.load(GOLDEN_PIPE_SOUND)),
+        PlaybackSettings::DESPAWN,
+    ));
+}
@@ -0,0 +1,130 @@
+use bevy::{color::palettes::css::WHITE, prelude::*};
+
+use crate::{
+    components::Player,
+    constants::*,
+    resources::{GameConfig, GravityCurve},
+    AppState,
+};
+
+pub fn gravity_plugin(app: &mut App) {
+    app.add_systems(Update, draw_flap_arc.run_if(in_state(AppState::GameStart)));
+}
+
+// One step under a constant acceleration, the curve only changes the acceleration between steps
+// s = v_0 * t + 1/2 * a * t^2
+// v = v_0 + a * t
+pub fn fall_step(y: f32, velocity: f32, acceleration: f32, dt: f32) -> (f32, f32) {
+    (
+        y + (velocity * dt + 0.5 * acceleration * dt.powi(2)),
+        velocity + acceleration * dt,
+    )
+}
+
+// Gravity on a bird right now. Holding the flap only eases the fall, a rising bird slows down
+// at the usual rate
+pub fn fall_acceleration(
+    config: &GameConfig,
+    airtime: f32,
+    velocity: f32,
+    glide_held: bool,
+) -> f32 {
+    let acceleration = config.gravity * config.gravity_curve.factor(airtime);
+    match config.glide_factor {
+        Some(factor) if glide_held && velocity < 0. => acceleration * factor,
+        _ => acceleration,
+    }
+}
+
+// The arc of a single flap from the hover, drawn only for the non-classic curves so the
+// get-ready screen shows what the selected curve does
+fn draw_flap_arc(
+    mut gizmos: Gizmos,
+    config: Res<GameConfig>,
+    player_query: Query<&Transform, With<Player>>,
+) {
+    if config.gravity_curve == GravityCurve::Classic {
+        return;
+    }
+
+    for transform in player_query.iter() {
+        let mut position = transform.translation.truncate();
+        let mut velocity = config.jump_velocity;
+        let points = (0..FLAP_ARC_STEPS).map(|step| {
+            let airtime = step as f32 * FLAP_ARC_STEP;
+            let acceleration = config.gravity * config.gravity_curve.factor(airtime);
+            (position.y, velocity) = fall_step(position.y, velocity, acceleration, FLAP_ARC_STEP);
+            position.x += config.pipe_speed * FLAP_ARC_STEP;
+            position
+        });
+        gizmos.linestrip_2d(points, WHITE.with_alpha(0.5));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::resources::JumpMode;
+
+    // Classic has to stay bit for bit what apply_gravity did before the curves existed
+    #[test]
+    fn classic_curve_keeps_the_old_kinematics() {
+        let (mut y, mut velocity) = (0., PLAYER_JUMP_VELOCITY);
+        let (mut expected_y, mut expected_velocity) = (y, velocity);
+        let mut airtime = 0.;
+
+        for dt in [1. / 64., 1. / 60., 1. / 144., 0.05].repeat(40) {
+            let acceleration = GRAVITY * GravityCurve::Classic.factor(airtime);
+            (y, velocity) = fall_step(y, velocity, acceleration, dt);
+            airtime += dt;
+
+            expected_y += expected_velocity * dt + 0.5 * GRAVITY * dt.powi(2);
+            expected_velocity += GRAVITY * dt;
+
+            assert_eq!(y, expected_y);
+            assert_eq!(velocity, expected_velocity);
+        }
+    }
+
+    #[test]
+    fn soft_start_eases_into_full_gravity() {
+        assert!(GravityCurve::SoftStart.factor(0.) < 1.);
+        assert_eq!(GravityCurve::SoftStart.factor(1.), 1.);
+        assert!(GravityCurve::Heavy.factor(1.) > 1.);
+    }
+
+    #[test]
+    fn holding_the_flap_glides_only_when_enabled() {
+        let fall_for_a_second = |config: &GameConfig, glide_held: bool| {
+            let (mut y, mut velocity, mut airtime) = (0., 0., 0.);
+            for _ in 0..60 {
+                let acceleration = fall_acceleration(config, airtime, velocity, glide_held);
+                (y, velocity) = fall_step(y, velocity, acceleration, 1. / 60.);
+                airtime += 1. / 60.;
+            }
+            -y
+        };
+
+        let classic = GameConfig::default();
+        let gliding = GameConfig {
+            glide_factor: Some(GLIDE_GRAVITY_FACTOR),
+            ..default()
+        };
+        let normal_fall = fall_for_a_second(&classic, false);
+        assert_eq!(fall_for_a_second(&classic, true), normal_fall);
+        assert_eq!(fall_for_a_second(&gliding, false), normal_fall);
+        assert!(fall_for_a_second(&gliding, true) < normal_fall * 0.5);
+    }
+
+    #[test]
+    fn reset_flaps_ignore_the_fall_and_additive_ones_keep_it() {
+        let jump = PLAYER_JUMP_VELOCITY;
+        for velocity in [-1500., -300., 0., jump] {
+            assert_eq!(JumpMode::Reset.jump(velocity, jump), jump);
+        }
+
+        assert_eq!(JumpMode::Additive.jump(-1500., jump), -1500. + jump);
+        assert_eq!(JumpMode::Additive.jump(0., jump), jump);
+        assert_eq!(JumpMode::Additive.jump(jump, jump), jump * MAX_JUMP_STACK);
+    }
+}
@@ -0,0 +1,154 @@
+use bevy::prelude::*;
+
+use crate::{
+    persistence::{self, SaveStorage},
+    resources::{
+        AssistLevel, Feel, GameMode, GravityCurve, HighScore, JumpMode, PlayerStats, RunFlags,
+        Score, Settings, TimedHighScore,
+    },
+    AppState,
+};
+
+const HIGH_SCORE_FILE: &str = "high_score.ron";
+const TIMED_HIGH_SCORE_FILE: &str = "timed_high_score.ron";
+
+pub fn high_score_plugin(app: &mut App) {
+    app.insert_resource(persistence::load_signed::<HighScore>(HIGH_SCORE_FILE))
+        .insert_resource(persistence::load_signed::<TimedHighScore>(
+            TIMED_HIGH_SCORE_FILE,
+        ))
+        .init_resource::<RunFlags>()
+        .init_resource::<SaveStorage>()
+        .add_systems(OnEnter(AppState::GameStart), reset_run_flags)
+        .add_systems(OnEnter(AppState::GameOver), record_high_score);
+}
+
+fn reset_run_flags(
+    mode: Res<GameMode>,
+    settings: Res<Settings>,
+    stats: Res<PlayerStats>,
+    mut run_flags: ResMut<RunFlags>,
+) {
+    *run_flags = RunFlags {
+        custom_rules: !matches!(*mode, GameMode::Normal | GameMode::ScoreAttack)
+            || settings.smooth_gaps
+            || settings.shield_pickups,
+        handicap: settings.starting_score != 0,
+        assisted: settings.assist_guide || settings.trajectory_guide,
+        auto_flap: settings.auto_flap,
+        custom_feel: settings.feel != Feel::Classic
+            || settings.gravity_curve != GravityCurve::Classic
+            || settings.jump_mode != JumpMode::Reset
+            || settings.glide,
+        easy_assist: settings.assist_level != AssistLevel::None,
+        rescue_flap: settings.rescue_lookahead > 0.,
+        tutorial_save: stats.games_played < settings.tutorial_games,
+        extra_lives: settings.lives > 1,
+        start_grace: settings.start_grace > 0.,
+        bird_size: settings.bird_scale != 1.,
+        ..default()
+    };
+}
+
+// Keeps throwaway runs off the table, the player stats are counted elsewhere and still grow
+fn beats_high_score(score: i32, high_score: i32, min_saved_score: i32) -> bool {
+    score >= min_saved_score && score > high_score
+}
+
+fn record_high_score(
+    score: Res<Score>,
+    run_flags: Res<RunFlags>,
+    mode: Res<GameMode>,
+    settings: Res<Settings>,
+    mut high_score: ResMut<HighScore>,
+    mut timed_high_score: ResMut<TimedHighScore>,
+    storage: Res<SaveStorage>,
+) {
+    if !run_flags.is_ranked() {
+        info!("Run used training aids or custom rules, high score not recorded");
+        return;
+    }
+
+    if *mode == GameMode::ScoreAttack {
+        if beats_high_score(score.0, timed_high_score.0, settings.min_saved_score) {
+            timed_high_score.0 = score.0;
+            persistence::save_signed_to(
+                storage.0.as_ref(),
+                TIMED_HIGH_SCORE_FILE,
+                &*timed_high_score,
+            );
+        }
+        return;
+    }
+
+    if !beats_high_score(score.0, high_score.0, settings.min_saved_score) {
+        return;
+    }
+
+    high_score.0 = score.0;
+    persistence::save_signed_to(storage.0.as_ref(), HIGH_SCORE_FILE, &*high_score);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashMap,
+        sync::{Arc, Mutex},
+    };
+
+    use bevy::ecs::system::RunSystemOnce;
+
+    use super::*;
+    use crate::persistence::Storage;
+
+    // Keeps the files in memory, the test holds a second handle to look at them
+    #[derive(Clone, Default)]
+    struct MemoryStorage(Arc<Mutex<HashMap<String, String>>>);
+
+    impl Storage for MemoryStorage {
+        fn read(&self, file_name: &str) -> Option<String> {
+            self.0.lock().unwrap().get(file_name).cloned()
+        }
+
+        fn write(&self, file_name: &str, contents: &str) -> Result<(), String> {
+            self.0
+                .lock()
+                .unwrap()
+                .insert(file_name.into(), contents.into());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn runs_below_the_threshold_are_not_saved() {
+        assert!(beats_high_score(2, 0, 0));
+        assert!(!beats_high_score(2, 0, 3));
+        assert!(beats_high_score(3, 0, 3));
+        assert!(!beats_high_score(3, 5, 0));
+    }
+
+    #[test]
+    fn a_run_below_the_threshold_leaves_the_high_score_alone() {
+        let storage = MemoryStorage::default();
+        let mut world = World::new();
+        world.insert_resource(Score(2));
+        world.insert_resource(HighScore(0));
+        world.init_resource::<TimedHighScore>();
+        world.init_resource::<RunFlags>();
+        world.init_resource::<GameMode>();
+        world.insert_resource(Settings {
+            min_saved_score: 3,
+            ..default()
+        });
+        world.insert_resource(SaveStorage(Box::new(storage.clone())));
+
+        world.run_system_once(record_high_score).unwrap();
+        assert_eq!(world.resource::<HighScore>().0, 0);
+        assert!(storage.0.lock().unwrap().is_empty());
+
+        world.resource_mut::<Score>().0 = 3;
+        world.run_system_once(record_high_score).unwrap();
+        assert_eq!(world.resource::<HighScore>().0, 3);
+        assert!(storage.0.lock().unwrap().contains_key(HIGH_SCORE_FILE));
+    }
+}
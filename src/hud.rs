@@ -0,0 +1,431 @@
+use bevy::{color::palettes::css::ORANGE, prelude::*};
+
+use crate::{
+    components::{
+        Dead, DecayBarFill, Fallen, Heart, HudElement, HudFontSize, HudPanel, HudRoot, Lives,
+        ModeText, Player, ScoreText, Shield, StepModeText,
+    },
+    constants::*,
+    events::{LivesChangedEvent, ResetScoreEvent},
+    resources::{
+        AdaptiveState, AssistLevel, CustomModeSettings, DisplayedScore, Feel, GameConfig, GameMode,
+        HudLayout, HudPosition, HudState, PipePatterns, RetrySameSeed, Score, Settings,
+        StepControl,
+    },
+    AppState,
+};
+
+pub fn hud_plugin(app: &mut App) {
+    app.init_resource::<DisplayedScore>()
+        .init_resource::<HudState>()
+        .add_systems(Startup, spawn_hud)
+        .add_systems(
+            Update,
+            apply_hud_settings.run_if(resource_changed::<Settings>),
+        )
+        .add_systems(
+            OnEnter(AppState::GameStart),
+            (apply_hud_layout, reset_hud_state, update_mode_text),
+        )
+        .add_systems(Update, update_hud.run_if(resource_changed::<HudState>))
+        .add_systems(Update, (animate_score_text, update_step_text))
+        .add_observer(snap_score_on_reset)
+        .add_observer(count_dead_bird)
+        .add_observer(count_revived_bird)
+        .add_observer(count_lost_life)
+        .add_observer(fill_shield_icon)
+        .add_observer(empty_shield_icon);
+}
+
+fn spawn_hud(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let heart: Handle<Image> = asset_server.load(HEART_IMAGE);
+    let shield: Handle<Image> = asset_server.load(SHIELD_IMAGE);
+
+    commands.spawn((
+        HudRoot,
+        Node {
+            width: percent(100),
+            height: percent(100),
+            padding: UiRect::all(px(HUD_MARGIN)),
+            ..default()
+        },
+        children![(
+            HudPanel,
+            Node {
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            children![
+                (
+                    ScoreText,
+                    HudFontSize(HUD_FONT_SIZE),
+                    Text::new("0"),
+                    TextFont {
+                        font_size: HUD_FONT_SIZE,
+                        ..default()
+                    },
+                    TextColor(ORANGE.into()),
+                    TextLayout {
+                        justify: Justify::Center,
+                        ..default()
+                    },
+                ),
+                (
+                    ModeText,
+                    HudFontSize(HUD_FONT_SIZE / 2.),
+                    Text::new(""),
+                    TextFont {
+                        font_size: HUD_FONT_SIZE / 2.,
+                        ..default()
+                    },
+                    TextColor(ORANGE.into()),
+                ),
+                (
+                    HudElement::Flock,
+                    HudFontSize(HUD_FONT_SIZE / 2.),
+                    Text::new(""),
+                    TextFont {
+                        font_size: HUD_FONT_SIZE / 2.,
+                        ..default()
+                    },
+                    TextColor(ORANGE.into()),
+                    Node {
+                        display: Display::None,
+                        ..default()
+                    },
+                ),
+                (
+                    HudElement::Clock,
+                    HudFontSize(HUD_FONT_SIZE / 2.),
+                    Text::new(""),
+                    TextFont {
+                        font_size: HUD_FONT_SIZE / 2.,
+                        ..default()
+                    },
+                    TextColor(ORANGE.into()),
+                    Node {
+                        display: Display::None,
+                        ..default()
+                    },
+                ),
+                (
+                    HudElement::DecayBar,
+                    Node {
+                        display: Display::None,
+                        width: px(DECAY_BAR_SIZE.x),
+                        height: px(DECAY_BAR_SIZE.y),
+                        ..default()
+                    },
+                    BackgroundColor(Color::BLACK.with_alpha(0.4)),
+                    children![(
+                        DecayBarFill,
+                        Node {
+                            width: percent(100),
+                            height: percent(100),
+                            ..default()
+                        },
+                        BackgroundColor(ORANGE.into()),
+                    )],
+                ),
+                (
+                    StepModeText,
+                    HudFontSize(HUD_FONT_SIZE / 2.),
+                    Text::new(""),
+                    TextFont {
+                        font_size: HUD_FONT_SIZE / 2.,
+                        ..default()
+                    },
+                    TextColor(ORANGE.into()),
+                    Node {
+                        display: Display::None,
+                        ..default()
+                    },
+                ),
+                (
+                    HudElement::Lives,
+                    Node {
+                        display: Display::None,
+                        column_gap: px(HEART_SIZE / 4.),
+                        ..default()
+                    },
+                    Children::spawn(SpawnIter((0..MAX_LIVES).map(move |index| {
+                        (
+                            Heart(index),
+                            ImageNode::new(heart.clone()),
+                            Node {
+                                width: px(HEART_SIZE),
+                                height: px(HEART_SIZE),
+                                ..default()
+                            },
+                        )
+                    }))),
+                ),
+                (
+                    HudElement::Shield,
+                    ImageNode::new(shield)
+                        .with_color(Color::WHITE.with_alpha(SHIELD_ICON_EMPTY_ALPHA)),
+                    Node {
+                        display: Display::None,
+                        width: px(HEART_SIZE),
+                        height: px(HEART_SIZE),
+                        ..default()
+                    },
+                ),
+            ],
+        )],
+    ));
+}
+
+// The HUD root spans the whole window, so flex alignment keeps the HUD anchored on resize.
+fn apply_hud_settings(
+    settings: Res<Settings>,
+    mut root_query: Query<&mut Node, With<HudRoot>>,
+    mut text_query: Query<(&mut TextFont, &HudFontSize)>,
+) {
+    let (justify_content, align_items) = hud_alignment(settings.hud_position);
+    for mut node in root_query.iter_mut() {
+        node.justify_content = justify_content;
+        node.align_items = align_items;
+    }
+
+    for (mut font, base_size) in text_query.iter_mut() {
+        font.font_size = base_size.0 * settings.hud_scale;
+    }
+}
+
+fn hud_alignment(position: HudPosition) -> (JustifyContent, AlignItems) {
+    match position {
+        HudPosition::TopLeft => (JustifyContent::FlexStart, AlignItems::FlexStart),
+        HudPosition::TopCenter => (JustifyContent::Center, AlignItems::FlexStart),
+        HudPosition::TopRight => (JustifyContent::FlexEnd, AlignItems::FlexStart),
+        HudPosition::BottomLeft => (JustifyContent::FlexStart, AlignItems::FlexEnd),
+        HudPosition::BottomCenter => (JustifyContent::Center, AlignItems::FlexEnd),
+        HudPosition::BottomRight => (JustifyContent::FlexEnd, AlignItems::FlexEnd),
+    }
+}
+
+fn snap_score_on_reset(
+    reset_event: On<ResetScoreEvent>,
+    mut displayed_score: ResMut<DisplayedScore>,
+) {
+    displayed_score.0 = reset_event.starting_score as f32;
+}
+
+fn animate_score_text(
+    time: Res<Time>,
+    score: Res<Score>,
+    mut displayed_score: ResMut<DisplayedScore>,
+    mut query: Query<&mut Text, With<ScoreText>>,
+) {
+    let target = score.0 as f32;
+    let remaining = target - displayed_score.0;
+    if remaining.abs() < 0.01 {
+        displayed_score.0 = target;
+    } else {
+        // Exponential approach that covers ~98% of the gap in SCORE_COUNT_DURATION
+        let blend = 1. - (-4. * time.delta_secs() / SCORE_COUNT_DURATION).exp();
+        displayed_score.0 += remaining * blend;
+    }
+
+    let displayed_text = format!("{}", displayed_score.0.round() as i32);
+    for mut text in query.iter_mut() {
+        if text.0 != displayed_text {
+            text.0 = displayed_text.clone();
+        }
+    }
+}
+
+fn apply_hud_layout(
+    mode: Res<GameMode>,
+    settings: Res<Settings>,
+    mut query: Query<(&HudElement, &mut Node)>,
+) {
+    let layout = HudLayout::for_mode(*mode, settings.lives, settings.shield_pickups);
+    for (element, mut node) in query.iter_mut() {
+        node.display = if layout.shows(*element) {
+            Display::Flex
+        } else {
+            Display::None
+        };
+    }
+}
+
+// Birds are spawned by commands on the same transition, so the state starts from the config
+fn reset_hud_state(
+    config: Res<GameConfig>,
+    settings: Res<Settings>,
+    mut hud_state: ResMut<HudState>,
+) {
+    *hud_state = HudState {
+        birds_alive: config.bird_count as usize,
+        birds_total: config.bird_count as usize,
+        lives: settings.lives.max(1),
+        clock_seconds: SCORE_ATTACK_DURATION.ceil() as u32,
+        decay_remaining: 1.,
+        shield: false,
+    };
+}
+
+// `reviving` is a bird that is about to lose Dead but still has it while its observer runs.
+// In flock mode the hearts follow the bird with the most lives left, and the total stays the
+// flock size once fallen birds are despawned
+fn recount_birds(
+    hud_state: &mut HudState,
+    player_query: &Query<(Entity, &Lives, Has<Dead>), With<Player>>,
+    reviving: Option<Entity>,
+) {
+    let living_lives: Vec<u32> = player_query
+        .iter()
+        .filter(|&(entity, _, is_dead)| !is_dead || Some(entity) == reviving)
+        .map(|(_, lives, _)| lives.0)
+        .collect();
+    hud_state.birds_alive = living_lives.len();
+    hud_state.lives = living_lives.into_iter().max().unwrap_or(0);
+}
+
+fn count_dead_bird(
+    _dead: On<Add, Dead>,
+    player_query: Query<(Entity, &Lives, Has<Dead>), With<Player>>,
+    mut hud_state: ResMut<HudState>,
+) {
+    recount_birds(&mut hud_state, &player_query, None);
+}
+
+// Despawning a fallen bird removes its Dead too, that one stays dead
+fn count_revived_bird(
+    revived: On<Remove, Dead>,
+    player_query: Query<(Entity, &Lives, Has<Dead>), With<Player>>,
+    fallen_query: Query<(), With<Fallen>>,
+    mut hud_state: ResMut<HudState>,
+) {
+    let reviving = (!fallen_query.contains(revived.entity)).then_some(revived.entity);
+    recount_birds(&mut hud_state, &player_query, reviving);
+}
+
+fn count_lost_life(
+    _lives_event: On<LivesChangedEvent>,
+    player_query: Query<(Entity, &Lives, Has<Dead>), With<Player>>,
+    mut hud_state: ResMut<HudState>,
+) {
+    recount_birds(&mut hud_state, &player_query, None);
+}
+
+fn fill_shield_icon(_shield: On<Add, Shield>, mut hud_state: ResMut<HudState>) {
+    hud_state.shield = true;
+}
+
+// In flock mode the icon stays full while any bird still has its shield
+fn empty_shield_icon(
+    removed: On<Remove, Shield>,
+    shield_query: Query<Entity, With<Shield>>,
+    mut hud_state: ResMut<HudState>,
+) {
+    hud_state.shield = shield_query.iter().any(|entity| entity != removed.entity);
+}
+
+// The one place HUD stats are written, hidden elements are updated too so they are current
+// when a layout shows them
+fn update_hud(
+    hud_state: Res<HudState>,
+    mut element_query: Query<(&HudElement, &mut Text)>,
+    mut heart_query: Query<(&Heart, &mut Visibility)>,
+    mut decay_fill_query: Query<&mut Node, With<DecayBarFill>>,
+    mut icon_query: Query<(&HudElement, &mut ImageNode)>,
+) {
+    for (element, mut text) in element_query.iter_mut() {
+        match element {
+            HudElement::Flock => {
+                text.0 = format!("Birds: {}/{}", hud_state.birds_alive, hud_state.birds_total);
+            }
+            HudElement::Clock => {
+                let seconds = hud_state.clock_seconds;
+                text.0 = format!("Time: {}:{:02}", seconds / 60, seconds % 60);
+            }
+            HudElement::Lives | HudElement::DecayBar | HudElement::Shield => {}
+        }
+    }
+
+    for mut node in decay_fill_query.iter_mut() {
+        node.width = percent(hud_state.decay_remaining * 100.);
+    }
+
+    for (element, mut image) in icon_query.iter_mut() {
+        if *element == HudElement::Shield {
+            let alpha = if hud_state.shield {
+                1.
+            } else {
+                SHIELD_ICON_EMPTY_ALPHA
+            };
+            image.color.set_alpha(alpha);
+        }
+    }
+
+    for (heart, mut visibility) in heart_query.iter_mut() {
+        *visibility = if heart.0 < hud_state.lives {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+    }
+}
+
+fn update_mode_text(
+    mode: Res<GameMode>,
+    adaptive_state: Res<AdaptiveState>,
+    settings: Res<Settings>,
+    patterns: Res<PipePatterns>,
+    custom_mode: Res<CustomModeSettings>,
+    retry_same_seed: Res<RetrySameSeed>,
+    mut query: Query<&mut Text, With<ModeText>>,
+) {
+    let mode_label = match *mode {
+        GameMode::Normal => String::new(),
+        GameMode::Custom => "Custom".into(),
+        GameMode::Flock => "Flock".into(),
+        GameMode::Adaptive => format!("Adaptive {:+.1}", adaptive_state.level),
+        GameMode::Practice => "Practice - Backspace rewinds".into(),
+        GameMode::Pattern => patterns
+            .0
+            .get(custom_mode.pattern)
+            .map_or_else(String::new, |pattern| pattern.name.clone()),
+        GameMode::Scrolling => "Tall world".into(),
+        GameMode::ScoreAttack => "Score attack".into(),
+        GameMode::ScoreDecay => "Score decay".into(),
+    };
+
+    let mut parts = vec![mode_label];
+    if settings.feel != Feel::Classic {
+        parts.push(format!("{:?}", settings.feel));
+    }
+    if settings.assist_level != AssistLevel::None {
+        parts.push(format!("{:?} assist", settings.assist_level));
+    }
+    if retry_same_seed.0 {
+        parts.push("Same pipes as last run".into());
+    }
+    parts.retain(|part| !part.is_empty());
+    let label = parts.join(" - ");
+
+    for mut text in query.iter_mut() {
+        text.0 = label.clone();
+    }
+}
+
+fn update_step_text(
+    step_control: Res<StepControl>,
+    mut query: Query<(&mut Text, &mut Node), With<StepModeText>>,
+) {
+    if !step_control.is_changed() {
+        return;
+    }
+
+    for (mut text, mut node) in query.iter_mut() {
+        if step_control.enabled {
+            node.display = Display::Flex;
+            text.0 = format!("STEP MODE - tick {}", step_control.tick);
+        } else {
+            node.display = Display::None;
+        }
+    }
+}
@@ -0,0 +1,42 @@
+use bevy::{diagnostic::FrameCount, prelude::*};
+
+use crate::{
+    components::LivingBird,
+    events::JumpEvent,
+    resources::{DebugFlags, RunTimer, StepControl},
+    AppState,
+};
+
+pub fn input_log_plugin(app: &mut App) {
+    app.init_resource::<DebugFlags>().add_observer(log_jump);
+}
+
+// Mirrors the filter in apply_jump_velocity to report whether the flap reached a bird
+fn log_jump(
+    jump_event: On<JumpEvent>,
+    flags: Res<DebugFlags>,
+    frame_count: Res<FrameCount>,
+    step_control: Res<StepControl>,
+    run_timer: Res<RunTimer>,
+    state: Res<State<AppState>>,
+    player_query: Query<(), LivingBird>,
+) {
+    if !flags.log_input {
+        return;
+    }
+
+    let consumed = !player_query.is_empty();
+    info!(
+        "jump from {:?}: frame {}, tick {}, run time {:.3}s, state {:?}, {}",
+        jump_event.source,
+        frame_count.0,
+        step_control.tick,
+        run_timer.0.elapsed_secs(),
+        state.get(),
+        if consumed {
+            "applied"
+        } else {
+            "dropped, no living bird"
+        }
+    );
+}
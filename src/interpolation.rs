@@ -0,0 +1,74 @@
+use bevy::prelude::*;
+
+use crate::components::FixedInterpolation;
+
+pub fn interpolation_plugin(app: &mut App) {
+    app.add_systems(FixedFirst, remember_previous_positions)
+        .add_systems(FixedLast, remember_current_positions)
+        .add_systems(
+            Last,
+            (interpolate_physics_positions, repropagate_next_frame).chain(),
+        );
+}
+
+fn remember_previous_positions(mut query: Query<(&Transform, &mut FixedInterpolation)>) {
+    for (transform, mut interpolation) in query.iter_mut() {
+        interpolation.previous = Some(transform.translation);
+    }
+}
+
+fn remember_current_positions(mut query: Query<(&Transform, &mut FixedInterpolation)>) {
+    for (transform, mut interpolation) in query.iter_mut() {
+        interpolation.current = Some(transform.translation);
+    }
+}
+
+// Transform keeps the exact physics value for the next tick and for collision detection, only
+// the rendered GlobalTransform of the entity and its children is drawn between the last two ticks
+pub fn interpolate_physics_positions(
+    fixed_time: Res<Time<Fixed>>,
+    root_query: Query<(Entity, &Transform, &FixedInterpolation), Without<ChildOf>>,
+    mut node_query: Query<(&Transform, &mut GlobalTransform, Option<&Children>)>,
+) {
+    let overstep = fixed_time.overstep_fraction();
+    for (entity, transform, interpolation) in root_query.iter() {
+        let (Some(previous), Some(current)) = (interpolation.previous, interpolation.current)
+        else {
+            continue;
+        };
+        // Moved outside the fixed loop, by a rewind or the death replay, so it is drawn where it is
+        if transform.translation != current {
+            continue;
+        }
+
+        let shown = Transform {
+            translation: previous.lerp(current, overstep),
+            ..*transform
+        };
+        let mut pending = vec![(entity, GlobalTransform::from(shown))];
+        while let Some((node, global_transform)) = pending.pop() {
+            let Ok((_, mut node_global_transform, children)) = node_query.get_mut(node) else {
+                continue;
+            };
+            *node_global_transform = global_transform;
+            let children: Vec<Entity> = children
+                .map(|children| children.to_vec())
+                .unwrap_or_default();
+            for child in children {
+                if let Ok((child_transform, ..)) = node_query.get(child) {
+                    pending.push((child, global_transform.mul_transform(*child_transform)));
+                }
+            }
+        }
+    }
+}
+
+// Propagation skips entities whose Transform did not change, without this a frame with no fixed
+// tick would leave the drawn position in GlobalTransform for collision detection to read
+fn repropagate_next_frame(
+    mut query: Query<&mut Transform, (With<FixedInterpolation>, Without<ChildOf>)>,
+) {
+    for mut transform in query.iter_mut() {
+        transform.set_changed();
+    }
+}
@@ -0,0 +1,55 @@
+use std::f32::consts::PI;
+
+use bevy::prelude::*;
+
+use crate::{
+    components::{FirstFrameGuard, Invincible, Player},
+    constants::INVINCIBLE_FLASH_FREQUENCY,
+    resources::Settings,
+    AppState,
+};
+
+pub fn invincible_plugin(app: &mut App) {
+    app.add_systems(OnEnter(AppState::InGame), guard_first_frame)
+        .add_systems(Update, flash_invincible_players)
+        .add_systems(Last, end_first_frame_guard);
+}
+
+// A pipe overlapping the bird on the frame the run starts must not kill it before anything
+// was drawn. The guard is lifted in Last and leaves any real Invincible window alone
+fn guard_first_frame(mut commands: Commands, query: Query<Entity, With<Player>>) {
+    for entity in query.iter() {
+        commands.entity(entity).insert(FirstFrameGuard);
+    }
+}
+
+fn end_first_frame_guard(mut commands: Commands, query: Query<Entity, With<FirstFrameGuard>>) {
+    for entity in query.iter() {
+        commands.entity(entity).remove::<FirstFrameGuard>();
+    }
+}
+
+// Virtual time stops while paused, so the window does not run out in the pause menu.
+// Reduced motion dims the bird for the whole window instead of flashing it
+fn flash_invincible_players(
+    time: Res<Time>,
+    settings: Res<Settings>,
+    mut commands: Commands,
+    mut query: Query<(Entity, &mut Invincible, &mut Sprite), With<Player>>,
+) {
+    for (entity, mut invincible, mut sprite) in query.iter_mut() {
+        if invincible.0.tick(time.delta()).just_finished() {
+            sprite.color.set_alpha(1.);
+            commands.entity(entity).remove::<Invincible>();
+            continue;
+        }
+
+        let alpha = if settings.reduced_motion {
+            0.5
+        } else {
+            let wave_position = 2. * PI * INVINCIBLE_FLASH_FREQUENCY * invincible.0.elapsed_secs();
+            0.6 + 0.4 * wave_position.cos()
+        };
+        sprite.color.set_alpha(alpha);
+    }
+}
@@ -0,0 +1,178 @@
+use bevy::prelude::*;
+
+use crate::{
+    actions::{Action, ActionState},
+    assist::nearest_upcoming_pipe,
+    components::{KioskText, LivingBird, Pipe, Velocity},
+    demo::autopilot_should_flap,
+    events::{JumpEvent, JumpSource},
+    resources::{KioskState, RetrySameSeed, RunFlags, Settings},
+    AppState, PauseState,
+};
+
+pub fn kiosk_plugin(app: &mut App) {
+    app.init_resource::<KioskState>()
+        .add_systems(Startup, spawn_kiosk_text)
+        .add_systems(
+            OnEnter(AppState::GameStart),
+            (stop_kiosk_autopilot, reset_kiosk_countdown),
+        )
+        .add_systems(OnEnter(AppState::GameOver), reset_kiosk_countdown)
+        .add_systems(
+            Update,
+            (
+                auto_launch.run_if(in_state(AppState::GameStart).and(auto_restart_enabled)),
+                kiosk_autopilot.run_if(in_state(PauseState::Running)),
+                auto_restart.run_if(in_state(AppState::GameOver).and(auto_restart_enabled)),
+                update_kiosk_text,
+            ),
+        );
+}
+
+fn auto_restart_enabled(settings: Res<Settings>) -> bool {
+    settings.auto_restart
+}
+
+fn spawn_kiosk_text(mut commands: Commands) {
+    commands.spawn((
+        KioskText,
+        Text::default(),
+        TextFont {
+            font_size: 16.0,
+            ..default()
+        },
+        TextColor(Color::WHITE.with_alpha(0.7)),
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: px(5),
+            width: percent(100),
+            justify_content: JustifyContent::Center,
+            ..default()
+        },
+        TextLayout {
+            justify: Justify::Center,
+            ..default()
+        },
+        Visibility::Hidden,
+        Pickable::IGNORE,
+    ));
+}
+
+fn stop_kiosk_autopilot(mut kiosk: ResMut<KioskState>) {
+    kiosk.autopilot = false;
+}
+
+fn reset_kiosk_countdown(settings: Res<Settings>, mut kiosk: ResMut<KioskState>) {
+    kiosk.countdown = Timer::from_seconds(settings.auto_restart_delay, TimerMode::Once);
+}
+
+// Anyone touching the controls has the screen to themselves, the countdown starts over
+fn tick_kiosk_countdown(kiosk: &mut KioskState, actions: &ActionState, time: &Time<Real>) -> bool {
+    if Action::ALL.iter().any(|&action| actions.pressed(action)) {
+        kiosk.countdown.reset();
+        return false;
+    }
+    kiosk.countdown.tick(time.delta()).is_finished()
+}
+
+// An idle get-ready screen launches the run for the autopilot, the same way a flap would
+fn auto_launch(
+    time: Res<Time<Real>>,
+    actions: Res<ActionState>,
+    mut kiosk: ResMut<KioskState>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut commands: Commands,
+) {
+    if !tick_kiosk_countdown(&mut kiosk, &actions, &time)
+        || !matches!(*next_state, NextState::Unchanged)
+    {
+        return;
+    }
+
+    kiosk.autopilot = true;
+    commands.trigger(JumpEvent {
+        source: JumpSource::Autopilot,
+    });
+    next_state.set(AppState::InGame);
+}
+
+// The player's own flap takes over for the rest of the run, player_input already handles it
+fn kiosk_autopilot(
+    actions: Res<ActionState>,
+    mut kiosk: ResMut<KioskState>,
+    mut run_flags: ResMut<RunFlags>,
+    player_query: Query<(&Transform, &Velocity), LivingBird>,
+    pipe_query: Query<&Transform, With<Pipe>>,
+    mut commands: Commands,
+) {
+    if !kiosk.autopilot {
+        return;
+    }
+    if actions.just_pressed(Action::Flap) {
+        kiosk.autopilot = false;
+        return;
+    }
+    run_flags.autopilot = true;
+
+    let wants_flap = player_query.iter().any(|(transform, velocity)| {
+        let position = transform.translation.truncate();
+        let gap_center = nearest_upcoming_pipe(position.x, pipe_query.iter())
+            .unwrap_or(Vec2::new(position.x, 0.));
+        autopilot_should_flap(position, velocity.0, gap_center)
+    });
+
+    if wants_flap {
+        commands.trigger(JumpEvent {
+            source: JumpSource::Autopilot,
+        });
+    }
+}
+
+// Takes the same path as a manual restart, so the score and the pipe spawn timer reset with
+// GameStart and InGame as usual
+fn auto_restart(
+    time: Res<Time<Real>>,
+    actions: Res<ActionState>,
+    mut kiosk: ResMut<KioskState>,
+    mut retry_same_seed: ResMut<RetrySameSeed>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    if !tick_kiosk_countdown(&mut kiosk, &actions, &time)
+        || !matches!(*next_state, NextState::Unchanged)
+    {
+        return;
+    }
+
+    retry_same_seed.0 = false;
+    next_state.set(AppState::GameStart);
+}
+
+fn update_kiosk_text(
+    settings: Res<Settings>,
+    state: Res<State<AppState>>,
+    kiosk: Res<KioskState>,
+    text: Single<(&mut Text, &mut Visibility), With<KioskText>>,
+) {
+    let (mut text, mut visibility) = text.into_inner();
+    let shown = settings.auto_restart && *state.get() != AppState::MainMenu;
+    visibility.set_if_neq(if shown {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    });
+    if !shown {
+        return;
+    }
+
+    let label = match state.get() {
+        AppState::GameOver => format!(
+            "Auto-restart in {:.0}s",
+            kiosk.countdown.remaining_secs().ceil()
+        ),
+        AppState::InGame if kiosk.autopilot => "Demo play, flap to take over".into(),
+        _ => "Auto-restart on".into(),
+    };
+    if text.0 != label {
+        text.0 = label;
+    }
+}
@@ -0,0 +1,50 @@
+use bevy::prelude::*;
+
+use crate::constants::{BACKGROUND_Z, GHOST_Z, GROUND_Z, PARTICLE_Z, PIPE_Z, PLAYER_Z};
+
+pub fn layers_plugin(app: &mut App) {
+    app.init_resource::<ZLayers>();
+}
+
+// Sprite depth for everything the game spawns, back to front:
+// background < pipes < ground < replay ghost < player < particles.
+// Pipes sit behind the ground so they appear to grow out of it, and the bird flies in front
+// of both. Spawn sites read this instead of the constants, so a mod can insert its own
+// `ZLayers` before the first spawn to slot extra sprites in between.
+#[derive(Resource, Clone, Copy, Debug, PartialEq)]
+pub struct ZLayers {
+    pub background: f32,
+    pub pipe: f32,
+    pub ground: f32,
+    pub ghost: f32,
+    pub player: f32,
+    pub particle: f32,
+}
+
+impl Default for ZLayers {
+    fn default() -> Self {
+        Self {
+            background: BACKGROUND_Z,
+            pipe: PIPE_Z,
+            ground: GROUND_Z,
+            ghost: GHOST_Z,
+            player: PLAYER_Z,
+            particle: PARTICLE_Z,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_layers_stack_player_over_ground_over_pipes_over_background() {
+        let layers = ZLayers::default();
+        assert!(layers.player > layers.ground);
+        assert!(layers.ground > layers.pipe);
+        assert!(layers.pipe > layers.background);
+        assert!(layers.ghost > layers.ground && layers.ghost < layers.player);
+        assert!(layers.particle > layers.player);
+    }
+}
@@ -1,21 +1,195 @@
 use bevy::prelude::*;
 
-use crate::animation::animation_plugin;
+use crate::{
+    actions::actions_plugin, adaptive::adaptive_plugin, altitude_graph::altitude_graph_plugin,
+    animation::animation_plugin, antialiasing::antialiasing_plugin,
+    asset_variants::asset_variants_plugin, assist::assist_plugin, best_run::best_run_plugin,
+    camera::camera_plugin, clean_capture::clean_capture_plugin, cleanup::cleanup_plugin,
+    collision::collision_plugin, config::config_plugin, controller::controller_plugin,
+    death_replay::death_replay_plugin, demo::demo_plugin, display::display_plugin,
+    event_log::event_log_plugin, get_ready::get_ready_plugin, golden_pipe::golden_pipe_plugin,
+    gravity::gravity_plugin, high_score::high_score_plugin, hud::hud_plugin,
+    input_log::input_log_plugin, interpolation::interpolation_plugin,
+    invincible::invincible_plugin, kiosk::kiosk_plugin, layers::layers_plugin, menu::menu_plugin,
+    milestones::milestones_plugin, music::music_plugin, obstacles::obstacles_plugin,
+    parallax::parallax_plugin, particles::particles_plugin, pattern::pattern_plugin,
+    pause::pause_plugin, perfect_run::perfect_run_plugin, pipe_preview::pipe_preview_plugin,
+    pixel_snap::pixel_snap_plugin, quiet_hours::quiet_hours_plugin, replay::replay_plugin,
+    rewind::rewind_plugin, rng::rng_plugin, rumble::rumble_plugin, run_log::run_log_plugin,
+    score_attack::score_attack_plugin, score_decay::score_decay_plugin,
+    scoring_debug::scoring_debug_plugin, seed_display::seed_display_plugin,
+    settings::settings_plugin, shield::shield_plugin, skins::skins_plugin, step::step_plugin,
+    theme::theme_plugin, time_scale::time_scale_plugin, tutorial::tutorial_plugin,
+    vignette::vignette_plugin,
+};
 
+pub mod actions;
+pub mod adaptive;
+pub mod altitude_graph;
 pub mod animation;
+pub mod antialiasing;
+#[cfg(feature = "debug")]
+pub mod asset_status;
+pub mod asset_variants;
+pub mod assist;
+pub mod bench;
+pub mod best_run;
+pub mod branding;
+pub mod broadphase;
+pub mod camera;
+pub mod clean_capture;
+pub mod cleanup;
+pub mod clipboard;
+pub mod collision;
 pub mod components;
+pub mod config;
 pub mod constants;
+pub mod controller;
+pub mod death_replay;
+pub mod demo;
+pub mod display;
+pub mod event_log;
 pub mod events;
+pub mod get_ready;
+pub mod golden_pipe;
+pub mod gravity;
+pub mod high_score;
+pub mod hud;
+pub mod input_log;
+pub mod interpolation;
+pub mod invincible;
+pub mod kiosk;
+pub mod layers;
+pub mod menu;
+pub mod milestones;
+pub mod music;
+pub mod obstacles;
+pub mod parallax;
+pub mod particles;
+pub mod pattern;
+pub mod pause;
+pub mod perfect_run;
+pub mod persistence;
+pub mod pipe_preview;
+pub mod pipes;
+pub mod pixel_snap;
+pub mod practice_section;
+pub mod quiet_hours;
+pub mod replay;
 pub mod resources;
+pub mod rewind;
+pub mod rng;
+pub mod rumble;
+pub mod run_log;
+pub mod score_attack;
+pub mod score_decay;
+pub mod scoring_debug;
+pub mod seed_display;
+pub mod settings;
+pub mod shield;
+pub mod skins;
+pub mod step;
+pub mod theme;
+pub mod time_scale;
+pub mod tutorial;
+pub mod vignette;
 
 #[derive(Debug, Clone, Default, Eq, PartialEq, Hash, States)]
 pub enum AppState {
     #[default]
+    MainMenu,
     GameStart,
     InGame,
+    DeathReplay,
     GameOver,
 }
 
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash, SubStates)]
+#[source(AppState = AppState::MainMenu)]
+pub enum MenuScreen {
+    #[default]
+    Main,
+    Settings,
+    Custom,
+}
+
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash, SubStates)]
+#[source(AppState = AppState::InGame)]
+pub enum PauseState {
+    #[default]
+    Running,
+    Paused,
+}
+
 pub fn flappy_bird_plugin(app: &mut App) {
-    app.add_plugins(animation_plugin);
+    app.add_sub_state::<MenuScreen>()
+        .add_sub_state::<PauseState>()
+        .add_plugins((
+            actions_plugin,
+            animation_plugin,
+            settings_plugin,
+            config_plugin,
+            rng_plugin,
+            hud_plugin,
+            menu_plugin,
+            pause_plugin,
+            high_score_plugin,
+            particles_plugin,
+            parallax_plugin,
+            shield_plugin,
+            interpolation_plugin,
+        ))
+        .add_plugins((
+            replay_plugin,
+            time_scale_plugin,
+            golden_pipe_plugin,
+            altitude_graph_plugin,
+            assist_plugin,
+            adaptive_plugin,
+            step_plugin,
+            get_ready_plugin,
+            asset_variants_plugin,
+            rumble_plugin,
+            demo_plugin,
+            theme_plugin,
+            rewind_plugin,
+            skins_plugin,
+            perfect_run_plugin,
+        ))
+        .add_plugins((
+            cleanup_plugin,
+            milestones_plugin,
+            display_plugin,
+            pixel_snap_plugin,
+            input_log_plugin,
+            pattern_plugin,
+            run_log_plugin,
+            invincible_plugin,
+            camera_plugin,
+            antialiasing_plugin,
+            pipe_preview_plugin,
+            collision_plugin,
+            music_plugin,
+            scoring_debug_plugin,
+            controller_plugin,
+        ))
+        .add_plugins((
+            seed_display_plugin,
+            clean_capture_plugin,
+            layers_plugin,
+            score_attack_plugin,
+            obstacles_plugin,
+            tutorial_plugin,
+            death_replay_plugin,
+            quiet_hours_plugin,
+            score_decay_plugin,
+            best_run_plugin,
+            kiosk_plugin,
+            gravity_plugin,
+            vignette_plugin,
+            event_log_plugin,
+        ));
+
+    #[cfg(feature = "debug")]
+    app.add_plugins(asset_status::asset_status_plugin);
 }
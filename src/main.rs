@@ -1,34 +1,118 @@
 use bevy::{
-    color::palettes::css::{GREEN, ORANGE, RED},
+    app::ScheduleRunnerPlugin,
+    audio::Volume,
+    color::palettes::css::{GREEN, RED, YELLOW},
     diagnostic::{FrameTimeDiagnosticsPlugin, LogDiagnosticsPlugin},
-    math::bounding::{Aabb2d, IntersectsVolume},
+    math::bounding::{Aabb2d, BoundingVolume, IntersectsVolume},
     prelude::*,
-    window::WindowMode,
+    render::{settings::WgpuSettings, RenderPlugin},
+    window::{ExitCondition, WindowMode},
+    winit::WinitPlugin,
 };
 use flappy_bird::{
-    components::*, constants::*, events::*, flappy_bird_plugin, resources::*, AppState,
+    actions::{Action, ActionState},
+    asset_variants::image_variant,
+    assist::pipe_collider_box,
+    bench::bench_plugin,
+    branding::{branding_plugin, Branding},
+    broadphase::Broadphase,
+    collision::{collision_side, player_hitbox, swept_gate_box},
+    components::*,
+    constants::*,
+    events::*,
+    flappy_bird_plugin,
+    gravity::{fall_acceleration, fall_step},
+    layers::ZLayers,
+    obstacles::{ActiveObstacleSpawner, SpawnContext},
+    parallax::{spawn_parallax_layers, ParallaxConfig},
+    pipe_preview::predict_pipes,
+    pipes::{opening_bounds, world_top},
+    practice_section::PracticeSection,
+    resources::*,
+    step::physics_running,
+    AppState, PauseState,
 };
-use rand::Rng;
-use std::f32::consts::PI;
+use std::{f32::consts::PI, time::Duration};
 
 fn main() {
-    App::new()
-        .add_plugins(DefaultPlugins.set(WindowPlugin {
-            primary_window: Some(Window {
-                title: "Flappy Bird".into(),
-                mode: WindowMode::Windowed,
-                focused: true,
-                position: WindowPosition::Centered(MonitorSelection::Primary),
+    let mut app = App::new();
+    let branding = Branding::built_in();
+
+    match bench_duration() {
+        // No window and no GPU, the renderer is built without a backend so the bench runs on
+        // machines without a display
+        Some(duration) => {
+            app.add_plugins((
+                DefaultPlugins
+                    .set(WindowPlugin {
+                        primary_window: None,
+                        exit_condition: ExitCondition::DontExit,
+                        ..default()
+                    })
+                    .set(RenderPlugin {
+                        render_creation: WgpuSettings {
+                            backends: None,
+                            ..default()
+                        }
+                        .into(),
+                        ..default()
+                    })
+                    .disable::<WinitPlugin>(),
+                ScheduleRunnerPlugin::run_loop(Duration::ZERO),
+            ))
+            .insert_resource(BenchConfig { duration })
+            .add_plugins(bench_plugin);
+        }
+        None => {
+            app.add_plugins(DefaultPlugins.set(WindowPlugin {
+                primary_window: Some(Window {
+                    title: branding.title.clone(),
+                    mode: WindowMode::Windowed,
+                    focused: true,
+                    position: WindowPosition::Centered(MonitorSelection::Primary),
+                    ..default()
+                }),
                 ..default()
-            }),
-            ..default()
-        }))
-        .add_plugins(LogDiagnosticsPlugin::default())
-        .add_plugins(FrameTimeDiagnosticsPlugin::default())
-        .init_state::<AppState>()
+            }))
+            .add_plugins(LogDiagnosticsPlugin::default())
+            .add_plugins(FrameTimeDiagnosticsPlugin::default())
+            .add_plugins(branding_plugin);
+        }
+    }
+
+    app.init_state::<AppState>()
+        .insert_resource(branding)
+        .insert_resource(DebugFlags {
+            log_input: std::env::args().any(|arg| arg == "--log-input"),
+            log_scoring: std::env::args().any(|arg| arg == "--log-scoring"),
+            asset_status: std::env::args().any(|arg| arg == "--asset-status"),
+            show_colliders: std::env::args().any(|arg| arg == "--show-colliders"),
+        })
+        .init_resource::<RunTimer>()
+        .init_resource::<StartGrace>()
+        .insert_resource(AutoFlapTimer(Timer::from_seconds(
+            AUTO_FLAP_INTERVAL,
+            TimerMode::Repeating,
+        )))
         .add_plugins(flappy_bird_plugin)
         .add_systems(Startup, setup)
-        .add_systems(OnEnter(AppState::GameStart), spawn_player)
+        .add_systems(
+            OnEnter(AppState::GameStart),
+            (
+                spawn_player,
+                reset_run_timer,
+                reset_start_grace,
+                start_new_score,
+            ),
+        )
+        .add_systems(
+            OnEnter(AppState::InGame),
+            (
+                reset_pipe_spawn_timer,
+                jump_to_practice_section.run_if(resource_equals(GameMode::Practice)),
+            )
+                .chain(),
+        )
         .add_systems(
             Update,
             (trigger_game_start, idle_player_movement).run_if(in_state(AppState::GameStart)),
@@ -37,27 +121,63 @@ fn main() {
             Update,
             (
                 player_input,
+                count_pipes,
+                despawn_pipes,
+                despawn_fallen_birds,
+                animate_pipe_spawn,
+            )
+                .run_if(in_state(PauseState::Running)),
+        )
+        .add_systems(
+            FixedUpdate,
+            (
+                tick_run_timer,
                 apply_gravity,
+                maintain_player_x,
                 pipe_spawner,
                 pipe_movement,
-                count_pipes,
-                despawn_pipes,
             )
-                .run_if(in_state(AppState::InGame)),
+                .run_if(in_state(PauseState::Running).and(physics_running)),
+        )
+        .add_systems(
+            Update,
+            match_spawn_interval.run_if(resource_changed::<GameConfig>),
+        )
+        .add_systems(
+            Update,
+            (draw_colliders, draw_ground_line).run_if(show_colliders_enabled),
         )
-        .add_systems(Update, (draw_colliders,))
         .add_systems(
             PostUpdate,
-            detect_collision.run_if(in_state(AppState::InGame)),
+            (detect_collision, track_gate_positions)
+                .chain()
+                .run_if(in_state(PauseState::Running)),
         )
         .add_systems(Update, game_over_input.run_if(in_state(AppState::GameOver)))
-        .add_observer(update_score)
-        .add_observer(update_score_text)
+        .add_observer(reset_score)
+        .add_observer(increment_score)
         .add_observer(apply_jump_velocity)
         .run();
 }
 
-fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
+// `--bench [seconds]` runs the autopilot headless for the given simulated time
+fn bench_duration() -> Option<f32> {
+    let mut args = std::env::args().skip_while(|arg| arg != "--bench");
+    args.next()?;
+    Some(
+        args.next()
+            .and_then(|seconds| seconds.parse().ok())
+            .unwrap_or(BENCH_DEFAULT_SECONDS),
+    )
+}
+
+fn setup(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    config: Res<GameConfig>,
+    parallax: Res<ParallaxConfig>,
+    hidpi: Res<HiDpi>,
+) {
     commands.insert_resource(Score(0));
     commands.insert_resource(PipeSpawnTimer(Timer::from_seconds(
         BASE_PIPE_SPAWN_RATE,
@@ -66,335 +186,1203 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
 
     commands.spawn(Camera2d);
 
-    let background_handle = asset_server.load("background.png");
-    let background_y_pos = -WINDOW_SIZE.y / 2. + GROUND_HEIGHT + BACKGROUND_SPRITE_HEIGHT / 2.;
-    commands.spawn((
-        Sprite::from_image(background_handle),
-        Transform {
-            translation: Vec3::new(0., background_y_pos, BACKGROUND_Z),
-            ..Default::default()
-        },
-    ));
+    let ground_top = -WINDOW_SIZE.y / 2. + config.ground_height;
+    spawn_parallax_layers(&mut commands, &asset_server, &hidpi, &parallax, ground_top);
 
-    let ground_handle: Handle<Image> = asset_server.load("ground.png");
-    let ground_sprite_y_pos = -WINDOW_SIZE.y / 2. + GROUND_HEIGHT - GROUND_SPRITE_HEIGHT / 2.;
+    // The ground sprite scrolls with the parallax layers, the collider stays put and spans from
+    // the window bottom up to the grass line
     commands.spawn((
         Ground,
-        Collider {
-            kind: ColliderType::Bad,
-            size: Vec2::new(WINDOW_SIZE.x, GROUND_HEIGHT),
-        },
-        Sprite::from_image(ground_handle),
-        Transform {
-            translation: Vec3::new(0., ground_sprite_y_pos, GROUND_Z),
-            ..Default::default()
-        },
-    ));
-
-    commands.spawn((
-        ScoreText,
-        Text::new("0"),
-        TextFont {
-            font_size: 50.0,
-            ..default()
-        },
-        TextColor(ORANGE.into()),
-        TextLayout {
-            justify: Justify::Center,
-            ..default()
-        },
-        Node {
-            position_type: PositionType::Absolute,
-            bottom: px(5),
-            right: px(5),
-            ..default()
-        },
+        Transform::from_xyz(0., ground_top - config.ground_height / 2., 0.),
+        Visibility::Visible,
+        children![(
+            Collider {
+                kind: ColliderType::Bad,
+                size: Vec2::new(WINDOW_SIZE.x, config.ground_height),
+            },
+            Transform::default(),
+        )],
     ));
 }
 
+#[allow(
+    clippy::too_many_arguments,
+    reason = "the bird is built from the skin, the settings and the display"
+)]
 fn spawn_player(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
     mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
+    config: Res<GameConfig>,
+    settings: Res<Settings>,
+    hidpi: Res<HiDpi>,
+    layers: Res<ZLayers>,
+    skin: Res<BirdSkin>,
 ) {
-    let texture: Handle<Image> = asset_server.load("bird_old.png");
-    let layout = TextureAtlasLayout::from_grid(PLAYER_SIZE, 3, 1, None, None);
-    let layout_handle = texture_atlas_layouts.add(layout);
-    let animation_indices = AnimationIndices { first: 0, last: 2 };
+    let variant = image_variant(skin.0.image, &hidpi);
+    let texture: Handle<Image> = asset_server.load(variant.path);
+    let layout_handle = texture_atlas_layouts.add(skin.0.atlas_layout(variant.scale));
 
-    commands.spawn((
-        Player,
-        Velocity(0.0),
-        Collider {
-            kind: ColliderType::Good,
-            size: PLAYER_SIZE.as_vec2(),
-        },
-        AnimationTimer(Timer::from_seconds(0.15, TimerMode::Repeating)),
-        Transform {
-            translation: PLAYER_START_POSITION.extend(PLAYER_Z),
-            scale: Vec2::splat(1.).extend(1.),
-            ..default()
-        },
-        Sprite {
-            image: texture.clone(),
-            texture_atlas: Some(TextureAtlas {
-                layout: layout_handle.clone(),
-                index: animation_indices.first,
-            }),
-            ..default()
-        },
-        animation_indices,
-    ));
+    for bird_index in 0..config.bird_count {
+        let animation_indices = skin.0.animation_indices();
+        let start_position = Vec2::new(
+            config.lane_x - bird_index as f32 * FLOCK_SPACING,
+            PLAYER_START_POSITION.y,
+        );
+
+        commands.spawn((
+            Player,
+            Lane(start_position.x),
+            Lives(settings.lives.max(1)),
+            IdleBob {
+                start: start_position,
+                ease_from: start_position.y,
+                ease: Timer::from_seconds(IDLE_BOB_EASE_DURATION, TimerMode::Once),
+            },
+            Velocity(0.0),
+            Airtime::default(),
+            FixedInterpolation::default(),
+            Collider {
+                kind: ColliderType::Good,
+                size: PLAYER_SIZE.as_vec2(),
+            },
+            AnimationTimer(Timer::from_seconds(0.15, TimerMode::Repeating)),
+            Transform {
+                translation: start_position.extend(layers.player),
+                scale: Vec2::splat(settings.bird_scale).extend(1.),
+                ..default()
+            },
+            Sprite {
+                image: texture.clone(),
+                texture_atlas: Some(TextureAtlas {
+                    layout: layout_handle.clone(),
+                    index: animation_indices.first,
+                }),
+                custom_size: Some(PLAYER_SIZE.as_vec2()),
+                flip_x: skin.0.flip_x,
+                ..default()
+            },
+            animation_indices,
+        ));
+    }
 }
 
-fn apply_gravity(time: Res<Time>, mut query: Query<(&mut Transform, &mut Velocity), With<Player>>) {
-    for (mut transform, mut velocity) in &mut query {
-        // s = v_0 * t + 1/2 * a * t^2
-        transform.translation.y +=
-            velocity.0 * time.delta_secs() + 0.5 * GRAVITY * time.delta_secs().powi(2);
+fn apply_gravity(
+    time: Res<Time>,
+    config: Res<GameConfig>,
+    settings: Res<Settings>,
+    actions: Res<ActionState>,
+    run_timer: Res<RunTimer>,
+    mut start_grace: ResMut<StartGrace>,
+    mut query: Query<(&mut Transform, &mut Velocity, &mut Airtime), With<Player>>,
+) {
+    if start_grace.0 && run_timer.0.elapsed_secs() >= settings.start_grace {
+        start_grace.0 = false;
+    }
 
-        transform.translation.y = transform
-            .translation
-            .y
-            .min(WINDOW_SIZE.y / 2. + PLAYER_SIZE.as_vec2().y / 2.);
+    for (mut transform, mut velocity, mut airtime) in &mut query {
+        // The bird hovers in place until the grace window runs out or the player flaps
+        if start_grace.0 {
+            velocity.0 = 0.;
+            airtime.0 = 0.;
+            continue;
+        }
+
+        let glide_held = actions.pressed(Action::Flap);
+        let acceleration = fall_acceleration(&config, airtime.0, velocity.0, glide_held);
+        let (y, new_velocity) = fall_step(
+            transform.translation.y,
+            velocity.0,
+            acceleration,
+            time.delta_secs(),
+        );
+        airtime.0 += time.delta_secs();
+
+        transform.translation.y = y
+            .min(world_top(config.world_height) + PLAYER_SIZE.as_vec2().y * transform.scale.y / 2.);
+        velocity.0 = new_velocity;
+    }
+}
+
+fn reset_run_timer(mut run_timer: ResMut<RunTimer>) {
+    run_timer.0.reset();
+}
+
+fn reset_start_grace(settings: Res<Settings>, mut start_grace: ResMut<StartGrace>) {
+    start_grace.0 = settings.start_grace > 0.;
+}
+
+fn reset_pipe_spawn_timer(mut spawn_timer: ResMut<PipeSpawnTimer>) {
+    spawn_timer.0.reset();
+}
+
+// Derive the interval from the distance so the spacing stays constant when the speed changes
+fn match_spawn_interval(config: Res<GameConfig>, mut spawn_timer: ResMut<PipeSpawnTimer>) {
+    let spawn_interval = config.pipe_spacing / config.pipe_speed;
+    spawn_timer
+        .0
+        .set_duration(Duration::from_secs_f32(spawn_interval));
+}
+
+fn tick_run_timer(time: Res<Time>, mut run_timer: ResMut<RunTimer>) {
+    run_timer.0.tick(time.delta());
+}
 
-        // v = v_0 + a * t
-        velocity.0 += GRAVITY * time.delta_secs();
+// Only y is simulated, but anything that pushes a bird sideways (knockback, wind) just bends it
+// out of its lane, this pulls it back
+fn maintain_player_x(time: Res<Time>, mut query: Query<(&mut Transform, &Lane), With<Player>>) {
+    let pull = 1. - (-LANE_RETURN_RATE * time.delta_secs()).exp();
+    for (mut transform, lane) in &mut query {
+        let offset = lane.0 - transform.translation.x;
+        if offset == 0. {
+            continue;
+        }
+        transform.translation.x = if offset.abs() < LANE_SNAP_DISTANCE {
+            lane.0
+        } else {
+            transform.translation.x + offset * pull
+        };
     }
 }
 
 fn trigger_game_start(
-    mouse_input: Res<ButtonInput<MouseButton>>,
+    actions: Res<ActionState>,
     mut next_state: ResMut<NextState<AppState>>,
     mut commands: Commands,
 ) {
-    if !mouse_input.just_pressed(MouseButton::Left) {
+    if !actions.just_pressed(Action::Flap) {
         return;
     }
 
-    commands.trigger(JumpEvent);
+    commands.trigger(JumpEvent {
+        source: JumpSource::Flap,
+    });
     next_state.set(AppState::InGame);
 }
 
+// The wave runs on the global clock, so the bird eases into it instead of snapping to its phase
 fn idle_player_movement(
-    mut player_transform_query: Query<&mut Transform, With<Player>>,
+    mut player_query: Query<(&mut Transform, &mut IdleBob), With<Player>>,
+    config: Res<GameConfig>,
     time: Res<Time>,
 ) {
-    let frequency = 0.5;
-    let amplitude = 10.;
-    let wave_position = 2. * PI * frequency * time.elapsed_secs();
-    let translation = amplitude * wave_position.sin();
+    let wave_position = 2. * PI * config.idle_bob_frequency * time.elapsed_secs();
+    let offset = config.idle_bob_amplitude * wave_position.sin();
+
+    for (mut transform, mut idle_bob) in player_query.iter_mut() {
+        idle_bob.ease.tick(time.delta());
+        // Smoothstep, so the bird also leaves its current height without a jolt
+        let progress = idle_bob.ease.fraction();
+        let blend = progress * progress * (3. - 2. * progress);
+        let target = idle_bob.start.y + offset;
 
-    for mut transform in player_transform_query.iter_mut() {
-        transform.translation.y = translation;
+        transform.translation.x = idle_bob.start.x;
+        transform.translation.y = idle_bob.ease_from + (target - idle_bob.ease_from) * blend;
     }
 }
 
-fn player_input(mouse_input: Res<ButtonInput<MouseButton>>, mut commands: Commands) {
-    if mouse_input.just_pressed(MouseButton::Left) {
-        commands.trigger(JumpEvent)
+fn player_input(
+    actions: Res<ActionState>,
+    settings: Res<Settings>,
+    time: Res<Time>,
+    mut auto_flap_timer: ResMut<AutoFlapTimer>,
+    mut commands: Commands,
+) {
+    if actions.just_pressed(Action::Flap) {
+        auto_flap_timer.0.reset();
+        commands.trigger(JumpEvent {
+            source: JumpSource::Flap,
+        });
+        return;
+    }
+
+    let is_held = actions.pressed(Action::Flap);
+    if settings.auto_flap && is_held && auto_flap_timer.0.tick(time.delta()).just_finished() {
+        commands.trigger(JumpEvent {
+            source: JumpSource::AutoFlap,
+        });
     }
 }
 
 fn apply_jump_velocity(
     _jump_event: On<JumpEvent>,
-    mut player_velocity_query: Query<&mut Velocity, With<Player>>,
+    config: Res<GameConfig>,
+    state: Res<State<AppState>>,
+    mut start_grace: ResMut<StartGrace>,
+    mut player_velocity_query: Query<(&mut Velocity, &mut Airtime), LivingBird>,
 ) {
-    for mut velocity in player_velocity_query.iter_mut() {
-        velocity.0 = PLAYER_JUMP_VELOCITY;
+    // The jump that starts the run happens before InGame, any later one ends the hover so its
+    // arc is plain gravity
+    if *state.get() == AppState::InGame {
+        start_grace.0 = false;
+    }
+
+    for (mut velocity, mut airtime) in player_velocity_query.iter_mut() {
+        velocity.0 = config.jump_mode.jump(velocity.0, config.jump_velocity);
+        airtime.0 = 0.;
     }
 }
 
 fn game_over_input(
     mut next_state: ResMut<NextState<AppState>>,
+    mut retry_same_seed: ResMut<RetrySameSeed>,
+    game_over_timer: Res<GameOverTimer>,
+    actions: Res<ActionState>,
+) {
+    if game_over_timer.0.is_finished() && actions.just_pressed(Action::Restart) {
+        retry_same_seed.0 = false;
+        next_state.set(AppState::GameStart);
+    }
+}
+
+fn start_new_score(settings: Res<Settings>, mut commands: Commands) {
+    commands.trigger(ResetScoreEvent {
+        starting_score: settings.starting_score,
+    });
+}
+
+#[allow(
+    clippy::too_many_arguments,
+    reason = "the obstacle spawner reads the run state and the spawn settings"
+)]
+fn pipe_spawner(
     mut commands: Commands,
-    player_query: Query<Entity, With<Player>>,
-    pipes_query: Query<Entity, With<Pipe>>,
-    key_input: Res<ButtonInput<KeyCode>>,
+    mut spawn_timer: ResMut<PipeSpawnTimer>,
+    mut rng: ResMut<GameRng>,
+    mut active_pattern: ResMut<ActivePattern>,
+    mut last_gap: ResMut<LastGapCenter>,
+    mut spawner: ResMut<ActiveObstacleSpawner>,
+    config: Res<GameConfig>,
+    settings: Res<Settings>,
+    hidpi: Res<HiDpi>,
+    layers: Res<ZLayers>,
+    play_area: Res<PlayArea>,
+    time: Res<Time>,
+    asset_server: Res<AssetServer>,
+    pipe_query: Query<(), With<Pipe>>,
 ) {
-    if !key_input.just_pressed(KeyCode::KeyR) {
+    if !spawn_timer.0.tick(time.delta()).just_finished() {
         return;
     }
 
-    for player in player_query.iter() {
-        commands.entity(player).despawn()
+    // A safety valve against leaked pipes, normal runs never get close
+    if pipe_query.iter().count() >= config.max_pipes {
+        warn!("{} live pipes, skipping this spawn", config.max_pipes);
+        return;
     }
 
-    for pipe in pipes_query.iter() {
-        commands.entity(pipe).despawn()
-    }
+    let context = SpawnContext {
+        config: &config,
+        settings: &settings,
+        play_area: &play_area,
+        texture: asset_server.load(image_variant(PIPE_IMAGE, &hidpi).path),
+        x: PIPE_SPAWN_X,
+        z: layers.pipe,
+    };
+    let Some(pipe) = spawner.0.spawn(
+        &mut commands,
+        &context,
+        &mut rng,
+        &mut active_pattern,
+        &mut last_gap,
+    ) else {
+        return;
+    };
 
-    commands.trigger(UpdateScoreEvent { new_score: 0 });
-    next_state.set(AppState::GameStart);
+    commands.spawn((
+        AudioPlayer::new(asset_server.load(PIPE_SPAWN_SOUND)),
+        PlaybackSettings::DESPAWN.with_volume(Volume::Linear(PIPE_SPAWN_VOLUME)),
+    ));
+    if !settings.reduced_motion {
+        commands.entity(pipe).insert(SpawnAnim(Timer::from_seconds(
+            PIPE_SPAWN_ANIM_DURATION,
+            TimerMode::Once,
+        )));
+    }
 }
 
-fn pipe_spawner(
+// Practice can start further down the course. Every earlier pipe still goes through the spawner,
+// so the rng and the pattern end up where a flown run would have left them
+#[allow(
+    clippy::too_many_arguments,
+    reason = "replays the spawner, so it needs everything pipe_spawner does"
+)]
+fn jump_to_practice_section(
     mut commands: Commands,
     mut spawn_timer: ResMut<PipeSpawnTimer>,
-    time: Res<Time>,
+    mut rng: ResMut<GameRng>,
+    mut active_pattern: ResMut<ActivePattern>,
+    mut last_gap: ResMut<LastGapCenter>,
+    mut spawner: ResMut<ActiveObstacleSpawner>,
+    custom_mode: Res<CustomModeSettings>,
+    config: Res<GameConfig>,
+    settings: Res<Settings>,
+    hidpi: Res<HiDpi>,
+    layers: Res<ZLayers>,
+    play_area: Res<PlayArea>,
     asset_server: Res<AssetServer>,
+    mut player_query: Query<&mut Transform, With<Player>>,
 ) {
-    if !spawn_timer.0.tick(time.delta()).just_finished() {
+    let target = custom_mode.practice_start_pipe.saturating_sub(1);
+    if target == 0 {
         return;
     }
 
-    let max_opening_y_pos = WINDOW_SIZE.y / 2. - MINIMUM_PIPE_HEIGHT - BASE_PIPE_SPACE / 2.;
-    let min_opening_y_pos = -max_opening_y_pos + GROUND_HEIGHT;
-    let pipe_group_center = rand::thread_rng().gen_range(min_opening_y_pos..=max_opening_y_pos);
+    // The score counts golden pipes at their full worth, so it is read off the same rolls the
+    // spawner is about to make
+    let bounds = opening_bounds(config.pipe_gap, config.ground_height, config.world_height);
+    let rolls = predict_pipes(
+        &rng,
+        &active_pattern,
+        *last_gap,
+        config.max_gap_delta,
+        bounds,
+        target as usize + 1,
+    );
+    let Some(target_roll) = rolls.get(target as usize) else {
+        info!("The course ends before pipe {}", target + 1);
+        return;
+    };
+    let points: i32 = rolls[..target as usize]
+        .iter()
+        .map(|roll| {
+            if roll.is_golden {
+                GOLDEN_PIPE_POINTS
+            } else {
+                1
+            }
+        })
+        .sum();
 
-    let pipe_offset = BASE_PIPE_SPACE / 2. + PIPE_HEIGHT / 2.;
-    let pipe_x_pos = WINDOW_SIZE.x / 2. + PIPE_WIDTH;
+    let section = PracticeSection::new(target, config.lane_x, config.pipe_spacing);
+    let texture = asset_server.load(image_variant(PIPE_IMAGE, &hidpi).path);
+    for index in 0..=section.last {
+        let context = SpawnContext {
+            config: &config,
+            settings: &settings,
+            play_area: &play_area,
+            texture: texture.clone(),
+            x: section.pipe_x(index),
+            z: layers.pipe,
+        };
+        let Some(pipe) = spawner.0.spawn(
+            &mut commands,
+            &context,
+            &mut rng,
+            &mut active_pattern,
+            &mut last_gap,
+        ) else {
+            break;
+        };
 
-    let texture_handle = asset_server.load("pipe.png");
-    commands
-        .spawn((
-            Pipe,
-            Transform {
-                translation: Vec3::new(pipe_x_pos, pipe_group_center, PIPE_Z),
-                ..default()
-            },
-            Visibility::Visible,
-        ))
-        .with_children(|parent| {
-            parent.spawn((
-                Collider {
-                    kind: ColliderType::Bad,
-                    size: Vec2::new(PIPE_WIDTH, PIPE_HEIGHT),
-                },
-                Sprite {
-                    image: texture_handle.clone(),
-                    ..default()
-                },
-                Transform {
-                    translation: Vec3::new(0., pipe_offset, 0.),
-                    rotation: Quat::from_rotation_x(PI),
-                    ..default()
-                },
-            ));
+        if !section.is_on_screen(index) {
+            commands.entity(pipe).despawn();
+        } else if index < target {
+            // Already flown through, its gate was scored on the way
+            commands.queue(move |world: &mut World| {
+                let Some(children) = world.get::<Children>(pipe) else {
+                    return;
+                };
+                let gates: Vec<Entity> = children
+                    .iter()
+                    .filter(|&child| world.get::<PointGate>(child).is_some())
+                    .collect();
+                for gate in gates {
+                    world.despawn(gate);
+                }
+            });
+        }
+    }
 
-            parent.spawn((
-                Collider {
-                    kind: ColliderType::Bad,
-                    size: Vec2::new(PIPE_WIDTH, PIPE_HEIGHT),
-                },
-                Sprite {
-                    image: texture_handle.clone(),
-                    ..default()
-                },
-                Transform {
-                    translation: Vec3::new(0., -pipe_offset, 0.),
-                    ..default()
-                },
-            ));
+    spawn_timer.0.set_elapsed(Duration::from_secs_f32(
+        section.since_last_spawn(config.pipe_speed),
+    ));
 
-            parent.spawn((
-                PointGate,
-                Collider {
-                    kind: ColliderType::Good,
-                    size: Vec2::new(10., BASE_PIPE_SPACE),
-                },
-                Sprite::from_color(RED, Vec2::new(10., BASE_PIPE_SPACE)),
-                Transform {
-                    // scale: Vec3::new(10., BASE_PIPE_SPACE, 0.),
-                    ..Default::default()
-                },
-            ));
-        });
+    for mut transform in &mut player_query {
+        transform.translation.y = target_roll.gap_center;
+    }
+    commands.trigger(ResetScoreEvent {
+        starting_score: settings.starting_score + points,
+    });
+}
+
+// Only the sprites fade and grow, the pipe and its colliders are at their final size and place
+// from the first frame
+// Pipes spawn past the right edge, so the animation waits until the pipe starts to show
+fn animate_pipe_spawn(
+    time: Res<Time>,
+    play_area: Res<PlayArea>,
+    mut commands: Commands,
+    mut pipe_query: Query<(Entity, &Transform, &mut SpawnAnim, &Children)>,
+    mut sprite_query: Query<(&mut Sprite, &mut Transform), Without<SpawnAnim>>,
+) {
+    let enter_x = play_area.visible_width / 2. + PIPE_WIDTH / 2.;
+    for (pipe, pipe_transform, mut spawn_anim, children) in pipe_query.iter_mut() {
+        if pipe_transform.translation.x < enter_x {
+            spawn_anim.0.tick(time.delta());
+        }
+        let finished = spawn_anim.0.is_finished();
+        let progress = spawn_anim.0.fraction();
+        let scale = PIPE_SPAWN_START_SCALE + (1. - PIPE_SPAWN_START_SCALE) * progress;
+        for child in children.iter() {
+            if let Ok((mut sprite, mut transform)) = sprite_query.get_mut(child) {
+                sprite.color.set_alpha(progress);
+                transform.scale = Vec3::new(scale, scale, 1.);
+            }
+        }
+
+        if finished {
+            commands.entity(pipe).remove::<SpawnAnim>();
+        }
+    }
 }
 
-fn pipe_movement(time: Res<Time>, mut query: Query<&mut Transform, With<Pipe>>) {
+fn pipe_movement(
+    time: Res<Time>,
+    config: Res<GameConfig>,
+    mut query: Query<&mut Transform, With<Pipe>>,
+) {
     for mut pipe_transform in &mut query {
-        pipe_transform.translation.x -= PIPE_BASE_SPEED * time.delta_secs();
+        pipe_transform.translation.x -= config.pipe_speed * time.delta_secs();
     }
 }
 
+#[allow(
+    clippy::type_complexity,
+    reason = "a bird's hit is decided by its lives, invincibility and shield together"
+)]
+#[allow(
+    clippy::too_many_arguments,
+    reason = "a crash can end the run, cost a life or use the tutorial save"
+)]
 fn detect_collision(
-    score: Res<Score>,
     mut commands: Commands,
     mut next_state: ResMut<NextState<AppState>>,
-    player_query: Query<(&GlobalTransform, &Collider), With<Player>>,
-    collider_query: Query<(Entity, &GlobalTransform, &Collider), Without<Player>>,
+    config: Res<GameConfig>,
+    mode: Res<GameMode>,
+    settings: Res<Settings>,
+    mut tutorial_save: ResMut<TutorialSave>,
+    mut player_query: Query<
+        (
+            Entity,
+            &GlobalTransform,
+            &Collider,
+            &mut Lives,
+            &mut Velocity,
+            Has<Invincible>,
+            Has<FirstFrameGuard>,
+            Has<Shield>,
+        ),
+        LivingBird,
+    >,
+    collider_query: Query<
+        (
+            Entity,
+            &GlobalTransform,
+            &Collider,
+            Option<&ChildOf>,
+            Option<&GateTrail>,
+        ),
+        Without<Player>,
+    >,
+    golden_pipe_query: Query<(), With<GoldenPipe>>,
+    pipe_query: Query<&GlobalTransform, With<Pipe>>,
 ) {
-    for (player_global_transform, player_collider) in &player_query {
-        let player_aabb = Aabb2d::new(
-            player_global_transform.translation().truncate(),
-            player_collider.size / 2.,
-        );
+    // Collider boxes do not depend on the bird, so they are built once for every bird
+    let colliders: Vec<_> = collider_query
+        .iter()
+        .map(
+            |(collider_entity, collider_global_transform, collider, parent, trail)| {
+                let center = collider_global_transform.translation().truncate();
+                let pipe = parent
+                    .map(ChildOf::parent)
+                    .filter(|&pipe| pipe_query.contains(pipe));
+                let aabb = match pipe.and_then(|pipe| pipe_query.get(pipe).ok()) {
+                    Some(pipe_transform) => pipe_collider_box(
+                        &collider.kind,
+                        center,
+                        collider.size,
+                        pipe_transform.translation().y,
+                        settings.assist_level,
+                    ),
+                    None => Aabb2d::new(center, collider.size / 2.),
+                };
+                // A long frame or a fast pipe can carry the thin gate past the bird between two
+                // checks, so the gate covers everything it moved over since the last one
+                let aabb = match trail.and_then(|trail| trail.0) {
+                    Some(previous_x) => swept_gate_box(aabb, previous_x - center.x),
+                    None => aabb,
+                };
+                (collider_entity, center, collider, pipe, aabb)
+            },
+        )
+        .collect();
+    let boxes: Vec<Aabb2d> = colliders.iter().map(|&(.., aabb)| aabb).collect();
+    let broadphase = Broadphase::new(&boxes);
 
-        for (collider_entity, collider_global_transform, collider) in &collider_query {
-            let other_aabb = Aabb2d::new(
-                collider_global_transform.translation().truncate(),
-                collider.size / 2.,
+    let mut survivors = 0;
+    for (
+        player,
+        player_global_transform,
+        player_collider,
+        mut lives,
+        mut velocity,
+        is_invincible,
+        is_guarded,
+        has_shield,
+    ) in &mut player_query
+    {
+        // The collider keeps the unscaled size, the bird size setting only lives in the transform
+        let player_center = player_global_transform.translation().truncate();
+        let player_size = player_collider.size * player_global_transform.scale().truncate();
+        let search_aabb =
+            player_hitbox(&ColliderType::Good, player_center, player_size, &config).merge(
+                &player_hitbox(&ColliderType::Bad, player_center, player_size, &config),
             );
 
+        let mut hit = None;
+        for index in broadphase.overlapping(&boxes, &search_aabb) {
+            let (collider_entity, center, collider, pipe, other_aabb) = colliders[index];
+            let player_aabb = player_hitbox(&collider.kind, player_center, player_size, &config);
             if !player_aabb.intersects(&other_aabb) {
                 continue;
             }
 
             match collider.kind {
                 ColliderType::Good => {
-                    commands.trigger(UpdateScoreEvent {
-                        new_score: score.0 + 1,
+                    commands.trigger(PointGateReachedEvent {
+                        pipe,
+                        player_x: player_aabb.center().x,
+                        player_y: player_aabb.center().y,
+                        gate_x: center.x,
+                        gate_y: center.y,
+                        gate_size: collider.size,
+                        side: collision_side(
+                            &player_aabb,
+                            &Aabb2d::new(center, collider.size / 2.),
+                        ),
                     });
+                    let is_golden = pipe.is_some_and(|pipe| golden_pipe_query.contains(pipe));
+                    if is_golden {
+                        commands.trigger(IncrementScoreEvent {
+                            points: GOLDEN_PIPE_POINTS,
+                        });
+                        commands.trigger(GoldenPipeScoredEvent { position: center });
+                    } else {
+                        commands.trigger(IncrementScoreEvent { points: 1 });
+                    }
                     commands.entity(collider_entity).despawn();
                 }
+                ColliderType::Bad if is_invincible || is_guarded => {}
                 ColliderType::Bad => {
-                    next_state.set(AppState::GameOver);
+                    let side = collision_side(&player_aabb, &other_aabb);
+                    hit = hit.or(Some((side, pipe)));
                 }
             }
         }
+
+        match hit {
+            // A shield goes before anything else that absorbs a crash and clears the way the
+            // same as a spare life
+            Some((_, pipe)) if has_shield => {
+                match pipe {
+                    Some(pipe) => commands.entity(pipe).despawn(),
+                    None => velocity.0 = config.jump_velocity,
+                }
+                commands
+                    .entity(player)
+                    .remove::<Shield>()
+                    .insert(Invincible(Timer::from_seconds(
+                        SHIELD_BROKEN_INVINCIBLE_DURATION,
+                        TimerMode::Once,
+                    )));
+                commands.trigger(ShieldBrokenEvent {
+                    player,
+                    position: player_center,
+                });
+                survivors += 1;
+            }
+            // A spare life clears the pipe that was hit, or bounces the bird off the ground.
+            // Score attack pays for the crash with clock time instead, and a new player's first
+            // lethal crash of the run is forgiven once
+            Some((_, pipe)) if lives.0 > 1 || *mode == GameMode::ScoreAttack || tutorial_save.0 => {
+                if *mode == GameMode::ScoreAttack {
+                    commands.trigger(TimePenaltyEvent {
+                        seconds: SCORE_ATTACK_CRASH_PENALTY,
+                    });
+                } else if lives.0 > 1 {
+                    lives.0 -= 1;
+                    commands.trigger(LivesChangedEvent { player });
+                } else {
+                    tutorial_save.0 = false;
+                    commands.trigger(TutorialSaveEvent);
+                }
+                match pipe {
+                    Some(pipe) => commands.entity(pipe).despawn(),
+                    None => velocity.0 = config.jump_velocity,
+                }
+                commands
+                    .entity(player)
+                    .insert(Invincible(Timer::from_seconds(
+                        LIFE_LOST_INVINCIBLE_DURATION,
+                        TimerMode::Once,
+                    )));
+                survivors += 1;
+            }
+            Some((side, pipe)) => {
+                commands.entity(player).insert(Dead);
+                if pipe.is_some() {
+                    commands.trigger(PipeCollisionEvent { player, side });
+                } else {
+                    commands.trigger(GroundCollisionEvent { player, side });
+                }
+            }
+            None => survivors += 1,
+        }
+    }
+
+    if survivors == 0 {
+        if *mode == GameMode::Practice {
+            commands.trigger(RewindEvent);
+        } else {
+            next_state.set(AppState::DeathReplay);
+        }
+    }
+}
+
+fn track_gate_positions(mut query: Query<(&GlobalTransform, &mut GateTrail)>) {
+    for (gate_transform, mut trail) in query.iter_mut() {
+        trail.0 = Some(gate_transform.translation().x);
     }
 }
 
-fn update_score(
-    update_event: On<UpdateScoreEvent>,
+fn increment_score(
+    increment_event: On<IncrementScoreEvent>,
     mut score: ResMut<Score>,
     mut commands: Commands,
 ) {
-    score.0 = update_event.new_score;
+    score.0 += increment_event.points;
     commands.trigger(ScoreChangedEvent);
 }
 
-fn update_score_text(
-    _change_event: On<ScoreChangedEvent>,
-    score: Res<Score>,
-    mut query: Query<&mut Text, With<ScoreText>>,
+fn reset_score(reset_event: On<ResetScoreEvent>, mut score: ResMut<Score>, mut commands: Commands) {
+    score.0 = reset_event.starting_score;
+    commands.trigger(ScoreChangedEvent);
+}
+
+fn show_colliders_enabled(flags: Res<DebugFlags>) -> bool {
+    flags.show_colliders
+}
+
+fn draw_colliders(
+    mut gizmos: Gizmos,
+    config: Res<GameConfig>,
+    query: Query<(&Collider, &GlobalTransform, Has<Player>)>,
 ) {
-    for mut text in query.iter_mut() {
-        text.0 = format!("{}", score.0);
-    }
+    draw_collider_boxes(&mut gizmos, &config, &query);
 }
 
-fn draw_colliders(mut gizmos: Gizmos, query: Query<(&Collider, &GlobalTransform)>) {
-    for (collider, transform) in query.iter() {
-        let color = match collider.kind {
-            ColliderType::Good => GREEN,
-            ColliderType::Bad => RED,
-        };
+fn draw_collider_boxes(
+    gizmos: &mut Gizmos,
+    config: &GameConfig,
+    query: &Query<(&Collider, &GlobalTransform, Has<Player>)>,
+) {
+    for (collider, transform, is_player) in query.iter() {
+        let center = transform.translation().truncate();
+        if !is_player {
+            let color = match collider.kind {
+                ColliderType::Good => GREEN,
+                ColliderType::Bad => RED,
+            };
+            gizmos.rect_2d(Isometry2d::from_translation(center), collider.size, color);
+            continue;
+        }
 
-        gizmos.rect_2d(
-            Isometry2d::from_translation(transform.translation().truncate()),
-            collider.size,
-            color,
-        );
+        // The bird has one box per kind of collider it can touch, drawn in that collider's color
+        let size = collider.size * transform.scale().truncate();
+        for (kind, color) in [(ColliderType::Good, GREEN), (ColliderType::Bad, RED)] {
+            let hitbox = player_hitbox(&kind, center, size, config);
+            gizmos.rect_2d(
+                Isometry2d::from_translation(center),
+                hitbox.half_size() * 2.,
+                color,
+            );
+        }
     }
 }
 
+fn draw_ground_line(mut gizmos: Gizmos, config: Res<GameConfig>) {
+    let ground_top = -WINDOW_SIZE.y / 2. + config.ground_height;
+    gizmos.line_2d(
+        Vec2::new(-WINDOW_SIZE.x / 2., ground_top),
+        Vec2::new(WINDOW_SIZE.x / 2., ground_top),
+        YELLOW,
+    );
+}
+
 fn count_pipes(query: Query<&Pipe>) {
     println!("Pipe count: {}", query.iter().count());
 }
 
-fn despawn_pipes(
-    window: Single<&Window>,
-    query: Query<(Entity, &Transform), With<Pipe>>,
-    mut commands: Commands,
-) {
-    let width = window.resolution.physical_width();
-    println!("Physical width: {}", width);
-
+// Uses the world size rather than the window, so it also works headless
+fn despawn_pipes(query: Query<(Entity, &Transform), With<Pipe>>, mut commands: Commands) {
     for (entity, transform) in query {
-        if transform.translation.x < -(width as f32 / 2.) {
+        if transform.translation.x < PIPE_DESPAWN_X {
             commands.entity(entity).despawn();
         }
     }
 }
+
+// In flock mode the run goes on after a bird dies, and nothing stops it from falling forever
+fn despawn_fallen_birds(query: Query<(Entity, &Transform), With<Dead>>, mut commands: Commands) {
+    for (entity, transform) in query {
+        let top = transform.translation.y + PLAYER_SIZE.as_vec2().y * transform.scale.y / 2.;
+        if top < -WINDOW_SIZE.y / 2. {
+            commands.entity(entity).insert(Fallen).despawn();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::RunSystemOnce;
+    use flappy_bird::{
+        obstacles::spawner_for_mode,
+        pipes::spawn_pipe_pair,
+        skins::{BirdSkinDef, CLASSIC_BIRD_SKIN},
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+
+    // Reads the collider boxes back from the line strips the debug gizmos draw
+    fn drawn_boxes(app: &mut App) -> Vec<Rect> {
+        let strips = app
+            .world_mut()
+            .run_system_once(
+                |mut gizmos: Gizmos,
+                 config: Res<GameConfig>,
+                 query: Query<(&Collider, &GlobalTransform, Has<Player>)>| {
+                    draw_collider_boxes(&mut gizmos, &config, &query);
+                    gizmos.buffer().strip_positions.clone()
+                },
+            )
+            .unwrap();
+
+        strips
+            .split(|position| position.is_nan())
+            .filter(|corners| !corners.is_empty())
+            .map(|corners| {
+                corners.iter().fold(
+                    Rect::from_center_size(corners[0].truncate(), Vec2::ZERO),
+                    |rect, corner| rect.union_point(corner.truncate()),
+                )
+            })
+            .collect()
+    }
+
+    // Between two checks the pipe moves 400 units, so the gate is right of the bird in the first
+    // frame and far left of it in the second and never overlaps it in either
+    #[test]
+    fn a_gate_that_jumps_past_the_bird_in_one_frame_still_scores() {
+        let mut app = App::new();
+        app.add_plugins((TransformPlugin, bevy::state::app::StatesPlugin))
+            .init_state::<AppState>()
+            .init_resource::<GameConfig>()
+            .init_resource::<GameMode>()
+            .init_resource::<Settings>()
+            .init_resource::<TutorialSave>()
+            .insert_resource(Score(0))
+            .add_observer(increment_score);
+        let pipe = spawn_pipe_pair(
+            &mut app.world_mut().commands(),
+            Handle::default(),
+            Vec2::new(200., 0.),
+            BASE_PIPE_SPACE,
+            PIPE_HEIGHT,
+            Color::WHITE,
+            PIPE_Z,
+        );
+        app.world_mut().spawn((
+            Player,
+            Lives(1),
+            Velocity(0.),
+            Collider {
+                kind: ColliderType::Good,
+                size: PLAYER_SIZE.as_vec2(),
+            },
+            Transform::default(),
+        ));
+        app.world_mut().flush();
+
+        let check_collisions = |app: &mut App| {
+            app.world_mut().run_schedule(PostUpdate);
+            app.world_mut().run_system_once(detect_collision).unwrap();
+            app.world_mut()
+                .run_system_once(track_gate_positions)
+                .unwrap();
+        };
+
+        check_collisions(&mut app);
+        assert_eq!(app.world().resource::<Score>().0, 0);
+
+        app.world_mut()
+            .get_mut::<Transform>(pipe)
+            .unwrap()
+            .translation
+            .x = -200.;
+        check_collisions(&mut app);
+        assert_eq!(app.world().resource::<Score>().0, 1);
+    }
+
+    // The bottom pipe covers the bird from the start, it must survive the first InGame frame
+    // and die on the next one
+    #[test]
+    fn a_pipe_on_the_bird_cannot_kill_it_on_the_first_frame() {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            TransformPlugin,
+            bevy::state::app::StatesPlugin,
+            flappy_bird::invincible::invincible_plugin,
+        ))
+        .init_state::<AppState>()
+        .add_sub_state::<PauseState>()
+        .init_resource::<GameConfig>()
+        .init_resource::<GameMode>()
+        .init_resource::<Settings>()
+        .init_resource::<TutorialSave>()
+        .insert_resource(Score(0))
+        .add_systems(
+            PostUpdate,
+            detect_collision.run_if(in_state(PauseState::Running)),
+        );
+        spawn_pipe_pair(
+            &mut app.world_mut().commands(),
+            Handle::default(),
+            Vec2::new(0., 400.),
+            BASE_PIPE_SPACE,
+            PIPE_HEIGHT,
+            Color::WHITE,
+            PIPE_Z,
+        );
+        app.world_mut().spawn((
+            Player,
+            Lives(1),
+            Velocity(0.),
+            Collider {
+                kind: ColliderType::Good,
+                size: PLAYER_SIZE.as_vec2(),
+            },
+            Sprite::default(),
+            Transform::default(),
+        ));
+        app.update();
+
+        app.world_mut()
+            .resource_mut::<NextState<AppState>>()
+            .set(AppState::InGame);
+        app.update();
+        let state = |app: &App| app.world().resource::<State<AppState>>().get().clone();
+        assert_eq!(state(&app), AppState::InGame);
+        app.update();
+        assert_eq!(state(&app), AppState::InGame);
+
+        // The state change requested on the second frame applies at the start of the third
+        app.update();
+        assert_eq!(state(&app), AppState::DeathReplay);
+    }
+
+    // With a single life the shield is all that stands between the bird and the pipe, the
+    // second pipe after the invincibility window kills it
+    #[test]
+    fn a_shielded_bird_survives_one_hit() {
+        let mut app = App::new();
+        app.add_plugins((TransformPlugin, bevy::state::app::StatesPlugin))
+            .init_state::<AppState>()
+            .init_resource::<GameConfig>()
+            .init_resource::<GameMode>()
+            .init_resource::<Settings>()
+            .init_resource::<TutorialSave>();
+        let spawn_blocking_pipe = |app: &mut App| {
+            spawn_pipe_pair(
+                &mut app.world_mut().commands(),
+                Handle::default(),
+                Vec2::new(0., 400.),
+                BASE_PIPE_SPACE,
+                PIPE_HEIGHT,
+                Color::WHITE,
+                PIPE_Z,
+            )
+        };
+        let first_pipe = spawn_blocking_pipe(&mut app);
+        let bird = app
+            .world_mut()
+            .spawn((
+                Player,
+                Shield,
+                Lives(1),
+                Velocity(0.),
+                Collider {
+                    kind: ColliderType::Good,
+                    size: PLAYER_SIZE.as_vec2(),
+                },
+                Transform::default(),
+            ))
+            .id();
+        app.world_mut().flush();
+
+        let check_collisions = |app: &mut App| {
+            app.world_mut().run_schedule(PostUpdate);
+            app.world_mut().run_system_once(detect_collision).unwrap();
+        };
+        let is_dying = |app: &App| {
+            matches!(
+                app.world().resource::<NextState<AppState>>(),
+                NextState::Pending(AppState::DeathReplay)
+            )
+        };
+
+        check_collisions(&mut app);
+        let world = app.world();
+        assert!(!world.entity(bird).contains::<Dead>());
+        assert!(!world.entity(bird).contains::<Shield>());
+        assert!(world.entity(bird).contains::<Invincible>());
+        assert!(world.get_entity(first_pipe).is_err());
+        assert!(!is_dying(&app));
+
+        app.world_mut().entity_mut(bird).remove::<Invincible>();
+        spawn_blocking_pipe(&mut app);
+        app.world_mut().flush();
+        check_collisions(&mut app);
+        assert!(app.world().entity(bird).contains::<Dead>());
+        assert!(is_dying(&app));
+    }
+
+    #[test]
+    fn dead_birds_are_despawned_once_below_the_screen() {
+        let mut world = World::new();
+        let below = -WINDOW_SIZE.y / 2. - PLAYER_SIZE.as_vec2().y;
+        let fallen = world
+            .spawn((Player, Dead, Transform::from_xyz(0., below, 0.)))
+            .id();
+        let falling = world
+            .spawn((Player, Dead, Transform::from_xyz(0., 0., 0.)))
+            .id();
+        let alive = world
+            .spawn((Player, Transform::from_xyz(0., below, 0.)))
+            .id();
+
+        world.run_system_once(despawn_fallen_birds).unwrap();
+        assert!(world.get_entity(fallen).is_err());
+        assert!(world.get_entity(falling).is_ok());
+        assert!(world.get_entity(alive).is_ok());
+    }
+
+    // A hard sideways shove, the bird has to be back in its lane within a second
+    #[test]
+    fn a_knocked_back_bird_returns_to_its_lane() {
+        let mut world = World::new();
+        world.insert_resource(Time::<()>::default());
+        let lane = PLAYER_START_POSITION.x;
+        let bird = world
+            .spawn((Player, Lane(lane), Transform::from_xyz(lane + 150., 0., 0.)))
+            .id();
+
+        for _ in 0..64 {
+            world
+                .resource_mut::<Time>()
+                .advance_by(Duration::from_secs_f32(1. / 64.));
+            world.run_system_once(maintain_player_x).unwrap();
+        }
+
+        let x = world.get::<Transform>(bird).unwrap().translation.x;
+        assert!((x - lane).abs() < 1., "bird still at {x}");
+    }
+
+    // Nothing despawns the pipes here and the spawner fires every tick
+    #[test]
+    fn the_spawner_never_exceeds_the_pipe_cap() {
+        let config = GameConfig {
+            pipe_spacing: 1.,
+            max_pipes: 5,
+            ..default()
+        };
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default()))
+            .init_asset::<Image>()
+            .init_asset::<bevy::audio::AudioSource>()
+            .insert_resource(config)
+            .insert_resource(PipeSpawnTimer(Timer::from_seconds(
+                1.,
+                TimerMode::Repeating,
+            )))
+            .insert_resource(GameRng(StdRng::seed_from_u64(0)))
+            .insert_resource(ActiveObstacleSpawner(spawner_for_mode(GameMode::Normal)))
+            .init_resource::<ActivePattern>()
+            .init_resource::<LastGapCenter>()
+            .init_resource::<Settings>()
+            .init_resource::<HiDpi>()
+            .init_resource::<PlayArea>()
+            .init_resource::<ZLayers>();
+
+        let world = app.world_mut();
+        world.run_system_once(match_spawn_interval).unwrap();
+        for _ in 0..50 {
+            world
+                .resource_mut::<Time>()
+                .advance_by(Duration::from_secs_f32(1. / 64.));
+            world.run_system_once(pipe_spawner).unwrap();
+            let pipes = world.query_filtered::<(), With<Pipe>>().iter(world).count();
+            assert!(pipes <= 5, "{pipes} live pipes");
+        }
+
+        let pipes = world.query_filtered::<(), With<Pipe>>().iter(world).count();
+        assert_eq!(pipes, 5);
+    }
+
+    #[test]
+    fn top_pipe_hitbox_covers_exactly_the_drawn_pipe() {
+        let mut app = App::new();
+        app.add_plugins(TransformPlugin)
+            .init_gizmo_group::<DefaultGizmoConfigGroup>()
+            .init_resource::<GameConfig>();
+        let gap_center = Vec2::new(40., 50.);
+        let pipe = spawn_pipe_pair(
+            &mut app.world_mut().commands(),
+            Handle::default(),
+            gap_center,
+            BASE_PIPE_SPACE,
+            PIPE_HEIGHT,
+            Color::WHITE,
+            PIPE_Z,
+        );
+        app.world_mut().flush();
+        // Only the transforms are needed, the gizmo groups also expect the time plugins
+        app.world_mut().run_schedule(PostUpdate);
+
+        let world = app.world();
+        let top_pipe = world
+            .get::<Children>(pipe)
+            .unwrap()
+            .iter()
+            .find(|&child| world.get::<Transform>(child).unwrap().translation.y > 0.)
+            .unwrap();
+        let sprite = world.get::<Sprite>(top_pipe).unwrap();
+        let sprite_rect = Rect::from_center_size(
+            world
+                .get::<GlobalTransform>(top_pipe)
+                .unwrap()
+                .translation()
+                .truncate(),
+            sprite.custom_size.unwrap(),
+        );
+
+        let boxes = drawn_boxes(&mut app);
+        let top_box = boxes.iter().find(|rect| rect.min.y > gap_center.y).unwrap();
+        assert_eq!(*top_box, sprite_rect);
+        assert_eq!(top_box.min.y, gap_center.y + BASE_PIPE_SPACE / 2.);
+    }
+
+    #[test]
+    fn a_left_facing_skin_spawns_a_flipped_bird() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default()))
+            .init_asset::<Image>()
+            .init_asset::<TextureAtlasLayout>()
+            .init_resource::<GameConfig>()
+            .init_resource::<Settings>()
+            .init_resource::<HiDpi>()
+            .init_resource::<ZLayers>()
+            .insert_resource(BirdSkin(BirdSkinDef {
+                flip_x: true,
+                ..CLASSIC_BIRD_SKIN
+            }));
+
+        let world = app.world_mut();
+        world.run_system_once(spawn_player).unwrap();
+        let sprite = world
+            .query_filtered::<&Sprite, With<Player>>()
+            .single(world)
+            .unwrap();
+        assert!(sprite.flip_x);
+        assert_eq!(sprite.texture_atlas.as_ref().unwrap().index, 0);
+    }
+
+    // Drives the real spawners through full restarts. Sounds are left out of the count, the audio
+    // plugin despawns them once they finish playing
+    #[test]
+    fn restarts_do_not_leak_entities() {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            AssetPlugin::default(),
+            bevy::state::app::StatesPlugin,
+            flappy_bird::cleanup::cleanup_plugin,
+            flappy_bird::particles::particles_plugin,
+            flappy_bird::golden_pipe::golden_pipe_plugin,
+        ))
+        .init_asset::<Image>()
+        .init_asset::<TextureAtlasLayout>()
+        .init_asset::<bevy::audio::AudioSource>()
+        .insert_resource(bevy::time::TimeUpdateStrategy::ManualDuration(
+            Duration::from_millis(100),
+        ))
+        .init_state::<AppState>()
+        .init_resource::<GameConfig>()
+        .init_resource::<Settings>()
+        .init_resource::<HiDpi>()
+        .init_resource::<ZLayers>()
+        .init_resource::<BirdSkin>()
+        .init_resource::<PlayArea>()
+        .insert_resource(GameRng(StdRng::seed_from_u64(0)))
+        .insert_resource(ActiveObstacleSpawner(spawner_for_mode(GameMode::Normal)))
+        .init_resource::<ActivePattern>()
+        .init_resource::<LastGapCenter>()
+        .insert_resource(PipeSpawnTimer(Timer::from_seconds(
+            BASE_PIPE_SPAWN_RATE,
+            TimerMode::Repeating,
+        )))
+        .add_systems(OnEnter(AppState::GameStart), spawn_player)
+        .add_systems(OnEnter(AppState::InGame), reset_pipe_spawn_timer)
+        .add_systems(
+            Update,
+            match_spawn_interval.run_if(resource_changed::<GameConfig>),
+        )
+        .add_systems(Update, pipe_spawner.run_if(in_state(AppState::InGame)));
+
+        let set_state = |app: &mut App, state: AppState| {
+            app.world_mut()
+                .resource_mut::<NextState<AppState>>()
+                .set(state);
+            app.update();
+        };
+        let entity_count = |app: &mut App| {
+            let world = app.world_mut();
+            world
+                .query_filtered::<(), Without<AudioPlayer>>()
+                .iter(world)
+                .count()
+        };
+
+        set_state(&mut app, AppState::GameStart);
+        let baseline = entity_count(&mut app);
+        for _ in 0..3 {
+            set_state(&mut app, AppState::InGame);
+            for _ in 0..30 {
+                app.update();
+            }
+            app.world_mut().trigger(GoldenPipeScoredEvent {
+                position: Vec2::ZERO,
+            });
+            app.world_mut().flush();
+            let world = app.world_mut();
+            assert!(world.query::<&Pipe>().iter(world).count() > 0);
+            assert!(world.query::<&Particle>().iter(world).count() > 0);
+
+            set_state(&mut app, AppState::GameOver);
+            set_state(&mut app, AppState::GameStart);
+            assert_eq!(entity_count(&mut app), baseline);
+        }
+    }
+}
@@ -0,0 +1,1359 @@
+use bevy::{
+    input::mouse::{MouseScrollUnit, MouseWheel},
+    prelude::*,
+    window::Monitor,
+};
+
+use crate::{
+    actions::{action_just_pressed, Action, ActionState},
+    branding::Branding,
+    components::{MenuAction, MenuScroll, PauseDim, QuitPrompt, SettingLabel},
+    constants::*,
+    resources::{
+        Antialiasing, AssistLevel, CustomModeSettings, GameMode, GameOverTimer, GapDistribution,
+        PauseReason, PerfectCriteria, PipePatterns, QuitConfirm, RetrySameSeed, Settings,
+    },
+    run_log::open_run_log_location,
+    AppState, MenuScreen, PauseState,
+};
+
+const NORMAL_BUTTON: Color = Color::srgb(0.15, 0.15, 0.15);
+const HOVERED_BUTTON: Color = Color::srgb(0.25, 0.25, 0.25);
+const PRESSED_BUTTON: Color = Color::srgb(0.35, 0.75, 0.35);
+
+pub fn menu_plugin(app: &mut App) {
+    app.add_systems(OnEnter(MenuScreen::Main), spawn_main_menu)
+        .add_systems(OnEnter(MenuScreen::Settings), spawn_settings_menu)
+        .add_systems(OnEnter(MenuScreen::Custom), spawn_custom_menu)
+        .add_systems(OnEnter(PauseState::Paused), spawn_quit_dialog)
+        .init_resource::<GameOverTimer>()
+        .init_resource::<QuitConfirm>()
+        .add_systems(
+            OnEnter(AppState::GameOver),
+            (
+                reset_game_over_timer,
+                spawn_quit_prompt,
+                spawn_game_over_menu.run_if(
+                    resource_equals(GameMode::Normal).or(resource_equals(GameMode::ScoreAttack)),
+                ),
+            ),
+        )
+        .add_systems(
+            Update,
+            (tick_game_over_timer, show_retry_cooldown, game_over_quit)
+                .chain()
+                .run_if(in_state(AppState::GameOver)),
+        )
+        .add_systems(
+            Update,
+            (button_colors, menu_action, update_setting_labels).run_if(
+                in_state(AppState::MainMenu)
+                    .or(in_state(PauseState::Paused))
+                    .or(in_state(AppState::GameOver)),
+            ),
+        )
+        .add_systems(Update, scroll_menu.run_if(in_state(MenuScreen::Settings)))
+        .add_systems(
+            Update,
+            menu_back.run_if(in_state(AppState::MainMenu).and(action_just_pressed(Action::Back))),
+        );
+}
+
+fn menu_root<S: States>(screen: S) -> impl Bundle {
+    (
+        Node {
+            width: percent(100),
+            height: percent(100),
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::Center,
+            ..default()
+        },
+        DespawnOnExit(screen),
+    )
+}
+
+fn title(text: &str) -> impl Bundle {
+    (
+        Text::new(text),
+        TextFont {
+            font_size: 80.0,
+            ..default()
+        },
+        TextColor(Color::WHITE),
+        Node {
+            margin: UiRect::bottom(px(40)),
+            ..default()
+        },
+    )
+}
+
+fn button(action: MenuAction, label: &str) -> impl Bundle {
+    (
+        Button,
+        action,
+        Node {
+            width: px(300),
+            height: px(65),
+            margin: UiRect::all(px(10)),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        BackgroundColor(NORMAL_BUTTON),
+        children![(
+            Text::new(label),
+            TextFont {
+                font_size: 33.0,
+                ..default()
+            },
+            TextColor(Color::WHITE),
+        )],
+    )
+}
+
+fn small_button(action: MenuAction, label: &str) -> impl Bundle {
+    (
+        Button,
+        action,
+        Node {
+            width: px(65),
+            height: px(65),
+            margin: UiRect::all(px(5)),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        BackgroundColor(NORMAL_BUTTON),
+        children![(
+            Text::new(label),
+            TextFont {
+                font_size: 33.0,
+                ..default()
+            },
+            TextColor(Color::WHITE),
+        )],
+    )
+}
+
+fn setting_label(label: SettingLabel) -> impl Bundle {
+    (
+        label,
+        Text::new(""),
+        TextFont {
+            font_size: 33.0,
+            ..default()
+        },
+        TextColor(Color::WHITE),
+        Node {
+            min_width: px(300),
+            ..default()
+        },
+        TextLayout {
+            justify: Justify::Center,
+            ..default()
+        },
+    )
+}
+
+fn row() -> Node {
+    Node {
+        flex_direction: FlexDirection::Row,
+        align_items: AlignItems::Center,
+        ..default()
+    }
+}
+
+// The settings outgrew the window, the columns scroll between the title and the Back button
+fn scroll_row() -> impl Bundle {
+    (
+        Node {
+            flex_direction: FlexDirection::Row,
+            align_items: AlignItems::FlexStart,
+            flex_shrink: 1.,
+            min_height: px(0),
+            overflow: Overflow::scroll_y(),
+            margin: UiRect::bottom(px(20)),
+            ..default()
+        },
+        MenuScroll,
+    )
+}
+
+fn column() -> Node {
+    Node {
+        flex_direction: FlexDirection::Column,
+        align_items: AlignItems::Center,
+        margin: UiRect::horizontal(px(20)),
+        ..default()
+    }
+}
+
+fn spawn_main_menu(mut commands: Commands, branding: Res<Branding>) {
+    commands.spawn((
+        menu_root(MenuScreen::Main),
+        children![
+            title(&branding.title),
+            button(MenuAction::Play, "Play"),
+            button(MenuAction::PlayAdaptive, "Adaptive"),
+            button(MenuAction::PlayScoreAttack, "Score attack"),
+            button(MenuAction::PlayPractice, "Practice"),
+            button(MenuAction::Custom, "Custom"),
+            button(MenuAction::Settings, "Settings"),
+            button(MenuAction::Quit, "Quit"),
+        ],
+    ));
+}
+
+fn spawn_settings_menu(mut commands: Commands) {
+    commands.spawn((
+        menu_root(MenuScreen::Settings),
+        children![
+            title("Settings"),
+            (
+                scroll_row(),
+                children![
+                    (
+                        column(),
+                        children![
+                            (
+                                row(),
+                                children![
+                                    setting_label(SettingLabel::HudPosition),
+                                    small_button(MenuAction::CycleHudPosition, ">"),
+                                ],
+                            ),
+                            (
+                                row(),
+                                children![
+                                    small_button(MenuAction::DecreaseHudScale, "-"),
+                                    setting_label(SettingLabel::HudScale),
+                                    small_button(MenuAction::IncreaseHudScale, "+"),
+                                ],
+                            ),
+                            (
+                                row(),
+                                children![
+                                    setting_label(SettingLabel::Theme),
+                                    small_button(MenuAction::CycleTheme, ">"),
+                                ],
+                            ),
+                            (
+                                row(),
+                                children![
+                                    setting_label(SettingLabel::PipeDepthTint),
+                                    small_button(MenuAction::TogglePipeDepthTint, ">"),
+                                ],
+                            ),
+                            (
+                                row(),
+                                children![
+                                    setting_label(SettingLabel::Vignette),
+                                    small_button(MenuAction::ToggleVignette, ">"),
+                                ],
+                            ),
+                            (
+                                row(),
+                                children![
+                                    small_button(MenuAction::DecreaseVignetteStrength, "-"),
+                                    setting_label(SettingLabel::VignetteStrength),
+                                    small_button(MenuAction::IncreaseVignetteStrength, "+"),
+                                ],
+                            ),
+                            (
+                                row(),
+                                children![
+                                    setting_label(SettingLabel::Monitor),
+                                    small_button(MenuAction::CycleMonitor, ">"),
+                                ],
+                            ),
+                            (
+                                row(),
+                                children![
+                                    setting_label(SettingLabel::PixelSnap),
+                                    small_button(MenuAction::TogglePixelSnap, ">"),
+                                ],
+                            ),
+                            (
+                                row(),
+                                children![
+                                    setting_label(SettingLabel::Antialiasing),
+                                    small_button(MenuAction::CycleAntialiasing, ">"),
+                                ],
+                            ),
+                            (
+                                row(),
+                                children![
+                                    setting_label(SettingLabel::ReducedMotion),
+                                    small_button(MenuAction::ToggleReducedMotion, ">"),
+                                ],
+                            ),
+                            (
+                                row(),
+                                children![
+                                    setting_label(SettingLabel::SeedDisplay),
+                                    small_button(MenuAction::ToggleSeedDisplay, ">"),
+                                ],
+                            ),
+                            (
+                                row(),
+                                children![
+                                    setting_label(SettingLabel::RunLog),
+                                    small_button(MenuAction::ToggleRunLog, ">"),
+                                    small_button(MenuAction::OpenRunLog, "..."),
+                                ],
+                            ),
+                        ],
+                    ),
+                    (
+                        column(),
+                        children![
+                            (
+                                row(),
+                                children![
+                                    setting_label(SettingLabel::InstantQuit),
+                                    small_button(MenuAction::ToggleInstantQuit, ">"),
+                                ],
+                            ),
+                            (
+                                row(),
+                                children![
+                                    setting_label(SettingLabel::ControllerPause),
+                                    small_button(MenuAction::ToggleControllerPause, ">"),
+                                ],
+                            ),
+                            (
+                                row(),
+                                children![
+                                    small_button(MenuAction::DecreaseStartingScore, "-"),
+                                    setting_label(SettingLabel::StartingScore),
+                                    small_button(MenuAction::IncreaseStartingScore, "+"),
+                                ],
+                            ),
+                            (
+                                row(),
+                                children![
+                                    small_button(MenuAction::DecreaseMinSavedScore, "-"),
+                                    setting_label(SettingLabel::MinSavedScore),
+                                    small_button(MenuAction::IncreaseMinSavedScore, "+"),
+                                ],
+                            ),
+                            (
+                                row(),
+                                children![
+                                    small_button(MenuAction::DecreaseLives, "-"),
+                                    setting_label(SettingLabel::Lives),
+                                    small_button(MenuAction::IncreaseLives, "+"),
+                                ],
+                            ),
+                            (
+                                row(),
+                                children![
+                                    setting_label(SettingLabel::AssistGuide),
+                                    small_button(MenuAction::ToggleAssistGuide, ">"),
+                                ],
+                            ),
+                            (
+                                row(),
+                                children![
+                                    setting_label(SettingLabel::TrajectoryGuide),
+                                    small_button(MenuAction::ToggleTrajectoryGuide, ">"),
+                                ],
+                            ),
+                            (
+                                row(),
+                                children![
+                                    setting_label(SettingLabel::AssistLevel),
+                                    small_button(MenuAction::CycleAssistLevel, ">"),
+                                ],
+                            ),
+                            (
+                                row(),
+                                children![
+                                    small_button(MenuAction::DecreaseTutorialGames, "-"),
+                                    setting_label(SettingLabel::TutorialGames),
+                                    small_button(MenuAction::IncreaseTutorialGames, "+"),
+                                ],
+                            ),
+                            (
+                                row(),
+                                children![
+                                    small_button(MenuAction::DecreaseRescueLookahead, "-"),
+                                    setting_label(SettingLabel::RescueLookahead),
+                                    small_button(MenuAction::IncreaseRescueLookahead, "+"),
+                                ],
+                            ),
+                            (
+                                row(),
+                                children![
+                                    small_button(MenuAction::DecreaseTurboSpeed, "-"),
+                                    setting_label(SettingLabel::TurboSpeed),
+                                    small_button(MenuAction::IncreaseTurboSpeed, "+"),
+                                ],
+                            ),
+                            (
+                                row(),
+                                children![
+                                    setting_label(SettingLabel::Feel),
+                                    small_button(MenuAction::CycleFeel, ">"),
+                                ],
+                            ),
+                            (
+                                row(),
+                                children![
+                                    setting_label(SettingLabel::GravityCurve),
+                                    small_button(MenuAction::CycleGravityCurve, ">"),
+                                ],
+                            ),
+                            (
+                                row(),
+                                children![
+                                    setting_label(SettingLabel::JumpMode),
+                                    small_button(MenuAction::CycleJumpMode, ">"),
+                                ],
+                            ),
+                            (
+                                row(),
+                                children![
+                                    setting_label(SettingLabel::Glide),
+                                    small_button(MenuAction::ToggleGlide, ">"),
+                                ],
+                            ),
+                            (
+                                row(),
+                                children![
+                                    setting_label(SettingLabel::PerfectRun),
+                                    small_button(MenuAction::CyclePerfectRun, ">"),
+                                ],
+                            ),
+                            (
+                                row(),
+                                children![
+                                    setting_label(SettingLabel::SmoothGaps),
+                                    small_button(MenuAction::ToggleSmoothGaps, ">"),
+                                ],
+                            ),
+                            (
+                                row(),
+                                children![
+                                    setting_label(SettingLabel::ShieldPickups),
+                                    small_button(MenuAction::ToggleShieldPickups, ">"),
+                                ],
+                            ),
+                            (
+                                row(),
+                                children![
+                                    setting_label(SettingLabel::FlapButton),
+                                    small_button(MenuAction::CycleFlapButton, ">"),
+                                ],
+                            ),
+                            (
+                                row(),
+                                children![
+                                    setting_label(SettingLabel::AutoFlap),
+                                    small_button(MenuAction::ToggleAutoFlap, ">"),
+                                ],
+                            ),
+                            (
+                                row(),
+                                children![
+                                    small_button(MenuAction::DecreaseStartGrace, "-"),
+                                    setting_label(SettingLabel::StartGrace),
+                                    small_button(MenuAction::IncreaseStartGrace, "+"),
+                                ],
+                            ),
+                            (
+                                row(),
+                                children![
+                                    small_button(MenuAction::DecreaseRetryCooldown, "-"),
+                                    setting_label(SettingLabel::RetryCooldown),
+                                    small_button(MenuAction::IncreaseRetryCooldown, "+"),
+                                ],
+                            ),
+                            (
+                                row(),
+                                children![
+                                    setting_label(SettingLabel::AutoRestart),
+                                    small_button(MenuAction::ToggleAutoRestart, ">"),
+                                ],
+                            ),
+                            (
+                                row(),
+                                children![
+                                    small_button(MenuAction::DecreaseAutoRestartDelay, "-"),
+                                    setting_label(SettingLabel::AutoRestartDelay),
+                                    small_button(MenuAction::IncreaseAutoRestartDelay, "+"),
+                                ],
+                            ),
+                            (
+                                row(),
+                                children![
+                                    small_button(MenuAction::DecreaseBirdScale, "-"),
+                                    setting_label(SettingLabel::BirdScale),
+                                    small_button(MenuAction::IncreaseBirdScale, "+"),
+                                ],
+                            ),
+                            (
+                                row(),
+                                children![
+                                    small_button(MenuAction::DecreaseRumbleStrength, "-"),
+                                    setting_label(SettingLabel::RumbleStrength),
+                                    small_button(MenuAction::IncreaseRumbleStrength, "+"),
+                                ],
+                            ),
+                            (
+                                row(),
+                                children![
+                                    small_button(MenuAction::DecreaseGamepadDeadzone, "-"),
+                                    setting_label(SettingLabel::GamepadDeadzone),
+                                    small_button(MenuAction::IncreaseGamepadDeadzone, "+"),
+                                ],
+                            ),
+                            (
+                                row(),
+                                children![
+                                    small_button(MenuAction::DecreaseMusicVolume, "-"),
+                                    setting_label(SettingLabel::MusicVolume),
+                                    small_button(MenuAction::IncreaseMusicVolume, "+"),
+                                ],
+                            ),
+                            (
+                                row(),
+                                children![
+                                    setting_label(SettingLabel::QuietHours),
+                                    small_button(MenuAction::ToggleQuietHours, ">"),
+                                ],
+                            ),
+                            (
+                                row(),
+                                children![
+                                    small_button(MenuAction::DecreaseQuietHoursStart, "-"),
+                                    setting_label(SettingLabel::QuietHoursStart),
+                                    small_button(MenuAction::IncreaseQuietHoursStart, "+"),
+                                ],
+                            ),
+                            (
+                                row(),
+                                children![
+                                    small_button(MenuAction::DecreaseQuietHoursEnd, "-"),
+                                    setting_label(SettingLabel::QuietHoursEnd),
+                                    small_button(MenuAction::IncreaseQuietHoursEnd, "+"),
+                                ],
+                            ),
+                        ],
+                    ),
+                ],
+            ),
+            button(MenuAction::Back, "Back"),
+        ],
+    ));
+}
+
+fn spawn_custom_menu(mut commands: Commands) {
+    commands.spawn((
+        menu_root(MenuScreen::Custom),
+        children![
+            title("Custom"),
+            (
+                row(),
+                children![
+                    small_button(MenuAction::DecreasePipeSpacing, "-"),
+                    setting_label(SettingLabel::PipeSpacing),
+                    small_button(MenuAction::IncreasePipeSpacing, "+"),
+                ],
+            ),
+            (
+                row(),
+                children![
+                    setting_label(SettingLabel::GapDistribution),
+                    small_button(MenuAction::CycleGapDistribution, ">"),
+                ],
+            ),
+            button(MenuAction::PlayCustom, "Play"),
+            (
+                row(),
+                children![
+                    small_button(MenuAction::DecreaseFlockSize, "-"),
+                    setting_label(SettingLabel::FlockSize),
+                    small_button(MenuAction::IncreaseFlockSize, "+"),
+                ],
+            ),
+            button(MenuAction::PlayFlock, "Play flock"),
+            (
+                row(),
+                children![
+                    setting_label(SettingLabel::Pattern),
+                    small_button(MenuAction::CyclePattern, ">"),
+                ],
+            ),
+            button(MenuAction::PlayPattern, "Play pattern"),
+            button(MenuAction::PlayScrolling, "Play tall world"),
+            (
+                row(),
+                children![
+                    small_button(MenuAction::DecreaseScoreDecayRate, "-"),
+                    setting_label(SettingLabel::ScoreDecayRate),
+                    small_button(MenuAction::IncreaseScoreDecayRate, "+"),
+                ],
+            ),
+            button(MenuAction::PlayScoreDecay, "Play score decay"),
+            (
+                row(),
+                children![
+                    small_button(MenuAction::DecreasePracticeStartPipe, "-"),
+                    setting_label(SettingLabel::PracticeStartPipe),
+                    small_button(MenuAction::IncreasePracticeStartPipe, "+"),
+                ],
+            ),
+            button(MenuAction::PlayPractice, "Play practice"),
+            button(MenuAction::Back, "Back"),
+        ],
+    ));
+}
+
+// The dialog goes with the pause, the dim around it fades in and out in pause.rs
+fn pause_dim(settings: &Settings) -> impl Bundle {
+    let alpha = if settings.reduced_motion {
+        PAUSE_DIM_ALPHA
+    } else {
+        0.
+    };
+    (
+        PauseDim::default(),
+        Node {
+            width: percent(100),
+            height: percent(100),
+            ..default()
+        },
+        BackgroundColor(Color::BLACK.with_alpha(alpha)),
+    )
+}
+
+fn spawn_quit_dialog(
+    mut commands: Commands,
+    pause_reason: Res<PauseReason>,
+    settings: Res<Settings>,
+) {
+    if *pause_reason == PauseReason::ControllerDisconnected {
+        commands.spawn((
+            pause_dim(&settings),
+            children![(
+                menu_root(PauseState::Paused),
+                children![
+                    title("Controller disconnected"),
+                    Text::new("Reconnect it or press any key to resume"),
+                ],
+            )],
+        ));
+        return;
+    }
+
+    commands.spawn((
+        pause_dim(&settings),
+        children![(
+            menu_root(PauseState::Paused),
+            children![
+                title("Quit game?"),
+                button(MenuAction::Quit, "Quit"),
+                button(MenuAction::Resume, "Cancel"),
+            ],
+        )],
+    ));
+}
+
+// Sits at the bottom so the altitude graph above stays visible
+fn spawn_game_over_menu(mut commands: Commands) {
+    commands.spawn((
+        Node {
+            width: percent(100),
+            height: percent(100),
+            flex_direction: FlexDirection::Row,
+            align_items: AlignItems::FlexEnd,
+            justify_content: JustifyContent::Center,
+            padding: UiRect::bottom(px(40)),
+            ..default()
+        },
+        DespawnOnExit(AppState::GameOver),
+        children![
+            button(MenuAction::Retry, "Retry"),
+            button(MenuAction::RetrySame, "Retry same"),
+        ],
+    ));
+}
+
+fn reset_game_over_timer(settings: Res<Settings>, mut game_over_timer: ResMut<GameOverTimer>) {
+    game_over_timer.0 = Timer::from_seconds(settings.retry_cooldown, TimerMode::Once);
+}
+
+// Starts out run down, so the first Esc only arms it
+fn spawn_quit_prompt(mut commands: Commands, mut quit_confirm: ResMut<QuitConfirm>) {
+    let mut timer = Timer::from_seconds(QUIT_CONFIRM_WINDOW, TimerMode::Once);
+    timer.tick(timer.duration());
+    quit_confirm.0 = timer;
+
+    commands.spawn((
+        QuitPrompt,
+        Text::new("Press Esc again to quit"),
+        TextFont {
+            font_size: 24.0,
+            ..default()
+        },
+        Node {
+            position_type: PositionType::Absolute,
+            top: px(10),
+            width: percent(100),
+            justify_content: JustifyContent::Center,
+            ..default()
+        },
+        TextLayout {
+            justify: Justify::Center,
+            ..default()
+        },
+        Visibility::Hidden,
+        Pickable::IGNORE,
+        DespawnOnExit(AppState::GameOver),
+    ));
+}
+
+// Same rule as Esc during a run: quits at once with instant quit on, otherwise it asks first
+fn game_over_quit(
+    actions: Res<ActionState>,
+    settings: Res<Settings>,
+    time: Res<Time<Real>>,
+    mut quit_confirm: ResMut<QuitConfirm>,
+    mut prompt: Single<&mut Visibility, With<QuitPrompt>>,
+    mut app_exit: MessageWriter<AppExit>,
+) {
+    quit_confirm.0.tick(time.delta());
+
+    if actions.just_pressed(Action::Back) {
+        if settings.instant_quit || !quit_confirm.0.is_finished() {
+            app_exit.write(AppExit::Success);
+            return;
+        }
+        quit_confirm.0.reset();
+    }
+
+    **prompt = if quit_confirm.0.is_finished() {
+        Visibility::Hidden
+    } else {
+        Visibility::Inherited
+    };
+}
+
+// Real time, so the delay is the same in slow motion and turbo runs
+fn tick_game_over_timer(time: Res<Time<Real>>, mut game_over_timer: ResMut<GameOverTimer>) {
+    game_over_timer.0.tick(time.delta());
+}
+
+// Retry buttons stay greyed out until they accept clicks
+fn show_retry_cooldown(
+    game_over_timer: Res<GameOverTimer>,
+    button_query: Query<(&MenuAction, &Children)>,
+    mut text_query: Query<&mut TextColor>,
+) {
+    let color = if game_over_timer.0.is_finished() {
+        Color::WHITE
+    } else {
+        Color::srgb(0.4, 0.4, 0.4)
+    };
+
+    for (action, children) in button_query.iter() {
+        if !matches!(action, MenuAction::Retry | MenuAction::RetrySame) {
+            continue;
+        }
+        for &child in children {
+            if let Ok(mut text_color) = text_query.get_mut(child) {
+                text_color.0 = color;
+            }
+        }
+    }
+}
+
+fn menu_back(screen: Res<State<MenuScreen>>, mut next_screen: ResMut<NextState<MenuScreen>>) {
+    if *screen.get() != MenuScreen::Main {
+        next_screen.set(MenuScreen::Main);
+    }
+}
+
+#[allow(
+    clippy::type_complexity,
+    reason = "only recolors buttons whose interaction changed"
+)]
+fn button_colors(
+    mut query: Query<(&Interaction, &mut BackgroundColor), (Changed<Interaction>, With<Button>)>,
+) {
+    for (interaction, mut background) in query.iter_mut() {
+        background.0 = match interaction {
+            Interaction::Pressed => PRESSED_BUTTON,
+            Interaction::Hovered => HOVERED_BUTTON,
+            Interaction::None => NORMAL_BUTTON,
+        };
+    }
+}
+
+fn scroll_menu(
+    mut wheel_events: MessageReader<MouseWheel>,
+    mut query: Query<(&mut ScrollPosition, &ComputedNode), With<MenuScroll>>,
+) {
+    let delta: f32 = wheel_events
+        .read()
+        .map(|event| match event.unit {
+            MouseScrollUnit::Line => event.y * MENU_SCROLL_LINE_HEIGHT,
+            MouseScrollUnit::Pixel => event.y,
+        })
+        .sum();
+    if delta == 0. {
+        return;
+    }
+
+    // The layout only clamps what it draws, so the position is kept in range here
+    for (mut scroll_position, node) in query.iter_mut() {
+        let max_y = ((node.content_size().y - node.size().y) * node.inverse_scale_factor()).max(0.);
+        scroll_position.y = (scroll_position.y - delta).clamp(0., max_y);
+    }
+}
+
+#[allow(
+    clippy::too_many_arguments,
+    reason = "every menu button is handled in this one match"
+)]
+fn menu_action(
+    query: Query<(&Interaction, &MenuAction), Changed<Interaction>>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut next_screen: ResMut<NextState<MenuScreen>>,
+    mut next_pause: ResMut<NextState<PauseState>>,
+    mut settings: ResMut<Settings>,
+    mut custom_mode: ResMut<CustomModeSettings>,
+    mut mode: ResMut<GameMode>,
+    monitors: Query<&Monitor>,
+    patterns: Res<PipePatterns>,
+    mut retry_same_seed: ResMut<RetrySameSeed>,
+    game_over_timer: Res<GameOverTimer>,
+    mut app_exit: MessageWriter<AppExit>,
+) {
+    for (interaction, action) in query.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        match action {
+            MenuAction::Play => {
+                *mode = GameMode::Normal;
+                next_state.set(AppState::GameStart);
+            }
+            MenuAction::PlayCustom => {
+                *mode = GameMode::Custom;
+                next_state.set(AppState::GameStart);
+            }
+            MenuAction::PlayAdaptive => {
+                *mode = GameMode::Adaptive;
+                next_state.set(AppState::GameStart);
+            }
+            MenuAction::PlayPractice => {
+                *mode = GameMode::Practice;
+                next_state.set(AppState::GameStart);
+            }
+            MenuAction::PlayPattern => {
+                *mode = GameMode::Pattern;
+                next_state.set(AppState::GameStart);
+            }
+            MenuAction::PlayScrolling => {
+                *mode = GameMode::Scrolling;
+                next_state.set(AppState::GameStart);
+            }
+            MenuAction::PlayScoreAttack => {
+                *mode = GameMode::ScoreAttack;
+                next_state.set(AppState::GameStart);
+            }
+            MenuAction::PlayScoreDecay => {
+                *mode = GameMode::ScoreDecay;
+                next_state.set(AppState::GameStart);
+            }
+            MenuAction::CycleGapDistribution => {
+                custom_mode.gap_distribution = custom_mode.gap_distribution.next();
+            }
+            MenuAction::CyclePattern => {
+                custom_mode.pattern = (custom_mode.pattern + 1) % patterns.0.len().max(1);
+            }
+            MenuAction::PlayFlock => {
+                *mode = GameMode::Flock;
+                next_state.set(AppState::GameStart);
+            }
+            MenuAction::Settings => next_screen.set(MenuScreen::Settings),
+            MenuAction::Custom => next_screen.set(MenuScreen::Custom),
+            MenuAction::Back => next_screen.set(MenuScreen::Main),
+            MenuAction::Quit => {
+                app_exit.write(AppExit::Success);
+            }
+            MenuAction::Resume => next_pause.set(PauseState::Running),
+            MenuAction::Retry | MenuAction::RetrySame if !game_over_timer.0.is_finished() => {}
+            MenuAction::Retry => {
+                retry_same_seed.0 = false;
+                next_state.set(AppState::GameStart);
+            }
+            MenuAction::RetrySame => {
+                retry_same_seed.0 = true;
+                next_state.set(AppState::GameStart);
+            }
+            MenuAction::ToggleInstantQuit => {
+                settings.instant_quit = !settings.instant_quit;
+            }
+            MenuAction::ToggleTrajectoryGuide => {
+                settings.trajectory_guide = !settings.trajectory_guide;
+            }
+            MenuAction::ToggleAssistGuide => {
+                settings.assist_guide = !settings.assist_guide;
+            }
+            MenuAction::CycleAssistLevel => {
+                settings.assist_level = settings.assist_level.next();
+            }
+            MenuAction::DecreaseTutorialGames => {
+                settings.tutorial_games = settings.tutorial_games.saturating_sub(1);
+            }
+            MenuAction::IncreaseTutorialGames => {
+                settings.tutorial_games = (settings.tutorial_games + 1).min(MAX_TUTORIAL_GAMES);
+            }
+            MenuAction::DecreaseRescueLookahead => {
+                settings.rescue_lookahead =
+                    (settings.rescue_lookahead - RESCUE_LOOKAHEAD_STEP).max(0.);
+            }
+            MenuAction::IncreaseRescueLookahead => {
+                settings.rescue_lookahead =
+                    (settings.rescue_lookahead + RESCUE_LOOKAHEAD_STEP).min(MAX_RESCUE_LOOKAHEAD);
+            }
+            MenuAction::CycleGravityCurve => {
+                settings.gravity_curve = settings.gravity_curve.next();
+            }
+            MenuAction::CycleJumpMode => {
+                settings.jump_mode = settings.jump_mode.next();
+            }
+            MenuAction::ToggleGlide => {
+                settings.glide = !settings.glide;
+            }
+            MenuAction::CyclePerfectRun => {
+                settings.perfect_run = settings.perfect_run.next();
+            }
+            MenuAction::ToggleSmoothGaps => {
+                settings.smooth_gaps = !settings.smooth_gaps;
+            }
+            MenuAction::ToggleShieldPickups => {
+                settings.shield_pickups = !settings.shield_pickups;
+            }
+            MenuAction::CycleFeel => {
+                settings.feel = settings.feel.next();
+            }
+            MenuAction::ToggleControllerPause => {
+                settings.pause_on_controller_disconnect = !settings.pause_on_controller_disconnect;
+            }
+            MenuAction::DecreaseBirdScale => {
+                settings.bird_scale = (settings.bird_scale - BIRD_SCALE_STEP).max(MIN_BIRD_SCALE);
+            }
+            MenuAction::IncreaseBirdScale => {
+                settings.bird_scale = (settings.bird_scale + BIRD_SCALE_STEP).min(MAX_BIRD_SCALE);
+            }
+            MenuAction::DecreaseRetryCooldown => {
+                settings.retry_cooldown = (settings.retry_cooldown - RETRY_COOLDOWN_STEP).max(0.);
+            }
+            MenuAction::IncreaseRetryCooldown => {
+                settings.retry_cooldown =
+                    (settings.retry_cooldown + RETRY_COOLDOWN_STEP).min(MAX_RETRY_COOLDOWN);
+            }
+            MenuAction::ToggleAutoRestart => {
+                settings.auto_restart = !settings.auto_restart;
+            }
+            MenuAction::DecreaseAutoRestartDelay => {
+                settings.auto_restart_delay = (settings.auto_restart_delay
+                    - AUTO_RESTART_DELAY_STEP)
+                    .max(MIN_AUTO_RESTART_DELAY);
+            }
+            MenuAction::IncreaseAutoRestartDelay => {
+                settings.auto_restart_delay = (settings.auto_restart_delay
+                    + AUTO_RESTART_DELAY_STEP)
+                    .min(MAX_AUTO_RESTART_DELAY);
+            }
+            MenuAction::DecreaseStartGrace => {
+                settings.start_grace = (settings.start_grace - START_GRACE_STEP).max(0.);
+            }
+            MenuAction::IncreaseStartGrace => {
+                settings.start_grace =
+                    (settings.start_grace + START_GRACE_STEP).min(MAX_START_GRACE);
+            }
+            MenuAction::CycleFlapButton => {
+                let bindings = &mut settings.input_bindings;
+                bindings.flap_mouse_button = bindings.flap_mouse_button.next();
+            }
+            MenuAction::ToggleAutoFlap => {
+                settings.auto_flap = !settings.auto_flap;
+            }
+            MenuAction::ToggleSeedDisplay => {
+                settings.show_seed = !settings.show_seed;
+            }
+            MenuAction::ToggleReducedMotion => {
+                settings.reduced_motion = !settings.reduced_motion;
+            }
+            MenuAction::DecreaseTurboSpeed => {
+                settings.turbo_speed =
+                    (settings.turbo_speed - TURBO_SPEED_STEP).max(MIN_TURBO_SPEED);
+            }
+            MenuAction::IncreaseTurboSpeed => {
+                settings.turbo_speed =
+                    (settings.turbo_speed + TURBO_SPEED_STEP).min(MAX_TURBO_SPEED);
+            }
+            MenuAction::DecreaseRumbleStrength => {
+                settings.rumble_strength =
+                    (settings.rumble_strength - RUMBLE_STRENGTH_STEP).max(0.);
+            }
+            MenuAction::IncreaseRumbleStrength => {
+                settings.rumble_strength =
+                    (settings.rumble_strength + RUMBLE_STRENGTH_STEP).min(1.);
+            }
+            MenuAction::DecreaseGamepadDeadzone => {
+                settings.gamepad_deadzone =
+                    (settings.gamepad_deadzone - GAMEPAD_DEADZONE_STEP).max(MIN_GAMEPAD_DEADZONE);
+            }
+            MenuAction::IncreaseGamepadDeadzone => {
+                settings.gamepad_deadzone =
+                    (settings.gamepad_deadzone + GAMEPAD_DEADZONE_STEP).min(MAX_GAMEPAD_DEADZONE);
+            }
+            MenuAction::DecreaseMusicVolume => {
+                settings.music_volume = (settings.music_volume - MUSIC_VOLUME_STEP).max(0.);
+            }
+            MenuAction::IncreaseMusicVolume => {
+                settings.music_volume = (settings.music_volume + MUSIC_VOLUME_STEP).min(1.);
+            }
+            MenuAction::ToggleQuietHours => {
+                settings.quiet_hours = !settings.quiet_hours;
+            }
+            MenuAction::DecreaseQuietHoursStart => {
+                settings.quiet_hours_start = (settings.quiet_hours_start + 23) % 24;
+            }
+            MenuAction::IncreaseQuietHoursStart => {
+                settings.quiet_hours_start = (settings.quiet_hours_start + 1) % 24;
+            }
+            MenuAction::DecreaseQuietHoursEnd => {
+                settings.quiet_hours_end = (settings.quiet_hours_end + 23) % 24;
+            }
+            MenuAction::IncreaseQuietHoursEnd => {
+                settings.quiet_hours_end = (settings.quiet_hours_end + 1) % 24;
+            }
+            MenuAction::DecreaseLives => {
+                settings.lives = settings.lives.saturating_sub(1).max(1);
+            }
+            MenuAction::IncreaseLives => {
+                settings.lives = (settings.lives + 1).min(MAX_LIVES);
+            }
+            MenuAction::DecreaseStartingScore => {
+                settings.starting_score = (settings.starting_score - STARTING_SCORE_STEP).max(0);
+            }
+            MenuAction::IncreaseStartingScore => {
+                settings.starting_score =
+                    (settings.starting_score + STARTING_SCORE_STEP).min(MAX_STARTING_SCORE);
+            }
+            MenuAction::DecreaseMinSavedScore => {
+                settings.min_saved_score = (settings.min_saved_score - 1).max(0);
+            }
+            MenuAction::IncreaseMinSavedScore => {
+                settings.min_saved_score = (settings.min_saved_score + 1).min(MAX_MIN_SAVED_SCORE);
+            }
+            MenuAction::CycleHudPosition => {
+                settings.hud_position = settings.hud_position.next();
+            }
+            MenuAction::CycleMonitor => {
+                settings.preferred_monitor = next_monitor(&settings.preferred_monitor, &monitors);
+            }
+            MenuAction::ToggleRunLog => {
+                settings.log_runs = !settings.log_runs;
+            }
+            MenuAction::OpenRunLog => open_run_log_location(),
+            MenuAction::TogglePixelSnap => {
+                settings.pixel_snap = !settings.pixel_snap;
+            }
+            MenuAction::CycleAntialiasing => {
+                settings.antialiasing = settings.antialiasing.next();
+            }
+            MenuAction::CycleTheme => {
+                settings.theme = settings.theme.next();
+            }
+            MenuAction::TogglePipeDepthTint => {
+                settings.pipe_depth_tint = !settings.pipe_depth_tint;
+            }
+            MenuAction::ToggleVignette => {
+                settings.vignette = !settings.vignette;
+            }
+            MenuAction::DecreaseVignetteStrength => {
+                settings.vignette_strength = (settings.vignette_strength - VIGNETTE_STRENGTH_STEP)
+                    .max(MIN_VIGNETTE_STRENGTH);
+            }
+            MenuAction::IncreaseVignetteStrength => {
+                settings.vignette_strength = (settings.vignette_strength + VIGNETTE_STRENGTH_STEP)
+                    .min(MAX_VIGNETTE_STRENGTH);
+            }
+            MenuAction::DecreaseHudScale => {
+                settings.hud_scale = (settings.hud_scale - HUD_SCALE_STEP).max(MIN_HUD_SCALE);
+            }
+            MenuAction::IncreaseHudScale => {
+                settings.hud_scale = (settings.hud_scale + HUD_SCALE_STEP).min(MAX_HUD_SCALE);
+            }
+            MenuAction::DecreasePipeSpacing => {
+                custom_mode.pipe_spacing =
+                    (custom_mode.pipe_spacing - PIPE_SPACING_STEP).max(MIN_PIPE_SPACING);
+            }
+            MenuAction::IncreasePipeSpacing => {
+                custom_mode.pipe_spacing =
+                    (custom_mode.pipe_spacing + PIPE_SPACING_STEP).min(MAX_PIPE_SPACING);
+            }
+            MenuAction::DecreaseFlockSize => {
+                custom_mode.flock_size =
+                    custom_mode.flock_size.saturating_sub(1).max(MIN_FLOCK_SIZE);
+            }
+            MenuAction::IncreaseFlockSize => {
+                custom_mode.flock_size = (custom_mode.flock_size + 1).min(MAX_FLOCK_SIZE);
+            }
+            MenuAction::DecreasePracticeStartPipe => {
+                custom_mode.practice_start_pipe =
+                    custom_mode.practice_start_pipe.saturating_sub(1).max(1);
+            }
+            MenuAction::IncreasePracticeStartPipe => {
+                custom_mode.practice_start_pipe =
+                    (custom_mode.practice_start_pipe + 1).min(MAX_PRACTICE_START_PIPE);
+            }
+            MenuAction::DecreaseScoreDecayRate => {
+                custom_mode.score_decay_rate = (custom_mode.score_decay_rate
+                    - SCORE_DECAY_RATE_STEP)
+                    .max(MIN_SCORE_DECAY_RATE);
+            }
+            MenuAction::IncreaseScoreDecayRate => {
+                custom_mode.score_decay_rate = (custom_mode.score_decay_rate
+                    + SCORE_DECAY_RATE_STEP)
+                    .min(MAX_SCORE_DECAY_RATE);
+            }
+        }
+    }
+}
+
+fn update_setting_labels(
+    settings: Res<Settings>,
+    custom_mode: Res<CustomModeSettings>,
+    patterns: Res<PipePatterns>,
+    mut query: Query<(&mut Text, &SettingLabel)>,
+) {
+    for (mut text, label) in query.iter_mut() {
+        text.0 = match label {
+            SettingLabel::HudPosition => format!("HUD: {:?}", settings.hud_position),
+            SettingLabel::Monitor => format!(
+                "Display: {}",
+                settings.preferred_monitor.as_deref().unwrap_or("Primary")
+            ),
+            SettingLabel::PixelSnap => format!("Pixel snapping: {}", on_off(settings.pixel_snap)),
+            SettingLabel::Antialiasing => format!(
+                "Anti-aliasing: {}",
+                match settings.antialiasing {
+                    Antialiasing::Off => "Off",
+                    Antialiasing::X2 => "2x",
+                    Antialiasing::X4 => "4x",
+                }
+            ),
+            SettingLabel::RunLog => format!("Log runs: {}", on_off(settings.log_runs)),
+            SettingLabel::Theme => format!("Theme: {:?}", settings.theme),
+            SettingLabel::PipeDepthTint => {
+                format!("Pipe depth tint: {}", on_off(settings.pipe_depth_tint))
+            }
+            SettingLabel::Vignette => format!("Vignette: {}", on_off(settings.vignette)),
+            SettingLabel::VignetteStrength => format!(
+                "Vignette strength: {:.0}%",
+                settings.vignette_strength * 100.
+            ),
+            SettingLabel::HudScale => format!("HUD scale: {:.2}", settings.hud_scale),
+            SettingLabel::InstantQuit => {
+                format!("Esc quits instantly: {}", on_off(settings.instant_quit))
+            }
+            SettingLabel::AssistGuide => format!(
+                "Gap guide assist: {} (unranked)",
+                on_off(settings.assist_guide)
+            ),
+            SettingLabel::TrajectoryGuide => format!(
+                "Trajectory guide: {} (unranked)",
+                on_off(settings.trajectory_guide)
+            ),
+            SettingLabel::AssistLevel if settings.assist_level == AssistLevel::None => {
+                "Easy assist: Off".into()
+            }
+            SettingLabel::AssistLevel => {
+                format!("Easy assist: {:?} (unranked)", settings.assist_level)
+            }
+            SettingLabel::TutorialGames if settings.tutorial_games == 0 => {
+                "First-game saves: Off".into()
+            }
+            SettingLabel::TutorialGames => format!(
+                "First-game saves: {} games (unranked)",
+                settings.tutorial_games
+            ),
+            SettingLabel::RescueLookahead if settings.rescue_lookahead <= 0. => {
+                "Rescue flap: Off".into()
+            }
+            SettingLabel::RescueLookahead => format!(
+                "Rescue flap: {:.2}s ahead (unranked)",
+                settings.rescue_lookahead
+            ),
+            SettingLabel::Feel => format!("Bird feel: {:?}", settings.feel),
+            SettingLabel::GravityCurve => format!("Gravity curve: {:?}", settings.gravity_curve),
+            SettingLabel::JumpMode => format!("Flap: {:?}", settings.jump_mode),
+            SettingLabel::Glide if settings.glide => "Hold to glide: On (unranked)".into(),
+            SettingLabel::Glide => "Hold to glide: Off".into(),
+            SettingLabel::PerfectRun => format!(
+                "Perfect runs: {}",
+                match settings.perfect_run {
+                    PerfectCriteria::Off => "Off",
+                    PerfectCriteria::NoHits => "No hits",
+                    PerfectCriteria::Centered => "Centered passes",
+                }
+            ),
+            SettingLabel::SmoothGaps if settings.smooth_gaps => "Smooth gaps: On (unranked)".into(),
+            SettingLabel::SmoothGaps => "Smooth gaps: Off".into(),
+            SettingLabel::ShieldPickups if settings.shield_pickups => {
+                "Shield pickups: On (unranked)".into()
+            }
+            SettingLabel::ShieldPickups => "Shield pickups: Off".into(),
+            SettingLabel::ControllerPause => format!(
+                "Pause on controller loss: {}",
+                on_off(settings.pause_on_controller_disconnect)
+            ),
+            SettingLabel::BirdScale if settings.bird_scale == 1. => "Bird size: 100%".into(),
+            SettingLabel::BirdScale => {
+                format!("Bird size: {:.0}% (unranked)", settings.bird_scale * 100.)
+            }
+            SettingLabel::RetryCooldown if settings.retry_cooldown <= 0. => {
+                "Retry delay: Off".into()
+            }
+            SettingLabel::RetryCooldown => {
+                format!("Retry delay: {:.2}s", settings.retry_cooldown)
+            }
+            SettingLabel::AutoRestart => {
+                format!("Kiosk auto-restart: {}", on_off(settings.auto_restart))
+            }
+            SettingLabel::AutoRestartDelay => {
+                format!("Auto-restart after: {:.0}s", settings.auto_restart_delay)
+            }
+            SettingLabel::StartGrace if settings.start_grace <= 0. => "Start hover: Off".into(),
+            SettingLabel::StartGrace => {
+                format!("Start hover: {:.2}s (unranked)", settings.start_grace)
+            }
+            SettingLabel::FlapButton => format!(
+                "Flap mouse button: {:?}",
+                settings.input_bindings.flap_mouse_button
+            ),
+            SettingLabel::AutoFlap => {
+                format!("Hold to flap: {} (unranked)", on_off(settings.auto_flap))
+            }
+            SettingLabel::SeedDisplay => format!("Show seed: {}", on_off(settings.show_seed)),
+            SettingLabel::ReducedMotion => {
+                format!("Reduced motion: {}", on_off(settings.reduced_motion))
+            }
+            SettingLabel::TurboSpeed => {
+                format!("Turbo (F): {:.2}x (unranked)", settings.turbo_speed)
+            }
+            SettingLabel::RumbleStrength if settings.rumble_strength <= 0. => {
+                "Gamepad rumble: Off".into()
+            }
+            SettingLabel::RumbleStrength => {
+                format!("Gamepad rumble: {:.0}%", settings.rumble_strength * 100.)
+            }
+            SettingLabel::GamepadDeadzone => {
+                format!("Trigger deadzone: {:.0}%", settings.gamepad_deadzone * 100.)
+            }
+            SettingLabel::MusicVolume if settings.music_volume <= 0. => "Music: Off".into(),
+            SettingLabel::MusicVolume => {
+                format!("Music: {:.0}%", settings.music_volume * 100.)
+            }
+            SettingLabel::QuietHours => {
+                format!("Mute at night (M): {}", on_off(settings.quiet_hours))
+            }
+            SettingLabel::QuietHoursStart => {
+                format!("Quiet from: {:02}:00", settings.quiet_hours_start)
+            }
+            SettingLabel::QuietHoursEnd => {
+                format!("Quiet until: {:02}:00", settings.quiet_hours_end)
+            }
+            SettingLabel::Lives if settings.lives <= 1 => "Lives: 1 (sudden death)".into(),
+            SettingLabel::Lives => format!("Lives: {} (unranked)", settings.lives),
+            SettingLabel::StartingScore => format!(
+                "Starting score: {} (unranked if not 0)",
+                settings.starting_score
+            ),
+            SettingLabel::MinSavedScore if settings.min_saved_score <= 0 => {
+                "Save high scores: Any score".into()
+            }
+            SettingLabel::MinSavedScore => {
+                format!("Save high scores: {} and up", settings.min_saved_score)
+            }
+            SettingLabel::PipeSpacing => format!(
+                "Pipe spacing: {:.0} ({})",
+                custom_mode.pipe_spacing,
+                pipe_spacing_name(custom_mode.pipe_spacing)
+            ),
+            SettingLabel::FlockSize => format!("Flock size: {}", custom_mode.flock_size),
+            SettingLabel::PracticeStartPipe if custom_mode.practice_start_pipe <= 1 => {
+                "Practice from: Start".into()
+            }
+            SettingLabel::PracticeStartPipe => {
+                format!("Practice from pipe {}", custom_mode.practice_start_pipe)
+            }
+            SettingLabel::ScoreDecayRate => {
+                format!("Score decay: {:.2}/s", custom_mode.score_decay_rate)
+            }
+            SettingLabel::GapDistribution => format!(
+                "Gaps: {}",
+                match custom_mode.gap_distribution {
+                    GapDistribution::Uniform => "Uniform",
+                    GapDistribution::CenterBiased => "Center biased",
+                    GapDistribution::Alternating => "Alternating",
+                }
+            ),
+            SettingLabel::Pattern => format!(
+                "Pattern: {}",
+                patterns
+                    .0
+                    .get(custom_mode.pattern)
+                    .map_or("none", |pattern| pattern.name.as_str())
+            ),
+        };
+    }
+}
+
+// Cycles through the connected monitors by name, then back to the primary one
+fn next_monitor(current: &Option<String>, monitors: &Query<&Monitor>) -> Option<String> {
+    let mut names: Vec<String> = monitors
+        .iter()
+        .filter_map(|monitor| monitor.name.clone())
+        .collect();
+    names.sort();
+    names.dedup();
+
+    match current {
+        None => names.first().cloned(),
+        Some(current) => names
+            .iter()
+            .position(|name| name == current)
+            .and_then(|index| names.get(index + 1))
+            .cloned(),
+    }
+}
+
+fn pipe_spacing_name(pipe_spacing: f32) -> &'static str {
+    if pipe_spacing <= 350. {
+        "dense nightmare"
+    } else if pipe_spacing >= 700. {
+        "sparse zen"
+    } else {
+        "classic"
+    }
+}
+
+fn on_off(value: bool) -> &'static str {
+    if value {
+        "On"
+    } else {
+        "Off"
+    }
+}
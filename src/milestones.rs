@@ -0,0 +1,86 @@
+use bevy::{
+    color::palettes::css::{GOLD, HOT_PINK, LIME, ORANGE, SKY_BLUE},
+    prelude::*,
+};
+
+use crate::{
+    components::{ScoreFlash, ScoreText},
+    constants::*,
+    events::{ResetScoreEvent, ScoreChangedEvent},
+    layers::ZLayers,
+    particles::spawn_particle_burst,
+    resources::{LastMilestone, Score, Settings},
+};
+
+pub fn milestones_plugin(app: &mut App) {
+    app.init_resource::<LastMilestone>()
+        .add_systems(Update, flash_score_text)
+        .add_observer(reset_last_milestone)
+        .add_observer(celebrate_milestone);
+}
+
+// A handicap start should not count as reaching the milestones below it
+fn reset_last_milestone(
+    reset_event: On<ResetScoreEvent>,
+    mut last_milestone: ResMut<LastMilestone>,
+) {
+    last_milestone.0 = reset_event.starting_score.div_euclid(MILESTONE_INTERVAL);
+}
+
+#[allow(
+    clippy::too_many_arguments,
+    reason = "particles, the sting and the score pop all start here"
+)]
+fn celebrate_milestone(
+    _changed_event: On<ScoreChangedEvent>,
+    score: Res<Score>,
+    settings: Res<Settings>,
+    mut last_milestone: ResMut<LastMilestone>,
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    layers: Res<ZLayers>,
+    score_text_query: Query<Entity, With<ScoreText>>,
+) {
+    let milestone = score.0.div_euclid(MILESTONE_INTERVAL);
+    if milestone <= last_milestone.0 {
+        return;
+    }
+    last_milestone.0 = milestone;
+
+    if !settings.reduced_motion {
+        let position = Vec3::new(0., WINDOW_SIZE.y / 4., layers.particle);
+        for color in [GOLD, HOT_PINK, LIME, SKY_BLUE] {
+            spawn_particle_burst(&mut commands, position, color.into(), 12);
+        }
+    }
+
+    commands.spawn((
+        AudioPlayer::new(asset_server.load(MILESTONE_SOUND)),
+        PlaybackSettings::DESPAWN,
+    ));
+
+    for entity in score_text_query.iter() {
+        commands
+            .entity(entity)
+            .insert(ScoreFlash(Timer::from_seconds(
+                MILESTONE_FLASH_DURATION,
+                TimerMode::Once,
+            )));
+    }
+}
+
+fn flash_score_text(
+    time: Res<Time>,
+    mut commands: Commands,
+    mut query: Query<(Entity, &mut ScoreFlash, &mut TextColor)>,
+) {
+    for (entity, mut flash, mut color) in query.iter_mut() {
+        if flash.0.tick(time.delta()).just_finished() {
+            color.0 = ORANGE.into();
+            commands.entity(entity).remove::<ScoreFlash>();
+            continue;
+        }
+
+        color.0 = Color::WHITE.mix(&ORANGE.into(), flash.0.fraction());
+    }
+}
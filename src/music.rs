@@ -0,0 +1,80 @@
+use bevy::{audio::Volume, prelude::*};
+
+use crate::{
+    components::MusicLayer,
+    constants::*,
+    events::ScoreChangedEvent,
+    resources::{AudioMute, MusicIntensity, Score, Settings},
+    AppState,
+};
+
+pub fn music_plugin(app: &mut App) {
+    app.init_resource::<MusicIntensity>()
+        .add_systems(Startup, spawn_music_layers)
+        .add_systems(OnEnter(AppState::GameStart), restart_music)
+        .add_systems(OnEnter(AppState::GameOver), fade_out_music)
+        .add_systems(Update, blend_music_layers)
+        .add_observer(raise_music_intensity);
+}
+
+// Every layer loops from startup so they stay in sync
+fn spawn_music_layers(mut commands: Commands, asset_server: Res<AssetServer>) {
+    for (index, path) in MUSIC_LAYERS.into_iter().enumerate() {
+        commands.spawn((
+            MusicLayer(index),
+            AudioPlayer::new(asset_server.load(path)),
+            PlaybackSettings::LOOP.with_volume(Volume::Linear(0.)),
+        ));
+    }
+}
+
+fn restart_music(mut intensity: ResMut<MusicIntensity>) {
+    intensity.target_level = 0.;
+    intensity.target_fade = 1.;
+}
+
+fn fade_out_music(mut intensity: ResMut<MusicIntensity>) {
+    intensity.target_fade = 0.;
+}
+
+fn raise_music_intensity(
+    _changed_event: On<ScoreChangedEvent>,
+    score: Res<Score>,
+    mut intensity: ResMut<MusicIntensity>,
+) {
+    let passed = MUSIC_INTENSITY_THRESHOLDS
+        .iter()
+        .filter(|&&threshold| score.0 >= threshold)
+        .count();
+    intensity.target_level = passed as f32;
+}
+
+// Each layer peaks at its own level and crossfades linearly into its neighbours
+pub fn layer_weight(layer: usize, level: f32) -> f32 {
+    (1. - (level - layer as f32).abs()).max(0.)
+}
+
+// Real time, so the fade also finishes while the game is paused or slowed down
+fn blend_music_layers(
+    time: Res<Time<Real>>,
+    settings: Res<Settings>,
+    mute: Res<AudioMute>,
+    mut intensity: ResMut<MusicIntensity>,
+    mut query: Query<(&MusicLayer, &mut AudioSink)>,
+) {
+    let blend = 1. - (-MUSIC_BLEND_SPEED * time.delta_secs()).exp();
+    let level = intensity.level + (intensity.target_level - intensity.level) * blend;
+    let fade = intensity.fade + (intensity.target_fade - intensity.fade) * blend;
+    intensity.level = level;
+    intensity.fade = fade;
+
+    let master = if mute.muted {
+        0.
+    } else {
+        settings.music_volume
+    };
+    for (layer, mut sink) in query.iter_mut() {
+        let volume = master * fade * layer_weight(layer.0, level);
+        sink.set_volume(Volume::Linear(volume));
+    }
+}
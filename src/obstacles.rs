@@ -0,0 +1,209 @@
+use bevy::{color::palettes::css::GOLD, prelude::*};
+
+use crate::{
+    components::GoldenPipe,
+    pipes::{
+        add_pipe_caps, depth_tint, opening_bounds, pipe_body_length, roll_pipe, spawn_pipe_pair,
+        PIPE_CAP_COLOR,
+    },
+    resources::{ActivePattern, GameConfig, GameMode, GameRng, LastGapCenter, PlayArea, Settings},
+    AppState,
+};
+
+pub fn obstacles_plugin(app: &mut App) {
+    app.insert_resource(ActiveObstacleSpawner(spawner_for_mode(GameMode::Normal)))
+        .init_resource::<LastGapCenter>()
+        .add_systems(
+            OnEnter(AppState::GameStart),
+            (select_obstacle_spawner, reset_last_gap_center),
+        );
+}
+
+// What a spawner gets to work with besides the run's rng, pattern and last gap
+pub struct SpawnContext<'a> {
+    pub config: &'a GameConfig,
+    pub settings: &'a Settings,
+    pub play_area: &'a PlayArea,
+    pub texture: Handle<Image>,
+    pub x: f32,
+    pub z: f32,
+}
+
+// One kind of obstacle. `pipe_spawner` owns the timing and hands every spawn to the active
+// spawner, so obstacle variants only decide what appears, not when.
+// The returned entity must carry `Pipe`, so it moves, despawns and counts like any pipe, and
+// a `PointGate` child to score through. `None` means the run has nothing left to spawn.
+pub trait ObstacleSpawner: Send + Sync {
+    fn spawn(
+        &mut self,
+        commands: &mut Commands,
+        context: &SpawnContext,
+        rng: &mut GameRng,
+        pattern: &mut ActivePattern,
+        last_gap: &mut LastGapCenter,
+    ) -> Option<Entity>;
+}
+
+#[derive(Resource)]
+pub struct ActiveObstacleSpawner(pub Box<dyn ObstacleSpawner>);
+
+pub fn spawner_for_mode(mode: GameMode) -> Box<dyn ObstacleSpawner> {
+    match mode {
+        GameMode::Normal
+        | GameMode::Custom
+        | GameMode::Flock
+        | GameMode::Adaptive
+        | GameMode::Practice
+        | GameMode::Pattern
+        | GameMode::Scrolling
+        | GameMode::ScoreAttack
+        | GameMode::ScoreDecay => Box::new(StandardPipeSpawner),
+    }
+}
+
+fn select_obstacle_spawner(mode: Res<GameMode>, mut spawner: ResMut<ActiveObstacleSpawner>) {
+    spawner.0 = spawner_for_mode(*mode);
+}
+
+// Every run starts fresh, the first pipe is never held close to the last run's final gap
+fn reset_last_gap_center(mut last_gap: ResMut<LastGapCenter>) {
+    last_gap.0 = None;
+}
+
+// A pipe pair with a rolled gap, the occasional golden pipe, and caps in the day theme.
+// Pattern mode runs through here too, its gaps come out of the ActivePattern
+pub struct StandardPipeSpawner;
+
+impl ObstacleSpawner for StandardPipeSpawner {
+    fn spawn(
+        &mut self,
+        commands: &mut Commands,
+        context: &SpawnContext,
+        rng: &mut GameRng,
+        pattern: &mut ActivePattern,
+        last_gap: &mut LastGapCenter,
+    ) -> Option<Entity> {
+        let config = context.config;
+        let bounds = opening_bounds(config.pipe_gap, config.ground_height, config.world_height);
+        let roll = roll_pipe(&mut rng.0, pattern, last_gap, config.max_gap_delta, bounds)?;
+
+        let pipe_color = if roll.is_golden {
+            GOLD.into()
+        } else {
+            Color::WHITE
+        };
+        // Both halves and the caps share one tint, so a pair never looks split
+        let tint = |color: Color| {
+            if context.settings.pipe_depth_tint {
+                depth_tint(color, roll.gap_center, bounds)
+            } else {
+                color
+            }
+        };
+
+        let pipe = spawn_pipe_pair(
+            commands,
+            context.texture.clone(),
+            Vec2::new(context.x, roll.gap_center),
+            config.pipe_gap,
+            pipe_body_length(config.world_height, context.play_area.visible_height),
+            tint(pipe_color),
+            context.z,
+        );
+
+        if context.settings.theme.pipe_caps() {
+            let cap_color = if roll.is_golden {
+                GOLD.into()
+            } else {
+                PIPE_CAP_COLOR
+            };
+            add_pipe_caps(commands, pipe, config.pipe_gap, tint(cap_color));
+        }
+
+        if roll.is_golden {
+            commands.entity(pipe).insert(GoldenPipe);
+        }
+        Some(pipe)
+    }
+}
+
+// Every gap sits in the middle of the opening range and nothing is golden. No mode uses it
+// yet, it is the smallest possible alternate and a fixed course for trying out other rules
+pub struct CenteredPipeSpawner;
+
+impl ObstacleSpawner for CenteredPipeSpawner {
+    fn spawn(
+        &mut self,
+        commands: &mut Commands,
+        context: &SpawnContext,
+        _rng: &mut GameRng,
+        _pattern: &mut ActivePattern,
+        _last_gap: &mut LastGapCenter,
+    ) -> Option<Entity> {
+        let config = context.config;
+        let (min, max) = opening_bounds(config.pipe_gap, config.ground_height, config.world_height);
+        Some(spawn_pipe_pair(
+            commands,
+            context.texture.clone(),
+            Vec2::new(context.x, (min + max) / 2.),
+            config.pipe_gap,
+            pipe_body_length(config.world_height, context.play_area.visible_height),
+            Color::WHITE,
+            context.z,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::{Collider, ColliderType, Pipe, PointGate};
+
+    fn spawns_a_scorable_gate(spawner: &mut dyn ObstacleSpawner) {
+        let mut world = World::new();
+        let config = GameConfig::default();
+        let settings = Settings::default();
+        let play_area = PlayArea::default();
+        let context = SpawnContext {
+            config: &config,
+            settings: &settings,
+            play_area: &play_area,
+            texture: Handle::default(),
+            x: 0.,
+            z: 0.,
+        };
+        let mut rng = GameRng::from_seed(3);
+        let mut pattern = ActivePattern::default();
+        let mut last_gap = LastGapCenter::default();
+
+        let pipe = spawner
+            .spawn(
+                &mut world.commands(),
+                &context,
+                &mut rng,
+                &mut pattern,
+                &mut last_gap,
+            )
+            .unwrap();
+        world.flush();
+
+        assert!(world.get::<Pipe>(pipe).is_some());
+        let children = world.get::<Children>(pipe).unwrap().to_vec();
+        let gates: Vec<Entity> = children
+            .into_iter()
+            .filter(|&child| world.get::<PointGate>(child).is_some())
+            .collect();
+        assert_eq!(gates.len(), 1);
+        let collider = world.get::<Collider>(gates[0]).unwrap();
+        assert!(collider.kind == ColliderType::Good);
+    }
+
+    #[test]
+    fn every_spawner_produces_a_scorable_gate() {
+        spawns_a_scorable_gate(&mut StandardPipeSpawner);
+        spawns_a_scorable_gate(&mut CenteredPipeSpawner);
+        for mode in [GameMode::Normal, GameMode::Pattern, GameMode::ScoreAttack] {
+            spawns_a_scorable_gate(spawner_for_mode(mode).as_mut());
+        }
+    }
+}
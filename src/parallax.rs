@@ -0,0 +1,185 @@
+use bevy::prelude::*;
+
+use crate::{
+    asset_variants::image_variant,
+    components::{ParallaxLayer, ParallaxTile},
+    constants::*,
+    layers::ZLayers,
+    resources::{GameConfig, HiDpi, PlayArea},
+    AppState, PauseState,
+};
+
+// One strip of scenery, repeated sideways to fill the window
+#[derive(Clone, Debug)]
+pub struct ParallaxLayerDef {
+    pub image: String,
+    pub size: Vec2,
+    // Center of the strip above the top of the ground, negative values sit below it
+    pub y: f32,
+    pub z: f32,
+    // Share of the pipe speed, 1 keeps pace with the pipes and 0 stands still
+    pub speed_factor: f32,
+}
+
+// The scenery `setup` spawns, back to front. A theme or mod can insert its own before Startup
+// to add clouds, hills or foreground grass
+#[derive(Resource, Clone, Debug)]
+pub struct ParallaxConfig {
+    pub layers: Vec<ParallaxLayerDef>,
+}
+
+impl Default for ParallaxConfig {
+    fn default() -> Self {
+        let layers = ZLayers::default();
+        Self {
+            layers: vec![
+                ParallaxLayerDef {
+                    image: BACKGROUND_IMAGE.into(),
+                    size: Vec2::new(WINDOW_SIZE.x, BACKGROUND_SPRITE_HEIGHT),
+                    y: BACKGROUND_SPRITE_HEIGHT / 2.,
+                    z: layers.background,
+                    speed_factor: BACKGROUND_SCROLL_FACTOR,
+                },
+                ParallaxLayerDef {
+                    image: GROUND_IMAGE.into(),
+                    size: Vec2::new(WINDOW_SIZE.x, GROUND_SPRITE_HEIGHT),
+                    y: -GROUND_SPRITE_HEIGHT / 2.,
+                    z: layers.ground,
+                    speed_factor: 1.,
+                },
+            ],
+        }
+    }
+}
+
+pub fn parallax_plugin(app: &mut App) {
+    app.init_resource::<ParallaxConfig>().add_systems(
+        Update,
+        (
+            scroll_parallax.run_if(
+                in_state(AppState::MainMenu)
+                    .or(in_state(AppState::GameStart))
+                    .or(in_state(PauseState::Running)),
+            ),
+            swap_parallax_images.run_if(resource_changed::<HiDpi>),
+            layout_parallax,
+        )
+            .chain(),
+    );
+}
+
+pub fn spawn_parallax_layers(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    hidpi: &HiDpi,
+    config: &ParallaxConfig,
+    ground_top: f32,
+) {
+    for (index, layer) in config.layers.iter().enumerate() {
+        commands.spawn((
+            ParallaxLayer {
+                index,
+                image: asset_server.load(image_variant(&layer.image, hidpi).path),
+                offset: 0.,
+            },
+            Transform::from_xyz(0., ground_top + layer.y, layer.z),
+            Visibility::Visible,
+        ));
+    }
+}
+
+// Enough tiles that the row still reaches the right edge with the first one scrolled almost
+// all the way out on the left
+pub fn tiles_needed(visible_width: f32, tile_width: f32) -> usize {
+    (visible_width / tile_width).ceil() as usize + 1
+}
+
+// On the virtual clock, so pausing and slow motion apply here too
+fn scroll_parallax(
+    time: Res<Time>,
+    game_config: Res<GameConfig>,
+    config: Res<ParallaxConfig>,
+    mut layer_query: Query<&mut ParallaxLayer>,
+) {
+    for mut layer in layer_query.iter_mut() {
+        let Some(def) = config.layers.get(layer.index) else {
+            continue;
+        };
+        let distance = def.speed_factor * game_config.pipe_speed * time.delta_secs();
+        layer.offset = (layer.offset + distance).rem_euclid(def.size.x);
+    }
+}
+
+// The tiles are respawned by layout_parallax with the new image
+fn swap_parallax_images(
+    hidpi: Res<HiDpi>,
+    config: Res<ParallaxConfig>,
+    asset_server: Res<AssetServer>,
+    mut commands: Commands,
+    mut layer_query: Query<(Entity, &mut ParallaxLayer)>,
+) {
+    for (entity, mut layer) in layer_query.iter_mut() {
+        let Some(def) = config.layers.get(layer.index) else {
+            continue;
+        };
+        let image = asset_server.load(image_variant(&def.image, &hidpi).path);
+        if image != layer.image {
+            layer.image = image;
+            commands.entity(entity).despawn_children();
+        }
+    }
+}
+
+// The row starts at the left window edge and wraps by a whole tile, so the seam never shows.
+// Tiles are refitted whenever the window is resized
+fn layout_parallax(
+    play_area: Res<PlayArea>,
+    config: Res<ParallaxConfig>,
+    mut commands: Commands,
+    mut layer_query: Query<(Entity, &ParallaxLayer, &mut Transform, Option<&Children>)>,
+) {
+    for (entity, layer, mut transform, children) in layer_query.iter_mut() {
+        let Some(def) = config.layers.get(layer.index) else {
+            continue;
+        };
+        transform.translation.x = -play_area.visible_width / 2. + def.size.x / 2. - layer.offset;
+
+        let tiles = tiles_needed(play_area.visible_width, def.size.x);
+        if children.map_or(0, |children| children.len()) == tiles {
+            continue;
+        }
+        commands
+            .entity(entity)
+            .despawn_children()
+            .with_children(|parent| {
+                for tile in 0..tiles {
+                    parent.spawn((
+                        ParallaxTile,
+                        Sprite {
+                            image: layer.image.clone(),
+                            custom_size: Some(def.size),
+                            ..default()
+                        },
+                        Transform::from_xyz(tile as f32 * def.size.x, 0., 0.),
+                    ));
+                }
+            });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tiles_cover_any_window_width() {
+        let tile_width = WINDOW_SIZE.x;
+        for visible_width in [640., 1280., WINDOW_SIZE.x, 2560., 3440., 7680.] {
+            let tiles = tiles_needed(visible_width, tile_width);
+            for offset in [0., tile_width / 2., tile_width - 0.01] {
+                let row_right = -visible_width / 2. - offset + tiles as f32 * tile_width;
+                assert!(row_right >= visible_width / 2., "{visible_width} {offset}");
+            }
+        }
+    }
+}
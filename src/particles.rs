@@ -0,0 +1,51 @@
+use std::f32::consts::TAU;
+
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::{
+    components::Particle,
+    constants::{GRAVITY, PARTICLE_LIFETIME},
+};
+
+pub fn particles_plugin(app: &mut App) {
+    app.add_systems(Update, update_particles);
+}
+
+// Uses its own rng so effects never shift the seeded pipe layout. `position.z` is the layer
+// to draw on, normally `ZLayers::particle`
+pub fn spawn_particle_burst(commands: &mut Commands, position: Vec3, color: Color, count: usize) {
+    let mut rng = rand::thread_rng();
+    for _ in 0..count {
+        let angle = rng.gen_range(0.0..TAU);
+        let speed = rng.gen_range(100.0..400.0);
+
+        commands.spawn((
+            Particle {
+                velocity: Vec2::from_angle(angle) * speed,
+                lifetime: Timer::from_seconds(PARTICLE_LIFETIME, TimerMode::Once),
+            },
+            Sprite::from_color(color, Vec2::splat(8.)),
+            Transform::from_translation(position),
+        ));
+    }
+}
+
+fn update_particles(
+    time: Res<Time>,
+    mut commands: Commands,
+    mut query: Query<(Entity, &mut Particle, &mut Transform, &mut Sprite)>,
+) {
+    for (entity, mut particle, mut transform, mut sprite) in &mut query {
+        if particle.lifetime.tick(time.delta()).just_finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        particle.velocity.y += 0.5 * GRAVITY * time.delta_secs();
+        transform.translation += (particle.velocity * time.delta_secs()).extend(0.);
+        sprite
+            .color
+            .set_alpha(particle.lifetime.fraction_remaining());
+    }
+}
@@ -0,0 +1,91 @@
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::{
+    components::Pipe,
+    resources::{ActivePattern, CustomModeSettings, GameMode, GapDistribution, PipePatterns},
+    AppState,
+};
+
+const BUILT_IN_PATTERNS: &str = include_str!("../assets/patterns.ron");
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct PipePattern {
+    pub name: String,
+    pub gaps: Vec<f32>,
+    #[serde(default)]
+    pub looping: bool,
+}
+
+pub enum PatternStep {
+    Random,
+    Gap(f32),
+    Finished,
+}
+
+impl ActivePattern {
+    pub fn next_step(&mut self) -> PatternStep {
+        let Some(pattern) = &self.pattern else {
+            return PatternStep::Random;
+        };
+
+        if self.next >= pattern.gaps.len() {
+            if !pattern.looping || pattern.gaps.is_empty() {
+                self.finished = true;
+                return PatternStep::Finished;
+            }
+            self.next = 0;
+        }
+
+        let gap = pattern.gaps[self.next].clamp(-1., 1.);
+        self.next += 1;
+        PatternStep::Gap(gap)
+    }
+}
+
+pub fn pattern_plugin(app: &mut App) {
+    let patterns = ron::from_str(BUILT_IN_PATTERNS).unwrap_or_else(|error| {
+        error!("Failed to parse the built-in pipe patterns: {error}");
+        Vec::new()
+    });
+
+    app.insert_resource(PipePatterns(patterns))
+        .init_resource::<ActivePattern>()
+        .add_systems(OnEnter(AppState::GameStart), activate_pattern)
+        .add_systems(
+            Update,
+            end_finished_pattern.run_if(in_state(AppState::InGame)),
+        );
+}
+
+fn activate_pattern(
+    mode: Res<GameMode>,
+    patterns: Res<PipePatterns>,
+    custom_mode: Res<CustomModeSettings>,
+    mut active_pattern: ResMut<ActivePattern>,
+) {
+    *active_pattern = ActivePattern {
+        pattern: match *mode {
+            GameMode::Pattern => patterns.0.get(custom_mode.pattern).cloned(),
+            _ => None,
+        },
+        // Practice keeps the gaps near the middle so the focus stays on the flap timing
+        distribution: match *mode {
+            GameMode::Custom => custom_mode.gap_distribution,
+            GameMode::Practice => GapDistribution::CenterBiased,
+            _ => GapDistribution::Uniform,
+        },
+        ..default()
+    };
+}
+
+// A finished pattern ends the run once its last pipe has left the screen
+fn end_finished_pattern(
+    active_pattern: Res<ActivePattern>,
+    pipe_query: Query<(), With<Pipe>>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    if active_pattern.finished && pipe_query.is_empty() {
+        next_state.set(AppState::GameOver);
+    }
+}
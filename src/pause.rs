@@ -0,0 +1,88 @@
+use bevy::prelude::*;
+
+use crate::{
+    actions::{Action, ActionState},
+    components::PauseDim,
+    constants::*,
+    resources::Settings,
+    AppState, PauseState,
+};
+
+pub fn pause_plugin(app: &mut App) {
+    app.add_systems(OnEnter(PauseState::Paused), pause_time)
+        .add_systems(
+            OnExit(PauseState::Paused),
+            (resume_time, fade_out_pause_dim),
+        )
+        .add_systems(
+            Update,
+            (
+                pause_input.run_if(in_state(AppState::InGame)),
+                fade_pause_dim,
+            ),
+        );
+}
+
+fn pause_time(mut time: ResMut<Time<Virtual>>) {
+    time.pause();
+}
+
+fn resume_time(mut time: ResMut<Time<Virtual>>) {
+    time.unpause();
+}
+
+// Only the dim fades, time stops and starts with the state change itself
+fn fade_out_pause_dim(
+    settings: Res<Settings>,
+    mut commands: Commands,
+    mut dim_query: Query<(Entity, &mut PauseDim)>,
+) {
+    for (entity, mut dim) in dim_query.iter_mut() {
+        if settings.reduced_motion {
+            commands.entity(entity).despawn();
+        } else {
+            dim.fading_out = true;
+        }
+    }
+}
+
+// On the real clock, the virtual one is stopped for the whole fade in
+fn fade_pause_dim(
+    time: Res<Time<Real>>,
+    mut commands: Commands,
+    mut dim_query: Query<(Entity, &PauseDim, &mut BackgroundColor)>,
+) {
+    let step = PAUSE_DIM_ALPHA * time.delta_secs() / PAUSE_FADE_DURATION;
+    for (entity, dim, mut color) in dim_query.iter_mut() {
+        let alpha = color.0.alpha();
+        if dim.fading_out {
+            if alpha <= step {
+                commands.entity(entity).despawn();
+            } else {
+                color.0.set_alpha(alpha - step);
+            }
+        } else if alpha < PAUSE_DIM_ALPHA {
+            color.0.set_alpha((alpha + step).min(PAUSE_DIM_ALPHA));
+        }
+    }
+}
+
+fn pause_input(
+    actions: Res<ActionState>,
+    settings: Res<Settings>,
+    pause_state: Res<State<PauseState>>,
+    mut next_pause: ResMut<NextState<PauseState>>,
+    mut app_exit: MessageWriter<AppExit>,
+) {
+    if !actions.just_pressed(Action::Pause) {
+        return;
+    }
+
+    match pause_state.get() {
+        PauseState::Running if settings.instant_quit => {
+            app_exit.write(AppExit::Success);
+        }
+        PauseState::Running => next_pause.set(PauseState::Paused),
+        PauseState::Paused => next_pause.set(PauseState::Running),
+    }
+}
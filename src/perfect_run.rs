@@ -0,0 +1,164 @@
+use bevy::{color::palettes::css::GOLD, prelude::*};
+
+use crate::{
+    components::PerfectBanner,
+    constants::*,
+    events::{
+        LivesChangedEvent, PointGateReachedEvent, ShieldBrokenEvent, TimePenaltyEvent,
+        TutorialSaveEvent,
+    },
+    persistence,
+    resources::{PerfectCriteria, PerfectRun, PlayerStats, RunFlags, Settings},
+    tutorial::PLAYER_STATS_FILE,
+    AppState,
+};
+
+pub fn perfect_run_plugin(app: &mut App) {
+    app.init_resource::<PerfectRun>()
+        .add_systems(OnEnter(AppState::GameStart), reset_perfect_run)
+        .add_systems(OnEnter(AppState::GameOver), reward_perfect_run)
+        .add_observer(check_gate_pass)
+        .add_observer(break_on_life_lost)
+        .add_observer(break_on_time_penalty)
+        .add_observer(break_on_tutorial_save)
+        .add_observer(break_on_shield);
+}
+
+// Within PERFECT_CENTER_TOLERANCE of the half gap, either side of the gap center
+pub fn is_centered_pass(player_y: f32, gate_y: f32, gate_height: f32) -> bool {
+    (player_y - gate_y).abs() <= gate_height / 2. * PERFECT_CENTER_TOLERANCE
+}
+
+// Short runs are too easy to keep clean, so they never count
+pub fn is_perfect(criteria: PerfectCriteria, run: &PerfectRun) -> bool {
+    criteria != PerfectCriteria::Off && run.clean && run.gates >= PERFECT_RUN_MIN_GATES
+}
+
+fn reset_perfect_run(mut run: ResMut<PerfectRun>) {
+    *run = PerfectRun::default();
+}
+
+fn check_gate_pass(
+    gate_event: On<PointGateReachedEvent>,
+    state: Res<State<AppState>>,
+    settings: Res<Settings>,
+    mut run: ResMut<PerfectRun>,
+) {
+    // The menu demo flies through gates too
+    if *state.get() != AppState::InGame {
+        return;
+    }
+
+    run.gates += 1;
+    if settings.perfect_run == PerfectCriteria::Centered
+        && !is_centered_pass(
+            gate_event.player_y,
+            gate_event.gate_y,
+            gate_event.gate_size.y,
+        )
+    {
+        run.clean = false;
+    }
+}
+
+// The shield, spare lives, the score attack clock and the tutorial save all absorb a crash, and a
+// run that needed one of them is not perfect. There is no near-miss detection, so close calls
+// only count against the run through the centered pass criteria
+fn break_on_shield(_broken_event: On<ShieldBrokenEvent>, mut run: ResMut<PerfectRun>) {
+    run.clean = false;
+}
+
+fn break_on_life_lost(_lives_event: On<LivesChangedEvent>, mut run: ResMut<PerfectRun>) {
+    run.clean = false;
+}
+
+fn break_on_time_penalty(_penalty_event: On<TimePenaltyEvent>, mut run: ResMut<PerfectRun>) {
+    run.clean = false;
+}
+
+fn break_on_tutorial_save(_save_event: On<TutorialSaveEvent>, mut run: ResMut<PerfectRun>) {
+    run.clean = false;
+}
+
+// Same ranking rules as the high score, an assisted or slowed down run earns nothing
+fn reward_perfect_run(
+    run: Res<PerfectRun>,
+    settings: Res<Settings>,
+    run_flags: Res<RunFlags>,
+    mut stats: ResMut<PlayerStats>,
+    asset_server: Res<AssetServer>,
+    mut commands: Commands,
+) {
+    if !run_flags.is_ranked() || !is_perfect(settings.perfect_run, &run) {
+        return;
+    }
+
+    stats.perfect_runs += 1;
+    persistence::save(PLAYER_STATS_FILE, &*stats);
+
+    commands.spawn((
+        AudioPlayer::new(asset_server.load(PERFECT_SOUND)),
+        PlaybackSettings::DESPAWN,
+    ));
+    commands.spawn((
+        PerfectBanner,
+        Text::new(format!("Perfect run! ({} total)", stats.perfect_runs)),
+        TextFont {
+            font_size: 36.0,
+            ..default()
+        },
+        TextColor(GOLD.into()),
+        Node {
+            position_type: PositionType::Absolute,
+            width: percent(100),
+            top: percent(15),
+            justify_content: JustifyContent::Center,
+            ..default()
+        },
+        TextLayout {
+            justify: Justify::Center,
+            ..default()
+        },
+        Pickable::IGNORE,
+        DespawnOnExit(AppState::GameOver),
+    ));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_long_clean_runs_are_perfect() {
+        let clean = PerfectRun {
+            clean: true,
+            gates: PERFECT_RUN_MIN_GATES,
+        };
+        assert!(is_perfect(PerfectCriteria::NoHits, &clean));
+        assert!(!is_perfect(PerfectCriteria::Off, &clean));
+
+        let short = PerfectRun {
+            gates: PERFECT_RUN_MIN_GATES - 1,
+            ..clean
+        };
+        assert!(!is_perfect(PerfectCriteria::NoHits, &short));
+
+        let hit = PerfectRun {
+            clean: false,
+            ..clean
+        };
+        assert!(!is_perfect(PerfectCriteria::Centered, &hit));
+    }
+
+    #[test]
+    fn centered_passes_stay_near_the_gap_middle() {
+        let gap = 200.;
+        assert!(is_centered_pass(10., 0., gap));
+        assert!(is_centered_pass(
+            -gap / 2. * PERFECT_CENTER_TOLERANCE,
+            0.,
+            gap
+        ));
+        assert!(!is_centered_pass(60., 0., gap));
+    }
+}
@@ -0,0 +1,166 @@
+use bevy::{ecs::resource::Resource, log::warn};
+use ron::ser::PrettyConfig;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+// Only meant to stop casual edits of score files, not a real secret
+const SIGNING_KEY: &[u8] = b"flappy-bird-score-signing-key";
+
+pub trait Storage {
+    fn read(&self, file_name: &str) -> Option<String>;
+    fn write(&self, file_name: &str, contents: &str) -> Result<(), String>;
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub struct FileStorage;
+
+#[cfg(not(target_arch = "wasm32"))]
+impl FileStorage {
+    const SAVE_DIR: &'static str = "saves";
+
+    fn path(file_name: &str) -> std::path::PathBuf {
+        std::path::PathBuf::from(Self::SAVE_DIR).join(file_name)
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Storage for FileStorage {
+    fn read(&self, file_name: &str) -> Option<String> {
+        std::fs::read_to_string(Self::path(file_name)).ok()
+    }
+
+    fn write(&self, file_name: &str, contents: &str) -> Result<(), String> {
+        std::fs::create_dir_all(Self::SAVE_DIR).map_err(|error| error.to_string())?;
+        std::fs::write(Self::path(file_name), contents).map_err(|error| error.to_string())
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn file_path(file_name: &str) -> std::path::PathBuf {
+    FileStorage::path(file_name)
+}
+
+#[cfg(target_arch = "wasm32")]
+pub struct LocalStorage;
+
+#[cfg(target_arch = "wasm32")]
+impl LocalStorage {
+    fn key(file_name: &str) -> String {
+        format!("flappy_bird/{file_name}")
+    }
+
+    fn storage() -> Option<web_sys::Storage> {
+        web_sys::window()?.local_storage().ok()?
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+impl Storage for LocalStorage {
+    fn read(&self, file_name: &str) -> Option<String> {
+        Self::storage()?.get_item(&Self::key(file_name)).ok()?
+    }
+
+    fn write(&self, file_name: &str, contents: &str) -> Result<(), String> {
+        Self::storage()
+            .ok_or_else(|| "local storage is unavailable".to_string())?
+            .set_item(&Self::key(file_name), contents)
+            .map_err(|error| format!("{error:?}"))
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn storage() -> impl Storage {
+    FileStorage
+}
+
+#[cfg(target_arch = "wasm32")]
+fn storage() -> impl Storage {
+    LocalStorage
+}
+
+// The platform storage as a resource, for systems whose saves a test needs to see
+#[derive(Resource)]
+pub struct SaveStorage(pub Box<dyn Storage + Send + Sync>);
+
+impl Default for SaveStorage {
+    fn default() -> Self {
+        Self(Box::new(storage()))
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct SignedRecord {
+    data: String,
+    signature: u64,
+}
+
+pub fn load<T: DeserializeOwned + Default>(file_name: &str) -> T {
+    let Some(contents) = storage().read(file_name) else {
+        return T::default();
+    };
+
+    ron::from_str(&contents).unwrap_or_else(|error| {
+        warn!("Failed to parse {file_name}: {error}");
+        T::default()
+    })
+}
+
+pub fn save<T: Serialize>(file_name: &str, value: &T) {
+    save_to(&storage(), file_name, value);
+}
+
+pub fn save_to<T: Serialize>(storage: &dyn Storage, file_name: &str, value: &T) {
+    let result = ron::ser::to_string_pretty(value, PrettyConfig::default())
+        .map_err(|error| error.to_string())
+        .and_then(|contents| storage.write(file_name, &contents));
+
+    if let Err(error) = result {
+        warn!("Failed to save {file_name}: {error}");
+    }
+}
+
+// The signature covers the stored text, so new fields with serde defaults keep old files valid
+pub fn load_signed<T: DeserializeOwned + Default>(file_name: &str) -> T {
+    let Some(contents) = storage().read(file_name) else {
+        return T::default();
+    };
+
+    let Ok(record) = ron::from_str::<SignedRecord>(&contents) else {
+        warn!("Failed to parse {file_name}, resetting it");
+        return T::default();
+    };
+
+    if sign(&record.data) != record.signature {
+        warn!("{file_name} has been tampered with, resetting it");
+        return T::default();
+    }
+
+    ron::from_str(&record.data).unwrap_or_else(|error| {
+        warn!("Failed to parse {file_name}: {error}");
+        T::default()
+    })
+}
+
+pub fn save_signed<T: Serialize>(file_name: &str, value: &T) {
+    save_signed_to(&storage(), file_name, value);
+}
+
+pub fn save_signed_to<T: Serialize>(storage: &dyn Storage, file_name: &str, value: &T) {
+    match ron::to_string(value) {
+        Ok(data) => {
+            let signature = sign(&data);
+            save_to(storage, file_name, &SignedRecord { data, signature });
+        }
+        Err(error) => warn!("Failed to serialize {file_name}: {error}"),
+    }
+}
+
+// Keyed FNV-1a
+fn sign(data: &str) -> u64 {
+    SIGNING_KEY
+        .iter()
+        .chain(data.as_bytes())
+        .chain(SIGNING_KEY)
+        .fold(0xcbf29ce484222325, |hash, &byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+        })
+}
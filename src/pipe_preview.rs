@@ -0,0 +1,120 @@
+use bevy::{
+    color::palettes::css::{GOLD, WHITE},
+    prelude::*,
+};
+
+use crate::{
+    constants::*,
+    pipes::{opening_bounds, roll_pipe, PipeRoll},
+    resources::{ActivePattern, GameConfig, GameRng, LastGapCenter, PipePreview, RunFlags},
+    AppState,
+};
+
+pub fn pipe_preview_plugin(app: &mut App) {
+    app.init_resource::<PipePreview>()
+        .add_systems(
+            Update,
+            (toggle_pipe_preview, flag_previewed_runs)
+                .chain()
+                .run_if(in_state(AppState::GameStart).or(in_state(AppState::InGame))),
+        )
+        .add_systems(
+            Update,
+            draw_pipe_preview.run_if(in_state(AppState::InGame).and(pipe_preview_enabled)),
+        );
+}
+
+fn pipe_preview_enabled(preview: Res<PipePreview>) -> bool {
+    preview.0
+}
+
+fn toggle_pipe_preview(key_input: Res<ButtonInput<KeyCode>>, mut preview: ResMut<PipePreview>) {
+    if key_input.just_pressed(KeyCode::KeyL) {
+        preview.0 = !preview.0;
+    }
+}
+
+fn flag_previewed_runs(preview: Res<PipePreview>, mut run_flags: ResMut<RunFlags>) {
+    if preview.0 {
+        run_flags.pipe_preview = true;
+    }
+}
+
+// Rolls the next pipes on copies of the run's rng, pattern and last gap, the real ones are left
+// untouched
+pub fn predict_pipes(
+    rng: &GameRng,
+    pattern: &ActivePattern,
+    last_gap: LastGapCenter,
+    max_gap_delta: Option<f32>,
+    bounds: (f32, f32),
+    count: usize,
+) -> Vec<PipeRoll> {
+    let mut rng = rng.0.clone();
+    let mut pattern = pattern.clone();
+    let mut last_gap = last_gap;
+    (0..count)
+        .map_while(|_| roll_pipe(&mut rng, &mut pattern, &mut last_gap, max_gap_delta, bounds))
+        .collect()
+}
+
+// The next pipe is the leftmost ghost, later ones follow to its right
+fn draw_pipe_preview(
+    mut gizmos: Gizmos,
+    rng: Res<GameRng>,
+    active_pattern: Res<ActivePattern>,
+    last_gap: Res<LastGapCenter>,
+    config: Res<GameConfig>,
+) {
+    let bounds = opening_bounds(config.pipe_gap, config.ground_height, config.world_height);
+    let first_x = WINDOW_SIZE.x / 2.
+        - PIPE_PREVIEW_WIDTH
+        - (PIPE_PREVIEW_COUNT - 1) as f32 * PIPE_PREVIEW_SPACING;
+
+    let predicted = predict_pipes(
+        &rng,
+        &active_pattern,
+        *last_gap,
+        config.max_gap_delta,
+        bounds,
+        PIPE_PREVIEW_COUNT,
+    );
+    for (index, roll) in predicted.iter().enumerate() {
+        let color = if roll.is_golden { GOLD } else { WHITE };
+        let x = first_x + index as f32 * PIPE_PREVIEW_SPACING;
+        let gap_size = Vec2::new(PIPE_PREVIEW_WIDTH, config.pipe_gap);
+        gizmos.rect_2d(
+            Isometry2d::from_translation(Vec2::new(x, roll.gap_center)),
+            gap_size,
+            color.with_alpha(0.4),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn predictions_match_the_rolled_pipes() {
+        let bounds = (-100., 200.);
+        let mut rng = GameRng::from_seed(7);
+        let mut pattern = ActivePattern::default();
+        let mut last_gap = LastGapCenter::default();
+        let max_gap_delta = Some(40.);
+
+        let predicted = predict_pipes(&rng, &pattern, last_gap, max_gap_delta, bounds, 5);
+        for prediction in predicted {
+            let roll = roll_pipe(
+                &mut rng.0,
+                &mut pattern,
+                &mut last_gap,
+                max_gap_delta,
+                bounds,
+            )
+            .unwrap();
+            assert_eq!(prediction.gap_center, roll.gap_center);
+            assert_eq!(prediction.is_golden, roll.is_golden);
+        }
+    }
+}
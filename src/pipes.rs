@@ -0,0 +1,498 @@
+use bevy::{color::palettes::css::RED, prelude::*};
+use rand::Rng;
+
+use crate::{
+    components::{Collider, ColliderType, FixedInterpolation, GateTrail, Pipe, PipeCap, PointGate},
+    constants::*,
+    pattern::PatternStep,
+    resources::{ActivePattern, GapDistribution, LastGapCenter},
+};
+
+pub const PIPE_CAP_COLOR: Color = Color::srgb(0.45, 0.75, 0.18);
+
+pub struct PipeRoll {
+    pub gap_center: f32,
+    pub is_golden: bool,
+}
+
+// The world starts at the bottom of the window and extends world_height upwards
+pub fn world_top(world_height: f32) -> f32 {
+    -WINDOW_SIZE.y / 2. + world_height
+}
+
+// Pipes grow with the world so they always reach both its top and the ground
+pub fn pipe_length(world_height: f32) -> f32 {
+    PIPE_HEIGHT * world_height / WINDOW_SIZE.y
+}
+
+// What gets drawn and collided with. Pipes start on screen, so a body this long runs past the
+// visible top and bottom wherever the camera is and however tall the window is. The opening
+// bounds keep using the design length of `pipe_length`
+pub fn pipe_body_length(world_height: f32, visible_height: f32) -> f32 {
+    world_height + (visible_height - WINDOW_SIZE.y).max(0.)
+}
+
+// Only the body stretches, the lip at the gap keeps its drawn height. The inset is in texture
+// pixels, an @2x pipe texture would need it doubled
+fn pipe_slicer() -> SpriteImageMode {
+    SpriteImageMode::Sliced(TextureSlicer {
+        border: BorderRect {
+            min_inset: Vec2::new(0., PIPE_LIP_HEIGHT),
+            max_inset: Vec2::ZERO,
+        },
+        ..default()
+    })
+}
+
+// Range of gap centers that keeps both pipes at least MINIMUM_PIPE_HEIGHT tall in the world,
+// while the sprites still reach the world top and the ground
+pub fn opening_bounds(gap: f32, ground_height: f32, world_height: f32) -> (f32, f32) {
+    let top = world_top(world_height);
+    let ground_top = -WINDOW_SIZE.y / 2. + ground_height;
+    let length = pipe_length(world_height);
+
+    let min = (ground_top + MINIMUM_PIPE_HEIGHT + gap / 2.).max(top - length + gap / 2.);
+    let max = (top - MINIMUM_PIPE_HEIGHT - gap / 2.).min(ground_top + length - gap / 2.);
+
+    if min > max {
+        let center = (ground_top + top) / 2.;
+        return (center, center);
+    }
+    (min, max)
+}
+
+// Darkens lower gaps so the pipe field reads with some depth. Only the lightness changes, so
+// the cue survives any remapping of the hue
+pub fn depth_tint(color: Color, gap_center: f32, (min, max): (f32, f32)) -> Color {
+    let height = if max > min {
+        ((gap_center - min) / (max - min)).clamp(0., 1.)
+    } else {
+        1.
+    };
+    color.darker(PIPE_DEPTH_TINT * (1. - height))
+}
+
+// Center-biased averages two rolls, which keeps the full range but makes the middle most likely.
+// Alternating switches between the upper and lower half of the range on every pipe
+pub fn next_gap_center(
+    rng: &mut impl Rng,
+    distribution: GapDistribution,
+    alternate_high: &mut bool,
+    (min, max): (f32, f32),
+) -> f32 {
+    let middle = (min + max) / 2.;
+    let center = match distribution {
+        GapDistribution::Uniform => rng.gen_range(min..=max),
+        GapDistribution::CenterBiased => (rng.gen_range(min..=max) + rng.gen_range(min..=max)) / 2.,
+        GapDistribution::Alternating => {
+            *alternate_high = !*alternate_high;
+            if *alternate_high {
+                rng.gen_range(middle..=max)
+            } else {
+                rng.gen_range(min..=middle)
+            }
+        }
+    };
+    center.clamp(min, max)
+}
+
+// The previous gap is always inside the opening bounds, so clamping towards it keeps the new
+// center inside them as well. The first pipe of a run has no previous gap and goes anywhere
+pub fn limit_gap_step(center: f32, previous: Option<f32>, max_delta: Option<f32>) -> f32 {
+    match (previous, max_delta) {
+        (Some(previous), Some(max_delta)) => {
+            center.clamp(previous - max_delta, previous + max_delta)
+        }
+        _ => center,
+    }
+}
+
+// Shared by the spawner and the preview, so a preview on a cloned rng matches the real pipes
+// Authored pattern gaps are placed as written, only random ones follow max_gap_delta
+pub fn roll_pipe(
+    rng: &mut impl Rng,
+    pattern: &mut ActivePattern,
+    last_gap: &mut LastGapCenter,
+    max_gap_delta: Option<f32>,
+    (min, max): (f32, f32),
+) -> Option<PipeRoll> {
+    let gap_center = match pattern.next_step() {
+        PatternStep::Random => {
+            let center = next_gap_center(
+                rng,
+                pattern.distribution,
+                &mut pattern.alternate_high,
+                (min, max),
+            );
+            limit_gap_step(center, last_gap.0, max_gap_delta)
+        }
+        PatternStep::Gap(gap) => {
+            let middle = (min + max) / 2.;
+            middle + gap * (max - middle)
+        }
+        PatternStep::Finished => return None,
+    };
+    last_gap.0 = Some(gap_center);
+
+    Some(PipeRoll {
+        gap_center,
+        is_golden: rng.gen_bool(GOLDEN_PIPE_CHANCE),
+    })
+}
+
+pub fn spawn_pipe_pair(
+    commands: &mut Commands,
+    texture_handle: Handle<Image>,
+    gap_center: Vec2,
+    gap: f32,
+    length: f32,
+    color: Color,
+    z: f32,
+) -> Entity {
+    let pipe_offset = gap / 2. + length / 2.;
+
+    commands
+        .spawn((
+            Pipe,
+            FixedInterpolation::default(),
+            Transform {
+                translation: gap_center.extend(z),
+                ..default()
+            },
+            Visibility::Visible,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Collider {
+                    kind: ColliderType::Bad,
+                    size: Vec2::new(PIPE_WIDTH, length),
+                },
+                Sprite {
+                    image: texture_handle.clone(),
+                    color,
+                    custom_size: Some(Vec2::new(PIPE_WIDTH, length)),
+                    image_mode: pipe_slicer(),
+                    flip_y: true,
+                    ..default()
+                },
+                Transform::from_xyz(0., pipe_offset, 0.),
+            ));
+
+            parent.spawn((
+                Collider {
+                    kind: ColliderType::Bad,
+                    size: Vec2::new(PIPE_WIDTH, length),
+                },
+                Sprite {
+                    image: texture_handle.clone(),
+                    color,
+                    custom_size: Some(Vec2::new(PIPE_WIDTH, length)),
+                    image_mode: pipe_slicer(),
+                    ..default()
+                },
+                Transform {
+                    translation: Vec3::new(0., -pipe_offset, 0.),
+                    ..default()
+                },
+            ));
+
+            parent.spawn((
+                PointGate,
+                GateTrail::default(),
+                Collider {
+                    kind: ColliderType::Good,
+                    size: Vec2::new(10., gap),
+                },
+                Sprite::from_color(RED, Vec2::new(10., gap)),
+                Transform {
+                    // scale: Vec3::new(10., BASE_PIPE_SPACE, 0.),
+                    ..Default::default()
+                },
+            ));
+        })
+        .id()
+}
+
+// Purely decorative lips at the gap-facing ends, they have no collider and leave the gate alone
+pub fn add_pipe_caps(commands: &mut Commands, pipe: Entity, gap: f32, color: Color) {
+    let cap_offset = gap / 2. + PIPE_CAP_HEIGHT / 2.;
+    commands.entity(pipe).with_children(|parent| {
+        for y in [cap_offset, -cap_offset] {
+            parent.spawn((
+                PipeCap,
+                Sprite::from_color(color, Vec2::new(PIPE_CAP_WIDTH, PIPE_CAP_HEIGHT)),
+                Transform::from_xyz(0., y, 0.1),
+            ));
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn depth_tint_darkens_lower_gaps() {
+        let bounds = (-100., 100.);
+        let high = depth_tint(Color::WHITE, 100., bounds);
+        let middle = depth_tint(Color::WHITE, 0., bounds);
+        let low = depth_tint(Color::WHITE, -100., bounds);
+
+        assert!(high.luminance() > middle.luminance());
+        assert!(middle.luminance() > low.luminance());
+    }
+
+    #[test]
+    fn opening_bounds_keep_the_gap_inside_the_playable_region() {
+        let screen_top = WINDOW_SIZE.y / 2.;
+        for ground_height in (0..=300).step_by(25) {
+            for gap in (50..=900).step_by(25) {
+                let (gap, ground_height) = (gap as f32, ground_height as f32);
+                let (min, max) = opening_bounds(gap, ground_height, WINDOW_SIZE.y);
+                assert!(min <= max, "{min} > {max} for {gap} {ground_height}");
+
+                let ground_top = -WINDOW_SIZE.y / 2. + ground_height;
+                if gap > screen_top - ground_top {
+                    continue;
+                }
+                for center in [min, max] {
+                    assert!(center - gap / 2. >= ground_top);
+                    assert!(center + gap / 2. <= screen_top);
+                }
+            }
+        }
+    }
+
+    // Both pipes keep their minimum height and their sprites still reach the screen top and the
+    // ground at the lowest, middle and highest gap
+    #[test]
+    fn opening_bounds_respect_the_ground_and_the_screen_top() {
+        let screen_top = WINDOW_SIZE.y / 2.;
+        for ground_height in (0..=400).step_by(20) {
+            let ground_height = ground_height as f32;
+            let ground_top = -WINDOW_SIZE.y / 2. + ground_height;
+            let (min, max) = opening_bounds(BASE_PIPE_SPACE, ground_height, WINDOW_SIZE.y);
+            if min == max {
+                continue;
+            }
+
+            for center in [min, (min + max) / 2., max] {
+                let gap_bottom = center - BASE_PIPE_SPACE / 2.;
+                let gap_top = center + BASE_PIPE_SPACE / 2.;
+                assert!(gap_bottom >= ground_top + MINIMUM_PIPE_HEIGHT - 1e-3);
+                assert!(gap_top <= screen_top - MINIMUM_PIPE_HEIGHT + 1e-3);
+                assert!(gap_bottom - PIPE_HEIGHT <= ground_top);
+                assert!(gap_top + PIPE_HEIGHT >= screen_top);
+            }
+        }
+    }
+
+    // The scrolling world is taller than the window, its pipes still span it from top to ground
+    #[test]
+    fn opening_bounds_fill_a_taller_world() {
+        let top = world_top(SCROLLING_WORLD_HEIGHT);
+        let ground_top = -WINDOW_SIZE.y / 2. + GROUND_HEIGHT;
+        let length = pipe_length(SCROLLING_WORLD_HEIGHT);
+        let (min, max) = opening_bounds(BASE_PIPE_SPACE, GROUND_HEIGHT, SCROLLING_WORLD_HEIGHT);
+
+        assert!(max > WINDOW_SIZE.y / 2.);
+        for center in [min, max] {
+            assert!(center - BASE_PIPE_SPACE / 2. - length <= ground_top);
+            assert!(center + BASE_PIPE_SPACE / 2. + length >= top);
+        }
+    }
+
+    // However tall the window and wherever the camera sits, no pipe end comes into view
+    #[test]
+    fn pipe_bodies_run_off_screen_for_any_window_height() {
+        use crate::camera::camera_target;
+
+        for world_height in [WINDOW_SIZE.y, SCROLLING_WORLD_HEIGHT] {
+            let (min, max) = opening_bounds(BASE_PIPE_SPACE, GROUND_HEIGHT, world_height);
+            let lowest_view = camera_target(None, world_height);
+            let highest_view = camera_target(Some(f32::MAX), world_height);
+            for visible_height in [720., 1080., 1440., 2160., 3000.] {
+                let length = pipe_body_length(world_height, visible_height);
+                for center in [min, max] {
+                    let top = center + BASE_PIPE_SPACE / 2. + length;
+                    let bottom = center - BASE_PIPE_SPACE / 2. - length;
+                    assert!(
+                        top >= highest_view + visible_height / 2.,
+                        "{visible_height}"
+                    );
+                    assert!(
+                        bottom <= lowest_view - visible_height / 2.,
+                        "{visible_height}"
+                    );
+                }
+            }
+        }
+    }
+
+    // A gap taller than the playable area has no valid range, it sits in the middle instead
+    #[test]
+    fn opening_bounds_collapse_when_the_gap_does_not_fit() {
+        let (min, max) = opening_bounds(WINDOW_SIZE.y, GROUND_HEIGHT, WINDOW_SIZE.y);
+        let ground_top = -WINDOW_SIZE.y / 2. + GROUND_HEIGHT;
+
+        assert_eq!(min, max);
+        assert_eq!(min, (ground_top + WINDOW_SIZE.y / 2.) / 2.);
+    }
+
+    // The top pipe is mirrored with flip_y instead of a rotation, so its box is the sprite
+    #[test]
+    fn top_pipe_is_flipped_without_a_rotation() {
+        let mut world = World::new();
+        let pipe = spawn_pipe_pair(
+            &mut world.commands(),
+            Handle::default(),
+            Vec2::new(0., 50.),
+            BASE_PIPE_SPACE,
+            PIPE_HEIGHT,
+            Color::WHITE,
+            PIPE_Z,
+        );
+        world.flush();
+
+        let children = world.get::<Children>(pipe).unwrap().to_vec();
+        let (collider, sprite, transform) = children
+            .iter()
+            .find_map(|&child| {
+                let entity = world.entity(child);
+                let transform = entity.get::<Transform>()?;
+                (transform.translation.y > 0.).then(|| {
+                    (
+                        entity.get::<Collider>().unwrap(),
+                        entity.get::<Sprite>().unwrap(),
+                        transform,
+                    )
+                })
+            })
+            .unwrap();
+
+        assert!(sprite.flip_y);
+        assert_eq!(transform.rotation, Quat::IDENTITY);
+        assert_eq!(Some(collider.size), sprite.custom_size);
+        assert_eq!(
+            transform.translation.y - collider.size.y / 2.,
+            BASE_PIPE_SPACE / 2.
+        );
+    }
+
+    #[test]
+    fn caps_leave_the_scoring_gate_alone() {
+        let mut world = World::new();
+        let pipe = {
+            let mut commands = world.commands();
+            let pipe = spawn_pipe_pair(
+                &mut commands,
+                Handle::default(),
+                Vec2::ZERO,
+                BASE_PIPE_SPACE,
+                PIPE_HEIGHT,
+                Color::WHITE,
+                PIPE_Z,
+            );
+            add_pipe_caps(&mut commands, pipe, BASE_PIPE_SPACE, Color::WHITE);
+            pipe
+        };
+        world.flush();
+
+        let children = world.get::<Children>(pipe).unwrap().to_vec();
+        let caps: Vec<_> = children
+            .iter()
+            .filter(|&&child| world.get::<PipeCap>(child).is_some())
+            .collect();
+        assert_eq!(caps.len(), 2);
+        for &&cap in &caps {
+            assert!(world.get::<Collider>(cap).is_none());
+            let cap_y = world.get::<Transform>(cap).unwrap().translation.y;
+            assert!(cap_y.abs() - PIPE_CAP_HEIGHT / 2. >= BASE_PIPE_SPACE / 2.);
+        }
+
+        let gate = children
+            .iter()
+            .find(|&&child| world.get::<PointGate>(child).is_some())
+            .unwrap();
+        let gate_collider = world.get::<Collider>(*gate).unwrap();
+        assert_eq!(gate_collider.size, Vec2::new(10., BASE_PIPE_SPACE));
+        assert_eq!(
+            world.get::<Transform>(*gate).unwrap().translation,
+            Vec3::ZERO
+        );
+    }
+
+    // Every distribution stays inside the bounds, and alternating gaps flip sides every pipe
+    #[test]
+    fn gap_distributions_respect_the_opening_bounds() {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let bounds = (-120., 180.);
+        let middle = (bounds.0 + bounds.1) / 2.;
+        let mut rng = StdRng::seed_from_u64(3);
+
+        for distribution in [
+            GapDistribution::Uniform,
+            GapDistribution::CenterBiased,
+            GapDistribution::Alternating,
+        ] {
+            let mut alternate_high = false;
+            let centers: Vec<f32> = (0..200)
+                .map(|_| next_gap_center(&mut rng, distribution, &mut alternate_high, bounds))
+                .collect();
+            assert!(centers
+                .iter()
+                .all(|&center| center >= bounds.0 && center <= bounds.1));
+
+            if distribution == GapDistribution::Alternating {
+                for (index, &center) in centers.iter().enumerate() {
+                    let is_high = index % 2 == 0;
+                    assert_eq!(center >= middle, is_high, "pipe {index} at {center}");
+                }
+            }
+        }
+
+        let mut alternate_high = false;
+        let collapsed = next_gap_center(
+            &mut rng,
+            GapDistribution::Alternating,
+            &mut alternate_high,
+            (40., 40.),
+        );
+        assert_eq!(collapsed, 40.);
+    }
+
+    #[test]
+    fn smooth_gaps_never_jump_further_than_the_delta() {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let bounds = opening_bounds(BASE_PIPE_SPACE, GROUND_HEIGHT, WINDOW_SIZE.y);
+        let mut rng = StdRng::seed_from_u64(11);
+        let mut pattern = ActivePattern::default();
+        let mut last_gap = LastGapCenter::default();
+        let max_delta = 50.;
+
+        let centers: Vec<f32> = (0..200)
+            .map(|_| {
+                roll_pipe(
+                    &mut rng,
+                    &mut pattern,
+                    &mut last_gap,
+                    Some(max_delta),
+                    bounds,
+                )
+                .unwrap()
+                .gap_center
+            })
+            .collect();
+        for pair in centers.windows(2) {
+            assert!((pair[1] - pair[0]).abs() <= max_delta, "{pair:?}");
+        }
+        assert!(centers
+            .iter()
+            .all(|&center| center >= bounds.0 && center <= bounds.1));
+
+        // A fresh run starts wherever the roll lands
+        assert_eq!(limit_gap_step(bounds.1, None, Some(max_delta)), bounds.1);
+    }
+}
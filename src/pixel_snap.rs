@@ -0,0 +1,42 @@
+use bevy::{prelude::*, window::PrimaryWindow};
+
+use crate::{interpolation::interpolate_physics_positions, resources::Settings};
+
+pub fn pixel_snap_plugin(app: &mut App) {
+    app.add_systems(
+        Last,
+        snap_sprites_to_pixels
+            .after(interpolate_physics_positions)
+            .run_if(pixel_snap_enabled),
+    );
+}
+
+fn pixel_snap_enabled(settings: Res<Settings>) -> bool {
+    settings.pixel_snap
+}
+
+// Physical screen pixels per world unit, a HiDPI window or a zoomed camera makes pixels finer or
+// coarser than one unit
+fn pixels_per_unit(scale_factor: f32, projection: &Projection) -> f32 {
+    let projection_scale = match projection {
+        Projection::Orthographic(orthographic) => orthographic.scale,
+        _ => 1.,
+    };
+    scale_factor / projection_scale
+}
+
+// Runs after collision detection, transform propagation and interpolation, so only the rendered
+// position is rounded while Transform keeps the exact physics value
+fn snap_sprites_to_pixels(
+    window: Single<&Window, With<PrimaryWindow>>,
+    projection: Single<&Projection, With<Camera2d>>,
+    mut query: Query<&mut GlobalTransform, With<Sprite>>,
+) {
+    let pixels_per_unit = pixels_per_unit(window.scale_factor(), &projection);
+    for mut global_transform in query.iter_mut() {
+        let mut affine = global_transform.affine();
+        affine.translation.x = (affine.translation.x * pixels_per_unit).round() / pixels_per_unit;
+        affine.translation.y = (affine.translation.y * pixels_per_unit).round() / pixels_per_unit;
+        *global_transform = GlobalTransform::from(affine);
+    }
+}
@@ -0,0 +1,66 @@
+use crate::constants::*;
+
+// Where the pipes sit when practice starts further down the course. The target pipe is half a
+// spacing ahead of the bird, so the one before it has just been passed, and the rest follow at
+// the usual spacing up to the spawn point, the same picture a flown run would show
+pub struct PracticeSection {
+    pub target: u32,
+    // Index of the newest pipe, the one closest to the spawn point
+    pub last: u32,
+    target_x: f32,
+    spacing: f32,
+}
+
+impl PracticeSection {
+    pub fn new(target: u32, lane_x: f32, spacing: f32) -> Self {
+        let target_x = lane_x + spacing / 2.;
+        let ahead = ((PIPE_SPAWN_X - target_x) / spacing).floor().max(0.) as u32;
+        Self {
+            target,
+            last: target + ahead,
+            target_x,
+            spacing,
+        }
+    }
+
+    pub fn pipe_x(&self, index: u32) -> f32 {
+        self.target_x + (index as f32 - self.target as f32) * self.spacing
+    }
+
+    // Same cut-off as despawn_pipes, anything further left would already be gone
+    pub fn is_on_screen(&self, index: u32) -> bool {
+        self.pipe_x(index) >= PIPE_DESPAWN_X
+    }
+
+    // How long ago the newest pipe would have spawned, for the spawn timer's elapsed time
+    pub fn since_last_spawn(&self, pipe_speed: f32) -> f32 {
+        (PIPE_SPAWN_X - self.pipe_x(self.last)) / pipe_speed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_section_looks_like_a_flown_run() {
+        let lane_x = PLAYER_START_POSITION.x;
+        for spacing in [MIN_PIPE_SPACING, BASE_PIPE_SPACING, MAX_PIPE_SPACING] {
+            let section = PracticeSection::new(15, lane_x, spacing);
+            let bird_clearance = PIPE_WIDTH / 2. + PLAYER_SIZE.x as f32 / 2.;
+
+            assert!(section.pipe_x(section.target) > lane_x + bird_clearance);
+            assert!(section.pipe_x(section.target - 1) < lane_x - bird_clearance);
+            assert!(section.pipe_x(section.last) <= PIPE_SPAWN_X);
+            assert!(section.pipe_x(section.last) + spacing > PIPE_SPAWN_X);
+
+            let interval = spacing / PIPE_BASE_SPEED;
+            let since_spawn = section.since_last_spawn(PIPE_BASE_SPEED);
+            assert!(
+                (0. ..interval).contains(&since_spawn),
+                "{since_spawn} {interval}"
+            );
+            assert!(!section.is_on_screen(0));
+        }
+    }
+}
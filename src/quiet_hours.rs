@@ -0,0 +1,127 @@
+use bevy::{audio::Volume, prelude::*, window::WindowFocused};
+use chrono::{Local, Timelike};
+
+use crate::{
+    components::QuietHoursText,
+    resources::{AudioMute, Settings},
+    PauseState,
+};
+
+pub fn quiet_hours_plugin(app: &mut App) {
+    app.init_resource::<AudioMute>()
+        .add_systems(Startup, (spawn_quiet_hours_text, check_quiet_hours).chain())
+        .add_systems(OnExit(PauseState::Paused), check_quiet_hours)
+        .add_systems(
+            Update,
+            (
+                check_quiet_hours.run_if(resource_changed::<Settings>.or(window_refocused)),
+                toggle_mute,
+                apply_audio_mute.run_if(resource_changed::<AudioMute>),
+            )
+                .chain(),
+        );
+}
+
+// The window wraps past midnight when it ends earlier than it starts, 22 to 7 covers the night
+pub fn in_quiet_hours(hour: u32, start: u32, end: u32) -> bool {
+    if start <= end {
+        (start..end).contains(&hour)
+    } else {
+        hour >= start || hour < end
+    }
+}
+
+fn window_refocused(mut focus_events: MessageReader<WindowFocused>) -> bool {
+    // Reads every message, a short-circuiting any() would leave the rest for the next frame
+    focus_events
+        .read()
+        .filter(|focus_event| focus_event.focused)
+        .count()
+        > 0
+}
+
+fn spawn_quiet_hours_text(mut commands: Commands) {
+    commands.spawn((
+        QuietHoursText,
+        Text::new("Muted (quiet hours)"),
+        TextFont {
+            font_size: 16.0,
+            ..default()
+        },
+        TextColor(Color::WHITE.with_alpha(0.7)),
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: px(5),
+            left: px(5),
+            ..default()
+        },
+        Visibility::Hidden,
+        Pickable::IGNORE,
+    ));
+}
+
+// Only ever mutes once per entry into the window, so a manual unmute holds for the session
+fn check_quiet_hours(settings: Res<Settings>, mut mute: ResMut<AudioMute>) {
+    let quiet = settings.quiet_hours
+        && in_quiet_hours(
+            Local::now().hour(),
+            settings.quiet_hours_start,
+            settings.quiet_hours_end,
+        );
+
+    if quiet && !mute.muted && !mute.manually_unmuted {
+        mute.muted = true;
+        mute.by_quiet_hours = true;
+    } else if !quiet && mute.by_quiet_hours {
+        mute.muted = false;
+        mute.by_quiet_hours = false;
+    }
+}
+
+fn toggle_mute(key_input: Res<ButtonInput<KeyCode>>, mut mute: ResMut<AudioMute>) {
+    if !key_input.just_pressed(KeyCode::KeyM) {
+        return;
+    }
+
+    if mute.by_quiet_hours {
+        mute.manually_unmuted = true;
+    }
+    mute.muted = !mute.muted;
+    mute.by_quiet_hours = false;
+}
+
+// Sound effects pick up the global volume when they spawn, the looping music layers read the
+// mute in their own volume blend
+fn apply_audio_mute(
+    mute: Res<AudioMute>,
+    mut global_volume: ResMut<GlobalVolume>,
+    mut text: Single<&mut Visibility, With<QuietHoursText>>,
+) {
+    global_volume.volume = if mute.muted {
+        Volume::SILENT
+    } else {
+        Volume::Linear(1.)
+    };
+    **text = if mute.by_quiet_hours {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quiet_hours_wrap_past_midnight() {
+        assert!(in_quiet_hours(23, 22, 7));
+        assert!(in_quiet_hours(3, 22, 7));
+        assert!(!in_quiet_hours(7, 22, 7));
+        assert!(!in_quiet_hours(12, 22, 7));
+
+        assert!(in_quiet_hours(14, 13, 15));
+        assert!(!in_quiet_hours(15, 13, 15));
+        assert!(!in_quiet_hours(0, 5, 5));
+    }
+}
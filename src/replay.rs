@@ -0,0 +1,366 @@
+use std::fmt;
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use bevy::prelude::*;
+
+use crate::{
+    asset_variants::image_variant,
+    clipboard,
+    components::{
+        Airtime, AnimationTimer, FixedInterpolation, Ghost, GhostPlayback, ReplayMessageText,
+        Velocity,
+    },
+    constants::*,
+    events::JumpEvent,
+    gravity::fall_step,
+    layers::ZLayers,
+    pipes::world_top,
+    resources::{
+        ActiveReplay, BirdSkin, CurrentRunSeed, GameConfig, GameRng, HiDpi, ReplayRecorder,
+        RunTimer,
+    },
+    step::physics_running,
+    AppState,
+};
+
+const SHARE_CODE_VERSION: u8 = 1;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Replay {
+    pub seed: u64,
+    pub jumps: Vec<u32>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ShareCodeError {
+    InvalidEncoding,
+    Truncated,
+    UnsupportedVersion(u8),
+    ChecksumMismatch,
+    Malformed,
+}
+
+impl fmt::Display for ShareCodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ShareCodeError::InvalidEncoding => write!(f, "not a valid share code"),
+            ShareCodeError::Truncated => write!(f, "share code is incomplete"),
+            ShareCodeError::UnsupportedVersion(version) => {
+                write!(f, "share code version {version} is not supported")
+            }
+            ShareCodeError::ChecksumMismatch => write!(f, "share code is corrupted"),
+            ShareCodeError::Malformed => write!(f, "share code is malformed"),
+        }
+    }
+}
+
+impl Replay {
+    // Layout: version, seed (u64 LE), jump time deltas in ms (varints), checksum (u16 LE)
+    pub fn to_share_code(&self) -> String {
+        let mut bytes = vec![SHARE_CODE_VERSION];
+        bytes.extend_from_slice(&self.seed.to_le_bytes());
+
+        let mut previous_jump = 0;
+        for &jump in &self.jumps {
+            write_varint(&mut bytes, jump.saturating_sub(previous_jump));
+            previous_jump = jump;
+        }
+
+        bytes.extend_from_slice(&checksum(&bytes).to_le_bytes());
+        URL_SAFE_NO_PAD.encode(bytes)
+    }
+
+    pub fn from_share_code(code: &str) -> Result<Self, ShareCodeError> {
+        let bytes = URL_SAFE_NO_PAD
+            .decode(code.trim())
+            .map_err(|_| ShareCodeError::InvalidEncoding)?;
+
+        let Some(&version) = bytes.first() else {
+            return Err(ShareCodeError::Truncated);
+        };
+        if version != SHARE_CODE_VERSION {
+            return Err(ShareCodeError::UnsupportedVersion(version));
+        }
+
+        let (payload, stored_checksum) = bytes
+            .split_last_chunk::<2>()
+            .ok_or(ShareCodeError::Truncated)?;
+        if checksum(payload) != u16::from_le_bytes(*stored_checksum) {
+            return Err(ShareCodeError::ChecksumMismatch);
+        }
+
+        let (seed_bytes, mut rest) = payload[1..]
+            .split_first_chunk::<8>()
+            .ok_or(ShareCodeError::Truncated)?;
+        let seed = u64::from_le_bytes(*seed_bytes);
+
+        let mut jumps = Vec::new();
+        let mut jump_time: u32 = 0;
+        while !rest.is_empty() {
+            let delta = read_varint(&mut rest)?;
+            jump_time = jump_time
+                .checked_add(delta)
+                .ok_or(ShareCodeError::Malformed)?;
+            jumps.push(jump_time);
+        }
+
+        Ok(Self { seed, jumps })
+    }
+}
+
+fn write_varint(bytes: &mut Vec<u8>, mut value: u32) {
+    while value >= 0x80 {
+        bytes.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    bytes.push(value as u8);
+}
+
+fn read_varint(bytes: &mut &[u8]) -> Result<u32, ShareCodeError> {
+    let mut value: u32 = 0;
+    for shift in (0..32).step_by(7) {
+        let (&byte, rest) = bytes.split_first().ok_or(ShareCodeError::Truncated)?;
+        *bytes = rest;
+        value |= u32::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err(ShareCodeError::Malformed)
+}
+
+// Fletcher-16
+fn checksum(bytes: &[u8]) -> u16 {
+    let (sum, sum_of_sums) = bytes
+        .iter()
+        .fold((0u16, 0u16), |(sum, sum_of_sums), &byte| {
+            let sum = (sum + u16::from(byte)) % 255;
+            (sum, (sum_of_sums + sum) % 255)
+        });
+    (sum_of_sums << 8) | sum
+}
+
+pub fn replay_plugin(app: &mut App) {
+    app.init_resource::<ReplayRecorder>()
+        .init_resource::<ActiveReplay>()
+        .add_systems(
+            OnEnter(AppState::GameStart),
+            (reset_recorder, clear_active_replay, spawn_import_message),
+        )
+        .add_systems(OnEnter(AppState::InGame), spawn_ghost)
+        .add_systems(OnEnter(AppState::GameOver), spawn_export_message)
+        .add_systems(
+            Update,
+            import_share_code.run_if(in_state(AppState::GameStart)),
+        )
+        .add_systems(
+            FixedUpdate,
+            play_ghost.run_if(in_state(AppState::InGame).and(physics_running)),
+        )
+        .add_systems(
+            Update,
+            export_share_code.run_if(in_state(AppState::GameOver)),
+        )
+        .add_observer(record_jump);
+}
+
+fn reset_recorder(mut recorder: ResMut<ReplayRecorder>) {
+    recorder.jumps.clear();
+}
+
+fn clear_active_replay(mut active_replay: ResMut<ActiveReplay>) {
+    active_replay.0 = None;
+}
+
+fn record_jump(
+    _jump_event: On<JumpEvent>,
+    run_timer: Res<RunTimer>,
+    mut recorder: ResMut<ReplayRecorder>,
+) {
+    recorder
+        .jumps
+        .push(run_timer.0.elapsed().as_millis() as u32);
+}
+
+fn spawn_import_message(commands: Commands) {
+    spawn_replay_message(
+        commands,
+        "Press V to import a share code",
+        AppState::GameStart,
+    );
+}
+
+fn spawn_export_message(commands: Commands) {
+    spawn_replay_message(
+        commands,
+        "Press C to copy a share code of this run",
+        AppState::GameOver,
+    );
+}
+
+fn spawn_replay_message(mut commands: Commands, message: &str, state: AppState) {
+    commands.spawn((
+        ReplayMessageText,
+        Text::new(message),
+        TextFont {
+            font_size: 30.0,
+            ..default()
+        },
+        TextColor(Color::WHITE),
+        Node {
+            position_type: PositionType::Absolute,
+            top: px(5),
+            left: px(5),
+            ..default()
+        },
+        DespawnOnExit(state),
+    ));
+}
+
+fn import_share_code(
+    key_input: Res<ButtonInput<KeyCode>>,
+    mut commands: Commands,
+    mut rng: ResMut<GameRng>,
+    mut current_seed: ResMut<CurrentRunSeed>,
+    mut message: Single<&mut Text, With<ReplayMessageText>>,
+) {
+    if !key_input.just_pressed(KeyCode::KeyV) {
+        return;
+    }
+
+    let replay = clipboard::get_text()
+        .and_then(|code| Replay::from_share_code(&code).map_err(|error| error.to_string()));
+
+    match replay {
+        Ok(replay) => {
+            *rng = GameRng::from_seed(replay.seed);
+            current_seed.0 = replay.seed;
+            message.0 = format!(
+                "Loaded a run with {} jumps, click to race its ghost",
+                replay.jumps.len()
+            );
+            commands.insert_resource(ActiveReplay(Some(replay)));
+        }
+        Err(error) => {
+            warn!("Failed to import share code: {error}");
+            message.0 = format!("Could not import share code: {error}");
+        }
+    }
+}
+
+fn export_share_code(
+    key_input: Res<ButtonInput<KeyCode>>,
+    current_seed: Res<CurrentRunSeed>,
+    recorder: Res<ReplayRecorder>,
+    mut message: Single<&mut Text, With<ReplayMessageText>>,
+) {
+    if !key_input.just_pressed(KeyCode::KeyC) {
+        return;
+    }
+
+    let code = Replay {
+        seed: current_seed.0,
+        jumps: recorder.jumps.clone(),
+    }
+    .to_share_code();
+    info!("Share code: {code}");
+
+    message.0 = match clipboard::set_text(code) {
+        Ok(()) => "Share code copied to clipboard".into(),
+        Err(error) => {
+            warn!("Failed to copy share code: {error}");
+            format!("Could not copy share code: {error}")
+        }
+    };
+}
+
+#[allow(
+    clippy::too_many_arguments,
+    reason = "the ghost is built from the same skin, layers and config as the player"
+)]
+fn spawn_ghost(
+    mut commands: Commands,
+    active_replay: Res<ActiveReplay>,
+    asset_server: Res<AssetServer>,
+    hidpi: Res<HiDpi>,
+    skin: Res<BirdSkin>,
+    layers: Res<ZLayers>,
+    config: Res<GameConfig>,
+    mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
+) {
+    let Some(replay) = &active_replay.0 else {
+        return;
+    };
+
+    let variant = image_variant(skin.0.image, &hidpi);
+    let texture: Handle<Image> = asset_server.load(variant.path);
+    let layout_handle = texture_atlas_layouts.add(skin.0.atlas_layout(variant.scale));
+    let animation_indices = skin.0.animation_indices();
+
+    commands.spawn((
+        Ghost,
+        GhostPlayback {
+            jumps: replay.jumps.clone(),
+            next_jump: 0,
+        },
+        Velocity(0.0),
+        Airtime::default(),
+        FixedInterpolation::default(),
+        AnimationTimer(Timer::from_seconds(0.15, TimerMode::Repeating)),
+        Transform::from_xyz(config.lane_x, PLAYER_START_POSITION.y, layers.ghost),
+        Sprite {
+            image: texture,
+            texture_atlas: Some(TextureAtlas {
+                layout: layout_handle,
+                index: animation_indices.first,
+            }),
+            color: Color::WHITE.with_alpha(0.4),
+            custom_size: Some(PLAYER_SIZE.as_vec2()),
+            flip_x: skin.0.flip_x,
+            ..default()
+        },
+        animation_indices,
+        DespawnOnExit(AppState::InGame),
+    ));
+}
+
+fn play_ghost(
+    time: Res<Time>,
+    run_timer: Res<RunTimer>,
+    config: Res<GameConfig>,
+    mut query: Query<
+        (
+            &mut Transform,
+            &mut Velocity,
+            &mut Airtime,
+            &mut GhostPlayback,
+        ),
+        With<Ghost>,
+    >,
+) {
+    let elapsed_ms = run_timer.0.elapsed().as_millis() as u32;
+
+    for (mut transform, mut velocity, mut airtime, mut playback) in &mut query {
+        while playback
+            .jumps
+            .get(playback.next_jump)
+            .is_some_and(|&jump| jump <= elapsed_ms)
+        {
+            velocity.0 = config.jump_mode.jump(velocity.0, config.jump_velocity);
+            airtime.0 = 0.;
+            playback.next_jump += 1;
+        }
+
+        let acceleration = config.gravity * config.gravity_curve.factor(airtime.0);
+        let (y, new_velocity) = fall_step(
+            transform.translation.y,
+            velocity.0,
+            acceleration,
+            time.delta_secs(),
+        );
+        airtime.0 += time.delta_secs();
+        transform.translation.y =
+            y.min(world_top(config.world_height) + PLAYER_SIZE.as_vec2().y / 2.);
+        velocity.0 = new_velocity;
+    }
+}
@@ -1,7 +1,974 @@
-use bevy::{ecs::resource::Resource, time::Timer};
+use std::{collections::VecDeque, time::Duration};
+
+use bevy::{
+    ecs::{entity::Entity, resource::Resource},
+    input::mouse::MouseButton,
+    math::{Rect, Vec3},
+    prelude::{Deref, DerefMut},
+    time::{Stopwatch, Timer},
+};
+use rand::{rngs::StdRng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    components::HudElement,
+    constants::{
+        BASE_PIPE_SPACE, BASE_PIPE_SPACING, GRAVITY, GROUND_HEIGHT, IDLE_BOB_AMPLITUDE,
+        IDLE_BOB_FREQUENCY, MAX_ADAPTIVE_LEVEL, MAX_AUTO_RESTART_DELAY, MAX_BIRD_SCALE,
+        MAX_HUD_SCALE, MAX_JUMP_STACK, MAX_LIVE_PIPES, MAX_PIPE_SPACING, MAX_RETRY_COOLDOWN,
+        MAX_START_GRACE, MAX_TURBO_SPEED, MIN_AUTO_RESTART_DELAY, MIN_BIRD_SCALE, MIN_HUD_SCALE,
+        MIN_PIPE_SPACING, MIN_TURBO_SPEED, PIPE_BASE_SPEED, PLAYER_GATE_HITBOX_SCALE,
+        PLAYER_HITBOX_SCALE, PLAYER_JUMP_VELOCITY, PLAYER_START_POSITION, WINDOW_SIZE,
+    },
+    pattern::PipePattern,
+    replay::Replay,
+    run_log::RunRecord,
+    skins::{BirdSkinDef, CLASSIC_BIRD_SKIN},
+};
 
 #[derive(Resource)]
 pub struct PipeSpawnTimer(pub Timer);
 
+#[derive(Resource)]
+pub struct DemoPipeTimer(pub Timer);
+
+#[derive(Resource)]
+pub struct AutoFlapTimer(pub Timer);
+
 #[derive(Resource, Debug)]
 pub struct Score(pub i32);
+
+#[derive(Resource, Default)]
+pub struct LastMilestone(pub i32);
+
+#[derive(Resource, Default)]
+pub struct DisplayedScore(pub f32);
+
+// What the HUD shows besides the score, only updated by the events that change it
+#[derive(Resource, Default)]
+pub struct HudState {
+    pub birds_alive: usize,
+    pub birds_total: usize,
+    pub lives: u32,
+    // Whole seconds left on the score attack clock, rounded up
+    pub clock_seconds: u32,
+    // How much of the current point is left before score decay takes it
+    pub decay_remaining: f32,
+    pub shield: bool,
+}
+
+// Which HUD stats the current mode shows
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct HudLayout {
+    pub flock: bool,
+    pub lives: bool,
+    pub clock: bool,
+    pub decay: bool,
+    pub shield: bool,
+}
+
+impl HudLayout {
+    pub fn for_mode(mode: GameMode, lives: u32, shield_pickups: bool) -> Self {
+        Self {
+            flock: mode == GameMode::Flock,
+            // Score attack charges crashes to the clock, so spare lives are never used
+            lives: lives > 1 && mode != GameMode::ScoreAttack,
+            clock: mode == GameMode::ScoreAttack,
+            decay: mode == GameMode::ScoreDecay,
+            shield: shield_pickups,
+        }
+    }
+
+    pub fn shows(&self, element: HudElement) -> bool {
+        match element {
+            HudElement::Flock => self.flock,
+            HudElement::Lives => self.lives,
+            HudElement::Clock => self.clock,
+            HudElement::DecayBar => self.decay,
+            HudElement::Shield => self.shield,
+        }
+    }
+}
+
+#[derive(Resource, Default, Serialize, Deserialize)]
+pub struct HighScore(pub i32);
+
+// Score attack has its own table, a fixed clock is not comparable to a run until death
+#[derive(Resource, Default, Serialize, Deserialize)]
+pub struct TimedHighScore(pub i32);
+
+#[derive(Resource, Default)]
+pub struct RunFlags {
+    pub slow_motion: bool,
+    pub turbo: bool,
+    pub custom_rules: bool,
+    pub handicap: bool,
+    pub assisted: bool,
+    pub stepped: bool,
+    pub auto_flap: bool,
+    pub custom_feel: bool,
+    pub easy_assist: bool,
+    pub rescue_flap: bool,
+    pub tutorial_save: bool,
+    pub pipe_preview: bool,
+    pub extra_lives: bool,
+    pub start_grace: bool,
+    pub bird_size: bool,
+    pub autopilot: bool,
+}
+
+impl RunFlags {
+    pub fn is_ranked(&self) -> bool {
+        !self.slow_motion
+            && !self.turbo
+            && !self.custom_rules
+            && !self.handicap
+            && !self.assisted
+            && !self.stepped
+            && !self.auto_flap
+            && !self.custom_feel
+            && !self.easy_assist
+            && !self.rescue_flap
+            && !self.tutorial_save
+            && !self.pipe_preview
+            && !self.extra_lives
+            && !self.start_grace
+            && !self.bird_size
+            && !self.autopilot
+    }
+}
+
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum GameMode {
+    #[default]
+    Normal,
+    Custom,
+    Flock,
+    Adaptive,
+    Practice,
+    Pattern,
+    Scrolling,
+    ScoreAttack,
+    ScoreDecay,
+}
+
+#[derive(Resource)]
+pub struct GameConfig {
+    pub pipe_speed: f32,
+    pub pipe_spacing: f32,
+    pub pipe_gap: f32,
+    // pipe_spawner refuses to spawn past this many live pipes
+    pub max_pipes: usize,
+    pub ground_height: f32,
+    pub world_height: f32,
+    pub bird_count: u32,
+    // The bird's hitbox against pipes and the ground
+    pub hitbox_scale: f32,
+    // The bird's hitbox against point gates
+    pub gate_hitbox_scale: f32,
+    pub gravity: f32,
+    pub gravity_curve: GravityCurve,
+    pub jump_mode: JumpMode,
+    // Share of gravity left while the flap is held on the way down, None turns gliding off
+    pub glide_factor: Option<f32>,
+    // How far a random gap may move from the previous one, None leaves the gaps unconstrained
+    pub max_gap_delta: Option<f32>,
+    pub jump_velocity: f32,
+    pub idle_bob_amplitude: f32,
+    pub idle_bob_frequency: f32,
+    // Horizontal lane of the lead bird
+    pub lane_x: f32,
+}
+
+impl Default for GameConfig {
+    fn default() -> Self {
+        Self {
+            pipe_speed: PIPE_BASE_SPEED,
+            pipe_spacing: BASE_PIPE_SPACING,
+            pipe_gap: BASE_PIPE_SPACE,
+            max_pipes: MAX_LIVE_PIPES,
+            ground_height: GROUND_HEIGHT,
+            world_height: WINDOW_SIZE.y,
+            bird_count: 1,
+            hitbox_scale: PLAYER_HITBOX_SCALE,
+            gate_hitbox_scale: PLAYER_GATE_HITBOX_SCALE,
+            gravity: GRAVITY,
+            gravity_curve: GravityCurve::Classic,
+            jump_mode: JumpMode::Reset,
+            glide_factor: None,
+            max_gap_delta: None,
+            jump_velocity: PLAYER_JUMP_VELOCITY,
+            idle_bob_amplitude: IDLE_BOB_AMPLITUDE,
+            idle_bob_frequency: IDLE_BOB_FREQUENCY,
+            lane_x: PLAYER_START_POSITION.x,
+        }
+    }
+}
+
+#[derive(Resource, Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CustomModeSettings {
+    pub pipe_spacing: f32,
+    pub flock_size: u32,
+    pub pattern: usize,
+    pub gap_distribution: GapDistribution,
+    // Points per second the score decay mode drains
+    pub score_decay_rate: f32,
+    // Practice starts with this pipe up next, 1 is the start of the course
+    pub practice_start_pipe: u32,
+}
+
+impl Default for CustomModeSettings {
+    fn default() -> Self {
+        Self {
+            pipe_spacing: BASE_PIPE_SPACING,
+            flock_size: 3,
+            pattern: 0,
+            gap_distribution: GapDistribution::Uniform,
+            score_decay_rate: 0.5,
+            practice_start_pipe: 1,
+        }
+    }
+}
+
+impl CustomModeSettings {
+    // The file can be edited by hand, the menu only ever steps inside these bounds
+    pub fn clamped(mut self) -> Self {
+        self.pipe_spacing = if self.pipe_spacing.is_finite() {
+            self.pipe_spacing.clamp(MIN_PIPE_SPACING, MAX_PIPE_SPACING)
+        } else {
+            BASE_PIPE_SPACING
+        };
+        self
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct TimeScale {
+    pub slow_motion: bool,
+    pub turbo: bool,
+}
+
+#[derive(Resource, Default, PartialEq)]
+pub struct HiDpi(pub bool);
+
+// How much of the world the window shows around the camera, a taller window sees more of it
+#[derive(Resource)]
+pub struct PlayArea {
+    pub visible_width: f32,
+    pub visible_height: f32,
+}
+
+impl Default for PlayArea {
+    fn default() -> Self {
+        Self {
+            visible_width: WINDOW_SIZE.x,
+            visible_height: WINDOW_SIZE.y,
+        }
+    }
+}
+
+#[derive(Resource)]
+pub struct BirdSkin(pub BirdSkinDef);
+
+impl Default for BirdSkin {
+    fn default() -> Self {
+        Self(CLASSIC_BIRD_SKIN)
+    }
+}
+
+// The first frame of the bird sheet, set when the sheet does not match the skin's grid
+#[derive(Resource, Default)]
+pub struct SkinFallback(pub Option<Rect>);
+
+#[derive(Resource, Default)]
+pub struct FirstPipeSeen(pub bool);
+
+#[derive(Resource, Default)]
+pub struct StepControl {
+    pub enabled: bool,
+    pub pending_steps: u32,
+    pub tick: u64,
+}
+
+#[derive(Resource, Deref, DerefMut)]
+pub struct GameRng(pub StdRng);
+
+impl GameRng {
+    pub fn from_seed(seed: u64) -> Self {
+        Self(StdRng::seed_from_u64(seed))
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct DebugFlags {
+    pub log_input: bool,
+    pub log_scoring: bool,
+    // F6 shows the load state of every asset, needs the debug feature
+    pub asset_status: bool,
+    // Outlines the colliders and the top of the ground
+    pub show_colliders: bool,
+}
+
+#[derive(Resource, Default)]
+pub struct SeedOverride(pub Option<u64>);
+
+#[derive(Resource)]
+pub struct BenchConfig {
+    pub duration: f32,
+}
+
+#[derive(Resource, Default)]
+pub struct BenchStats {
+    pub frames: u32,
+    pub runs: u32,
+    pub total_frame_time: Duration,
+    pub peak_entities: u32,
+}
+
+#[derive(Resource, Default)]
+pub struct CurrentRunSeed(pub u64);
+
+#[derive(Resource, Default)]
+pub struct RetrySameSeed(pub bool);
+
+#[derive(Resource, Default)]
+pub struct PipePreview(pub bool);
+
+// Hides all UI and gizmo overlays for trailers and screenshots, gameplay is untouched
+#[derive(Resource, Default)]
+pub struct CleanCapture(pub bool);
+
+// The pad the player last pressed a button on, if they are playing with one
+#[derive(Resource, Default)]
+pub struct ActiveGamepad(pub Option<Entity>);
+
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PauseReason {
+    #[default]
+    Menu,
+    ControllerDisconnected,
+}
+
+// Session only, `manually_unmuted` keeps quiet hours from muting again until the next start
+#[derive(Resource, Default)]
+pub struct AudioMute {
+    pub muted: bool,
+    pub by_quiet_hours: bool,
+    pub manually_unmuted: bool,
+}
+
+#[derive(Resource)]
+pub struct MusicIntensity {
+    pub level: f32,
+    pub target_level: f32,
+    pub fade: f32,
+    pub target_fade: f32,
+}
+
+impl Default for MusicIntensity {
+    fn default() -> Self {
+        Self {
+            level: 0.,
+            target_level: 0.,
+            fade: 0.,
+            target_fade: 1.,
+        }
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct RunTimer(pub Stopwatch);
+
+// Kept across sessions, so the game can tell a new player from an experienced one
+#[derive(Resource, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PlayerStats {
+    pub games_played: u32,
+    // Gates passed on the longest ranked run, the next gate is where that run ended
+    pub best_run_gates: u32,
+    pub perfect_runs: u32,
+}
+
+// Whether every gate so far this run met the PerfectCriteria
+#[derive(Resource)]
+pub struct PerfectRun {
+    pub clean: bool,
+    pub gates: u32,
+}
+
+impl Default for PerfectRun {
+    fn default() -> Self {
+        Self {
+            clean: true,
+            gates: 0,
+        }
+    }
+}
+
+// Where the current run stands against PlayerStats::best_run_gates
+#[derive(Resource, Default)]
+pub struct BestRunProgress {
+    pub gates_spawned: u32,
+    pub gates_passed: u32,
+    pub announced: bool,
+}
+
+// Gates spawned this run, every SHIELD_PICKUP_INTERVAL-th one carries a shield pickup
+#[derive(Resource, Default)]
+pub struct ShieldPickupCounter(pub u32);
+
+// Whether this run still has its forgiven crash, see Settings::tutorial_games
+#[derive(Resource, Default)]
+pub struct TutorialSave(pub bool);
+
+// Counts down the score attack clock, only ticks while the run is unpaused
+#[derive(Resource)]
+pub struct RoundTimer(pub Timer);
+
+// `drained` is the part of the next point already lost, the run can only end on decay once
+// a pipe was passed
+#[derive(Resource, Default)]
+pub struct ScoreDecay {
+    pub drained: f32,
+    pub has_scored: bool,
+}
+
+// Retry input is ignored until this runs out, see Settings::retry_cooldown
+#[derive(Resource, Default)]
+pub struct GameOverTimer(pub Timer);
+
+// Counts down an idle game over or get-ready screen while auto-restart is on. `autopilot` is
+// set while the autopilot flies the run, a flap hands it back to the player
+#[derive(Resource, Default)]
+pub struct KioskState {
+    pub countdown: Timer,
+    pub autopilot: bool,
+}
+
+// Esc on the game-over screen arms this, a second Esc before it runs out quits
+#[derive(Resource, Default)]
+pub struct QuitConfirm(pub Timer);
+
+// Gravity is held off while this is set, see Settings::start_grace
+#[derive(Resource, Default)]
+pub struct StartGrace(pub bool);
+
+#[derive(Resource, Default)]
+pub struct ReplayRecorder {
+    pub jumps: Vec<u32>,
+}
+
+#[derive(Resource, Default)]
+pub struct ActiveReplay(pub Option<Replay>);
+
+#[derive(Resource, Default)]
+pub struct RunCoins(pub u32);
+
+#[derive(Resource, Default)]
+pub struct PendingRunRecords(pub Vec<RunRecord>);
+
+#[derive(Resource)]
+pub struct PipePatterns(pub Vec<PipePattern>);
+
+#[derive(Resource, Clone, Default)]
+pub struct ActivePattern {
+    pub pattern: Option<PipePattern>,
+    pub next: usize,
+    pub finished: bool,
+    // Random gaps outside of a pattern follow this distribution
+    pub distribution: GapDistribution,
+    pub alternate_high: bool,
+}
+
+// Gap center of the previous pipe this run, None until the first pipe of a run has spawned
+#[derive(Resource, Clone, Copy, Default)]
+pub struct LastGapCenter(pub Option<f32>);
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HudPosition {
+    TopLeft,
+    #[default]
+    TopCenter,
+    TopRight,
+    BottomLeft,
+    BottomCenter,
+    BottomRight,
+}
+
+impl HudPosition {
+    pub fn next(self) -> Self {
+        match self {
+            HudPosition::TopLeft => HudPosition::TopCenter,
+            HudPosition::TopCenter => HudPosition::TopRight,
+            HudPosition::TopRight => HudPosition::BottomLeft,
+            HudPosition::BottomLeft => HudPosition::BottomCenter,
+            HudPosition::BottomCenter => HudPosition::BottomRight,
+            HudPosition::BottomRight => HudPosition::TopLeft,
+        }
+    }
+}
+
+// How random pipe gaps spread over the allowed opening range
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GapDistribution {
+    #[default]
+    Uniform,
+    CenterBiased,
+    Alternating,
+}
+
+impl GapDistribution {
+    pub fn next(self) -> Self {
+        match self {
+            GapDistribution::Uniform => GapDistribution::CenterBiased,
+            GapDistribution::CenterBiased => GapDistribution::Alternating,
+            GapDistribution::Alternating => GapDistribution::Uniform,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FlapButton {
+    #[default]
+    Left,
+    Right,
+    Middle,
+}
+
+impl FlapButton {
+    pub fn next(self) -> Self {
+        match self {
+            FlapButton::Left => FlapButton::Right,
+            FlapButton::Right => FlapButton::Middle,
+            FlapButton::Middle => FlapButton::Left,
+        }
+    }
+
+    pub fn mouse_button(self) -> MouseButton {
+        match self {
+            FlapButton::Left => MouseButton::Left,
+            FlapButton::Right => MouseButton::Right,
+            FlapButton::Middle => MouseButton::Middle,
+        }
+    }
+}
+
+// Menu buttons always answer to the left button, these only change what flaps the bird
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct InputBindings {
+    pub flap_mouse_button: FlapButton,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Feel {
+    Floaty,
+    #[default]
+    Classic,
+    Snappy,
+}
+
+impl Feel {
+    pub fn next(self) -> Self {
+        match self {
+            Feel::Floaty => Feel::Classic,
+            Feel::Classic => Feel::Snappy,
+            Feel::Snappy => Feel::Floaty,
+        }
+    }
+
+    // Gravity scales with the square of the factor so the jump height stays the same
+    pub fn factor(self) -> f32 {
+        match self {
+            Feel::Floaty => 0.8,
+            Feel::Classic => 1.,
+            Feel::Snappy => 1.25,
+        }
+    }
+
+    // Largest step between two gap centers with smooth gaps on, a quicker bird can follow more
+    pub fn max_gap_delta(self) -> f32 {
+        match self {
+            Feel::Floaty => 120.,
+            Feel::Classic => 160.,
+            Feel::Snappy => 200.,
+        }
+    }
+}
+
+// What a run has to do to count as perfect
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PerfectCriteria {
+    #[default]
+    Off,
+    // No crash was absorbed by a spare life, a clock penalty or a tutorial save
+    NoHits,
+    // As NoHits, and every gate was passed close to its middle
+    Centered,
+}
+
+impl PerfectCriteria {
+    pub fn next(self) -> Self {
+        match self {
+            PerfectCriteria::Off => PerfectCriteria::NoHits,
+            PerfectCriteria::NoHits => PerfectCriteria::Centered,
+            PerfectCriteria::Centered => PerfectCriteria::Off,
+        }
+    }
+}
+
+// How gravity builds up over the time since the last flap, as a factor on GameConfig::gravity
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GravityCurve {
+    #[default]
+    Classic,
+    // Hangs at the top of the arc a little longer before the fall picks up
+    SoftStart,
+    // Falls harder the longer the bird goes without a flap
+    Heavy,
+}
+
+impl GravityCurve {
+    pub fn next(self) -> Self {
+        match self {
+            GravityCurve::Classic => GravityCurve::SoftStart,
+            GravityCurve::SoftStart => GravityCurve::Heavy,
+            GravityCurve::Heavy => GravityCurve::Classic,
+        }
+    }
+
+    pub fn factor(self, airtime: f32) -> f32 {
+        match self {
+            GravityCurve::Classic => 1.,
+            GravityCurve::SoftStart => {
+                let progress = (airtime / 0.4).clamp(0., 1.);
+                0.5 + 0.5 * progress * progress * (3. - 2. * progress)
+            }
+            GravityCurve::Heavy => 1. + 0.5 * (airtime / 0.6).min(1.),
+        }
+    }
+}
+
+// What a flap does to the bird's current velocity
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum JumpMode {
+    // Every flap starts the same arc, whatever the bird was doing before
+    #[default]
+    Reset,
+    // A flap adds its lift to the current velocity, so a flap out of a steep fall only slows
+    // it and quick flaps stack up to a cap
+    Additive,
+}
+
+impl JumpMode {
+    pub fn next(self) -> Self {
+        match self {
+            JumpMode::Reset => JumpMode::Additive,
+            JumpMode::Additive => JumpMode::Reset,
+        }
+    }
+
+    pub fn jump(self, velocity: f32, jump_velocity: f32) -> f32 {
+        match self {
+            JumpMode::Reset => jump_velocity,
+            JumpMode::Additive => (velocity + jump_velocity).min(jump_velocity * MAX_JUMP_STACK),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AssistLevel {
+    #[default]
+    None,
+    Light,
+    Strong,
+}
+
+impl AssistLevel {
+    pub fn next(self) -> Self {
+        match self {
+            AssistLevel::None => AssistLevel::Light,
+            AssistLevel::Light => AssistLevel::Strong,
+            AssistLevel::Strong => AssistLevel::None,
+        }
+    }
+
+    pub fn pipe_width_scale(self) -> f32 {
+        match self {
+            AssistLevel::None => 1.,
+            AssistLevel::Light => 0.9,
+            AssistLevel::Strong => 0.8,
+        }
+    }
+
+    // Taken off the gap-facing end of both pipes, so the lethal gap grows by twice this
+    pub fn gap_inset(self) -> f32 {
+        match self {
+            AssistLevel::None => 0.,
+            AssistLevel::Light => 10.,
+            AssistLevel::Strong => 20.,
+        }
+    }
+
+    pub fn gate_width_scale(self) -> f32 {
+        match self {
+            AssistLevel::None => 1.,
+            AssistLevel::Light => 2.,
+            AssistLevel::Strong => 3.,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Theme {
+    #[default]
+    Day,
+    Night,
+}
+
+impl Theme {
+    pub fn next(self) -> Self {
+        match self {
+            Theme::Day => Theme::Night,
+            Theme::Night => Theme::Day,
+        }
+    }
+
+    pub fn pipe_caps(self) -> bool {
+        match self {
+            Theme::Day => true,
+            Theme::Night => false,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Antialiasing {
+    Off,
+    X2,
+    #[default]
+    X4,
+}
+
+impl Antialiasing {
+    pub fn next(self) -> Self {
+        match self {
+            Antialiasing::Off => Antialiasing::X2,
+            Antialiasing::X2 => Antialiasing::X4,
+            Antialiasing::X4 => Antialiasing::Off,
+        }
+    }
+}
+
+#[derive(Resource, Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub hud_scale: f32,
+    pub hud_position: HudPosition,
+    pub instant_quit: bool,
+    pub pause_on_controller_disconnect: bool,
+    pub starting_score: i32,
+    // Scores below this never make the high score table
+    pub min_saved_score: i32,
+    pub lives: u32,
+    pub assist_guide: bool,
+    // Draws where the bird is headed over the next second
+    pub trajectory_guide: bool,
+    pub assist_level: AssistLevel,
+    // Seconds of predicted fall the rescue flap looks ahead, 0 leaves it off
+    pub rescue_lookahead: f32,
+    // The first lethal crash of a run is forgiven until this many games have been played
+    pub tutorial_games: u32,
+    pub reduced_motion: bool,
+    pub show_seed: bool,
+    pub rumble_strength: f32,
+    // How far a trigger or stick has to travel before it flaps
+    pub gamepad_deadzone: f32,
+    pub music_volume: f32,
+    // Opt-in automatic mute between these local hours, the end hour is not included
+    pub quiet_hours: bool,
+    pub quiet_hours_start: u32,
+    pub quiet_hours_end: u32,
+    pub input_bindings: InputBindings,
+    pub auto_flap: bool,
+    // Seconds the bird hovers after the run starts, 0 for immediate gravity
+    pub start_grace: f32,
+    pub retry_cooldown: f32,
+    // For unattended kiosks, game over restarts on its own and an idle start is flown by the
+    // autopilot
+    pub auto_restart: bool,
+    pub auto_restart_delay: f32,
+    // Scales the bird sprite and its hitbox together
+    pub bird_scale: f32,
+    pub theme: Theme,
+    pub vignette: bool,
+    pub vignette_strength: f32,
+    // Lower pipes are drawn slightly darker than higher ones
+    pub pipe_depth_tint: bool,
+    pub preferred_monitor: Option<String>,
+    pub turbo_speed: f32,
+    pub pixel_snap: bool,
+    pub antialiasing: Antialiasing,
+    pub feel: Feel,
+    pub gravity_curve: GravityCurve,
+    pub jump_mode: JumpMode,
+    pub glide: bool,
+    pub perfect_run: PerfectCriteria,
+    pub smooth_gaps: bool,
+    pub shield_pickups: bool,
+    pub log_runs: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            hud_scale: 1.5,
+            hud_position: HudPosition::TopCenter,
+            instant_quit: false,
+            pause_on_controller_disconnect: true,
+            starting_score: 0,
+            min_saved_score: 0,
+            lives: 1,
+            assist_guide: false,
+            trajectory_guide: false,
+            assist_level: AssistLevel::None,
+            rescue_lookahead: 0.,
+            tutorial_games: 3,
+            reduced_motion: false,
+            show_seed: false,
+            rumble_strength: 1.,
+            gamepad_deadzone: 0.5,
+            music_volume: 0.5,
+            quiet_hours: false,
+            quiet_hours_start: 22,
+            quiet_hours_end: 7,
+            input_bindings: InputBindings::default(),
+            auto_flap: false,
+            start_grace: 0.,
+            retry_cooldown: 0.5,
+            auto_restart: false,
+            auto_restart_delay: 5.,
+            bird_scale: 1.,
+            theme: Theme::Day,
+            pipe_depth_tint: false,
+            vignette: false,
+            vignette_strength: 0.35,
+            preferred_monitor: None,
+            turbo_speed: MIN_TURBO_SPEED,
+            pixel_snap: false,
+            antialiasing: Antialiasing::X4,
+            feel: Feel::Classic,
+            gravity_curve: GravityCurve::Classic,
+            jump_mode: JumpMode::Reset,
+            glide: false,
+            perfect_run: PerfectCriteria::Off,
+            smooth_gaps: false,
+            shield_pickups: false,
+            log_runs: false,
+        }
+    }
+}
+
+impl Settings {
+    // The menu only steps these values inside their ranges, a hand edited file is brought back
+    // into them
+    pub fn clamped(mut self) -> Self {
+        let default = Self::default();
+        self.start_grace = clamp_or(self.start_grace, 0., MAX_START_GRACE, default.start_grace);
+        // A NaN speed would panic in Time::set_relative_speed
+        self.turbo_speed = clamp_or(
+            self.turbo_speed,
+            MIN_TURBO_SPEED,
+            MAX_TURBO_SPEED,
+            default.turbo_speed,
+        );
+        // A negative cooldown would panic in Timer::from_seconds
+        self.retry_cooldown = clamp_or(
+            self.retry_cooldown,
+            0.,
+            MAX_RETRY_COOLDOWN,
+            default.retry_cooldown,
+        );
+        self.auto_restart_delay = clamp_or(
+            self.auto_restart_delay,
+            MIN_AUTO_RESTART_DELAY,
+            MAX_AUTO_RESTART_DELAY,
+            default.auto_restart_delay,
+        );
+        self.bird_scale = clamp_or(
+            self.bird_scale,
+            MIN_BIRD_SCALE,
+            MAX_BIRD_SCALE,
+            default.bird_scale,
+        );
+        self.hud_scale = clamp_or(
+            self.hud_scale,
+            MIN_HUD_SCALE,
+            MAX_HUD_SCALE,
+            default.hud_scale,
+        );
+        self
+    }
+}
+
+// Values that are not finite cannot be clamped and use the fallback instead
+fn clamp_or(value: f32, min: f32, max: f32, fallback: f32) -> f32 {
+    if value.is_finite() {
+        value.clamp(min, max)
+    } else {
+        fallback
+    }
+}
+
+pub struct WorldSnapshot {
+    pub players: Vec<(Entity, Vec3, f32)>,
+    pub pipes: Vec<(Entity, Vec3)>,
+    pub pipe_spawn_elapsed: Duration,
+}
+
+#[derive(Resource, Default)]
+pub struct RewindBuffer {
+    pub snapshots: VecDeque<WorldSnapshot>,
+}
+
+// The last ticks before a crash, `playhead` is a fractional tick index for slow playback
+#[derive(Resource, Default)]
+pub struct DeathReplayBuffer {
+    pub snapshots: VecDeque<WorldSnapshot>,
+    pub playhead: f32,
+}
+
+#[derive(Resource, Default)]
+pub struct AltitudeHistory {
+    pub samples: VecDeque<f32>,
+    pub total_samples: usize,
+    pub score_marks: Vec<usize>,
+}
+
+impl AltitudeHistory {
+    pub fn clear(&mut self) {
+        self.samples.clear();
+        self.total_samples = 0;
+        self.score_marks.clear();
+    }
+
+    pub fn first_sample(&self) -> usize {
+        self.total_samples - self.samples.len()
+    }
+}
+
+#[derive(Resource, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AdaptiveState {
+    pub level: f32,
+    pub recent_durations: VecDeque<f32>,
+}
+
+impl AdaptiveState {
+    // A hand edited level past the range could shrink the pipe gap to nothing
+    pub fn clamped(mut self) -> Self {
+        self.level = clamp_or(self.level, -MAX_ADAPTIVE_LEVEL, MAX_ADAPTIVE_LEVEL, 0.);
+        self
+    }
+}
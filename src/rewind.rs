@@ -0,0 +1,107 @@
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+
+use crate::{
+    components::{Dead, LivingBird, Pipe, Player, Velocity},
+    constants::*,
+    events::RewindEvent,
+    resources::{GameMode, PipeSpawnTimer, RewindBuffer, WorldSnapshot},
+    step::physics_running,
+    AppState, PauseState,
+};
+
+pub fn rewind_plugin(app: &mut App) {
+    app.init_resource::<RewindBuffer>()
+        .add_systems(OnEnter(AppState::InGame), clear_rewind_buffer)
+        .add_systems(
+            FixedUpdate,
+            record_snapshot.run_if(
+                in_state(PauseState::Running)
+                    .and(physics_running)
+                    .and(resource_equals(GameMode::Practice)),
+            ),
+        )
+        .add_systems(
+            Update,
+            rewind_input
+                .run_if(in_state(PauseState::Running).and(resource_equals(GameMode::Practice))),
+        )
+        .add_observer(rewind);
+}
+
+fn clear_rewind_buffer(mut buffer: ResMut<RewindBuffer>) {
+    buffer.snapshots.clear();
+}
+
+fn record_snapshot(
+    mut buffer: ResMut<RewindBuffer>,
+    spawn_timer: Res<PipeSpawnTimer>,
+    player_query: Query<(Entity, &Transform, &Velocity), LivingBird>,
+    pipe_query: Query<(Entity, &Transform), With<Pipe>>,
+) {
+    if buffer.snapshots.len() == REWIND_BUFFER_SIZE {
+        buffer.snapshots.pop_front();
+    }
+
+    buffer.snapshots.push_back(WorldSnapshot {
+        players: player_query
+            .iter()
+            .map(|(entity, transform, velocity)| (entity, transform.translation, velocity.0))
+            .collect(),
+        pipes: pipe_query
+            .iter()
+            .map(|(entity, transform)| (entity, transform.translation))
+            .collect(),
+        pipe_spawn_elapsed: spawn_timer.0.elapsed(),
+    });
+}
+
+fn rewind_input(key_input: Res<ButtonInput<KeyCode>>, mut commands: Commands) {
+    if key_input.just_pressed(KeyCode::Backspace) {
+        commands.trigger(RewindEvent);
+    }
+}
+
+// Goes back up to REWIND_TICKS fixed ticks and forgets everything recorded after that point
+#[allow(
+    clippy::type_complexity,
+    reason = "the pipe query has to rule out birds to borrow Transform mutably"
+)]
+fn rewind(
+    _rewind_event: On<RewindEvent>,
+    mut commands: Commands,
+    mut buffer: ResMut<RewindBuffer>,
+    mut spawn_timer: ResMut<PipeSpawnTimer>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut player_query: Query<(&mut Transform, &mut Velocity, Has<Dead>), With<Player>>,
+    mut pipe_query: Query<(Entity, &mut Transform), (With<Pipe>, Without<Player>)>,
+) {
+    let target_len = buffer.snapshots.len().saturating_sub(REWIND_TICKS).max(1);
+    buffer.snapshots.truncate(target_len);
+    let Some(snapshot) = buffer.snapshots.back() else {
+        if player_query.iter().all(|(_, _, is_dead)| is_dead) {
+            next_state.set(AppState::GameOver);
+        }
+        return;
+    };
+
+    for &(entity, translation, velocity) in &snapshot.players {
+        let Ok((mut transform, mut player_velocity, _)) = player_query.get_mut(entity) else {
+            continue;
+        };
+        transform.translation = translation;
+        player_velocity.0 = velocity;
+        commands.entity(entity).remove::<Dead>();
+    }
+
+    let pipe_positions: HashMap<Entity, Vec3> = snapshot.pipes.iter().copied().collect();
+    for (entity, mut transform) in pipe_query.iter_mut() {
+        match pipe_positions.get(&entity) {
+            Some(&translation) => transform.translation = translation,
+            None => commands.entity(entity).despawn(),
+        }
+    }
+
+    spawn_timer.0.set_elapsed(snapshot.pipe_spawn_elapsed);
+}
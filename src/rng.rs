@@ -0,0 +1,66 @@
+use bevy::prelude::*;
+
+use crate::{
+    resources::{CurrentRunSeed, GameRng, RetrySameSeed, SeedOverride},
+    AppState,
+};
+
+pub fn rng_plugin(app: &mut App) {
+    app.insert_resource(GameRng::from_seed(rand::random()))
+        .init_resource::<CurrentRunSeed>()
+        .init_resource::<SeedOverride>()
+        .init_resource::<RetrySameSeed>()
+        .add_systems(OnEnter(AppState::GameStart), reseed_rng);
+}
+
+// Every run starts from its own seed, so nothing one run rolled carries over into the next.
+// Retrying the same seed and a fixed override, as the benchmark uses, are the only repeats
+fn reseed_rng(
+    seed_override: Res<SeedOverride>,
+    retry_same_seed: Res<RetrySameSeed>,
+    mut rng: ResMut<GameRng>,
+    mut current_seed: ResMut<CurrentRunSeed>,
+) {
+    let seed = if retry_same_seed.0 {
+        current_seed.0
+    } else {
+        seed_override.0.unwrap_or_else(rand::random)
+    };
+    current_seed.0 = seed;
+    *rng = GameRng::from_seed(seed);
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::RunSystemOnce;
+    use rand::Rng;
+
+    use super::*;
+
+    fn start_run(world: &mut World) -> Vec<u64> {
+        world.run_system_once(reseed_rng).unwrap();
+        let mut rng = world.resource_mut::<GameRng>();
+        (0..5).map(|_| rng.gen()).collect()
+    }
+
+    #[test]
+    fn runs_only_repeat_with_a_fixed_seed() {
+        let mut world = World::new();
+        world.insert_resource(GameRng::from_seed(0));
+        world.init_resource::<CurrentRunSeed>();
+        world.init_resource::<SeedOverride>();
+        world.init_resource::<RetrySameSeed>();
+
+        let first = start_run(&mut world);
+        assert_ne!(start_run(&mut world), first);
+
+        world.insert_resource(RetrySameSeed(true));
+        let retried = start_run(&mut world);
+        assert_eq!(start_run(&mut world), retried);
+
+        world.insert_resource(RetrySameSeed(false));
+        world.insert_resource(SeedOverride(Some(42)));
+        let seeded = start_run(&mut world);
+        assert_eq!(start_run(&mut world), seeded);
+    }
+}
@@ -0,0 +1,61 @@
+use std::time::Duration;
+
+use bevy::{
+    input::gamepad::{GamepadRumbleIntensity, GamepadRumbleRequest},
+    prelude::*,
+};
+
+use crate::{components::Dead, constants::*, events::IncrementScoreEvent, resources::Settings};
+
+pub fn rumble_plugin(app: &mut App) {
+    app.add_observer(rumble_on_death)
+        .add_observer(rumble_on_score);
+}
+
+fn rumble_on_death(
+    _dead: On<Add, Dead>,
+    settings: Res<Settings>,
+    gamepads: Query<Entity, With<Gamepad>>,
+    rumble_requests: MessageWriter<GamepadRumbleRequest>,
+) {
+    rumble(
+        GamepadRumbleIntensity::strong_motor(settings.rumble_strength),
+        DEATH_RUMBLE_DURATION,
+        gamepads,
+        rumble_requests,
+    );
+}
+
+fn rumble_on_score(
+    _increment_event: On<IncrementScoreEvent>,
+    settings: Res<Settings>,
+    gamepads: Query<Entity, With<Gamepad>>,
+    rumble_requests: MessageWriter<GamepadRumbleRequest>,
+) {
+    rumble(
+        GamepadRumbleIntensity::weak_motor(settings.rumble_strength * SCORE_RUMBLE_STRENGTH),
+        SCORE_RUMBLE_DURATION,
+        gamepads,
+        rumble_requests,
+    );
+}
+
+// Pads without rumble support simply ignore the request
+fn rumble(
+    intensity: GamepadRumbleIntensity,
+    duration_secs: f32,
+    gamepads: Query<Entity, With<Gamepad>>,
+    mut rumble_requests: MessageWriter<GamepadRumbleRequest>,
+) {
+    if intensity.strong_motor <= 0. && intensity.weak_motor <= 0. {
+        return;
+    }
+
+    for gamepad in gamepads.iter() {
+        rumble_requests.write(GamepadRumbleRequest::Add {
+            gamepad,
+            intensity,
+            duration: Duration::from_secs_f32(duration_secs),
+        });
+    }
+}
@@ -0,0 +1,132 @@
+use bevy::prelude::*;
+use serde::Serialize;
+
+use crate::{
+    events::{GoldenPipeScoredEvent, ResetScoreEvent},
+    resources::{GameMode, PendingRunRecords, RunCoins, RunTimer, Score, Settings},
+    AppState,
+};
+
+const RUN_LOG_FILE: &str = "runs.csv";
+
+// Field order is the column order, only ever append new columns at the end
+#[derive(Clone, Debug, Serialize)]
+pub struct RunRecord {
+    pub timestamp: u64,
+    pub mode: String,
+    pub difficulty: String,
+    pub score: i32,
+    pub duration: f32,
+    pub coins: u32,
+}
+
+pub fn run_log_plugin(app: &mut App) {
+    app.init_resource::<RunCoins>()
+        .init_resource::<PendingRunRecords>()
+        .add_observer(count_coin)
+        .add_observer(reset_run_coins)
+        .add_systems(OnEnter(AppState::GameOver), log_run);
+}
+
+fn reset_run_coins(_reset_event: On<ResetScoreEvent>, mut coins: ResMut<RunCoins>) {
+    coins.0 = 0;
+}
+
+fn count_coin(_scored_event: On<GoldenPipeScoredEvent>, mut coins: ResMut<RunCoins>) {
+    coins.0 += 1;
+}
+
+fn log_run(
+    settings: Res<Settings>,
+    mode: Res<GameMode>,
+    score: Res<Score>,
+    run_timer: Res<RunTimer>,
+    coins: Res<RunCoins>,
+    mut pending: ResMut<PendingRunRecords>,
+) {
+    if !settings.log_runs {
+        return;
+    }
+
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs());
+
+    pending.0.push(RunRecord {
+        timestamp,
+        mode: format!("{:?}", *mode),
+        difficulty: format!("{:?}", settings.feel),
+        score: score.0,
+        duration: run_timer.0.elapsed_secs(),
+        coins: coins.0,
+    });
+
+    // A spreadsheet holding the file open can lock it, so rows wait for the next run instead of being lost
+    match append_records(&pending.0) {
+        Ok(()) => pending.0.clear(),
+        Err(error) => warn!(
+            "Failed to write {RUN_LOG_FILE}, keeping {} run(s) for later: {error}",
+            pending.0.len()
+        ),
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn append_records(records: &[RunRecord]) -> Result<(), String> {
+    let path = crate::persistence::file_path(RUN_LOG_FILE);
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|error| error.to_string())?;
+    }
+
+    let file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .map_err(|error| error.to_string())?;
+    let is_empty = file.metadata().map_or(true, |metadata| metadata.len() == 0);
+
+    let mut writer = csv::WriterBuilder::new()
+        .has_headers(is_empty)
+        .from_writer(file);
+    for record in records {
+        writer
+            .serialize(record)
+            .map_err(|error| error.to_string())?;
+    }
+    writer.flush().map_err(|error| error.to_string())
+}
+
+#[cfg(target_arch = "wasm32")]
+fn append_records(_records: &[RunRecord]) -> Result<(), String> {
+    Err("run logging is not available in the browser".into())
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn open_run_log_location() {
+    let path = crate::persistence::file_path(RUN_LOG_FILE);
+    let Some(dir) = path.parent() else {
+        return;
+    };
+
+    if let Err(error) = std::fs::create_dir_all(dir) {
+        warn!("Failed to create {}: {error}", dir.display());
+        return;
+    }
+
+    let opener = if cfg!(target_os = "windows") {
+        "explorer"
+    } else if cfg!(target_os = "macos") {
+        "open"
+    } else {
+        "xdg-open"
+    };
+
+    if let Err(error) = std::process::Command::new(opener).arg(dir).spawn() {
+        warn!("Failed to open {}: {error}", dir.display());
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn open_run_log_location() {
+    warn!("Run logs are not available in the browser");
+}
@@ -0,0 +1,60 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use crate::{
+    constants::SCORE_ATTACK_DURATION,
+    events::TimePenaltyEvent,
+    resources::{GameMode, HudState, RoundTimer},
+    step::physics_running,
+    AppState, PauseState,
+};
+
+pub fn score_attack_plugin(app: &mut App) {
+    app.insert_resource(RoundTimer(Timer::from_seconds(
+        SCORE_ATTACK_DURATION,
+        TimerMode::Once,
+    )))
+    .add_systems(OnEnter(AppState::GameStart), reset_round_timer)
+    .add_systems(
+        FixedUpdate,
+        tick_round_timer.run_if(
+            in_state(PauseState::Running)
+                .and(physics_running)
+                .and(resource_equals(GameMode::ScoreAttack)),
+        ),
+    )
+    .add_systems(
+        Update,
+        update_round_clock
+            .run_if(resource_equals(GameMode::ScoreAttack).and(resource_changed::<RoundTimer>)),
+    )
+    .add_observer(apply_time_penalty);
+}
+
+fn reset_round_timer(mut round_timer: ResMut<RoundTimer>) {
+    round_timer.0.reset();
+}
+
+// The clock starts with the first flap, the GameStart hover does not count
+fn tick_round_timer(
+    time: Res<Time>,
+    mut round_timer: ResMut<RoundTimer>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    if round_timer.0.tick(time.delta()).is_finished() {
+        next_state.set(AppState::GameOver);
+    }
+}
+
+// A penalty past the end of the clock finishes the timer, the next tick ends the run
+fn apply_time_penalty(penalty: On<TimePenaltyEvent>, mut round_timer: ResMut<RoundTimer>) {
+    round_timer.0.tick(Duration::from_secs_f32(penalty.seconds));
+}
+
+fn update_round_clock(round_timer: Res<RoundTimer>, mut hud_state: ResMut<HudState>) {
+    let clock_seconds = round_timer.0.remaining_secs().ceil() as u32;
+    if hud_state.clock_seconds != clock_seconds {
+        hud_state.clock_seconds = clock_seconds;
+    }
+}
@@ -0,0 +1,96 @@
+use bevy::prelude::*;
+
+use crate::{
+    events::{IncrementScoreEvent, ScoreChangedEvent},
+    resources::{CustomModeSettings, GameMode, HudState, Score, ScoreDecay},
+    step::physics_running,
+    AppState, PauseState,
+};
+
+pub fn score_decay_plugin(app: &mut App) {
+    app.init_resource::<ScoreDecay>()
+        .add_systems(OnEnter(AppState::GameStart), reset_score_decay)
+        .add_systems(
+            FixedUpdate,
+            decay_score.run_if(
+                in_state(PauseState::Running)
+                    .and(physics_running)
+                    .and(resource_equals(GameMode::ScoreDecay)),
+            ),
+        )
+        .add_systems(
+            Update,
+            update_decay_bar
+                .run_if(resource_equals(GameMode::ScoreDecay).and(resource_changed::<ScoreDecay>)),
+        )
+        .add_observer(refill_score_decay);
+}
+
+// Drains `amount` more of a point from the score, returns the new score and how much of the
+// next point is already gone. An empty score stays at 0 and stops draining
+pub fn drain_score(score: i32, drained: f32, amount: f32) -> (i32, f32) {
+    let drained = drained + amount;
+    let whole_points = drained.floor() as i32;
+    let new_score = (score - whole_points).max(0);
+    if new_score == 0 {
+        (0, 0.)
+    } else {
+        (new_score, drained.fract())
+    }
+}
+
+fn reset_score_decay(mut decay: ResMut<ScoreDecay>) {
+    *decay = ScoreDecay::default();
+}
+
+fn decay_score(
+    time: Res<Time>,
+    custom_mode: Res<CustomModeSettings>,
+    mut decay: ResMut<ScoreDecay>,
+    mut score: ResMut<Score>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut commands: Commands,
+) {
+    if score.0 <= 0 {
+        return;
+    }
+
+    let (new_score, drained) = drain_score(
+        score.0,
+        decay.drained,
+        custom_mode.score_decay_rate * time.delta_secs(),
+    );
+    decay.drained = drained;
+    if new_score == score.0 {
+        return;
+    }
+
+    score.0 = new_score;
+    commands.trigger(ScoreChangedEvent);
+    if new_score == 0 && decay.has_scored {
+        next_state.set(AppState::GameOver);
+    }
+}
+
+// Passing a pipe also starts the next point from full
+fn refill_score_decay(_increment_event: On<IncrementScoreEvent>, mut decay: ResMut<ScoreDecay>) {
+    decay.has_scored = true;
+    decay.drained = 0.;
+}
+
+fn update_decay_bar(decay: Res<ScoreDecay>, mut hud_state: ResMut<HudState>) {
+    hud_state.decay_remaining = 1. - decay.drained;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_score_drains_point_by_point_down_to_zero() {
+        assert_eq!(drain_score(3, 0.25, 0.5), (3, 0.75));
+        assert_eq!(drain_score(3, 0.75, 0.5), (2, 0.25));
+        assert_eq!(drain_score(3, 0.5, 2.5), (0, 0.));
+        assert_eq!(drain_score(1, 0.5, 10.), (0, 0.));
+    }
+}
@@ -0,0 +1,95 @@
+use bevy::prelude::*;
+
+use crate::{
+    components::{Collider, GateFlash, LivingBird, MissedGate, PointGate},
+    constants::*,
+    events::PointGateReachedEvent,
+    layers::ZLayers,
+    resources::{DebugFlags, GameConfig},
+    AppState,
+};
+
+pub fn scoring_debug_plugin(app: &mut App) {
+    app.add_systems(Update, fade_gate_flashes)
+        // Last, so gates scored in detect_collision are already gone
+        .add_systems(
+            Last,
+            report_missed_gates.run_if(in_state(AppState::InGame).and(log_scoring_enabled)),
+        )
+        .add_observer(log_point_gate);
+}
+
+fn log_scoring_enabled(flags: Res<DebugFlags>) -> bool {
+    flags.log_scoring
+}
+
+fn log_point_gate(
+    gate_event: On<PointGateReachedEvent>,
+    flags: Res<DebugFlags>,
+    layers: Res<ZLayers>,
+    mut commands: Commands,
+) {
+    if !flags.log_scoring {
+        return;
+    }
+
+    info!(
+        "point gate reached: player x {:.1}, gate x {:.1}, side {:?}",
+        gate_event.player_x, gate_event.gate_x, gate_event.side
+    );
+
+    // The gate itself despawns once it scores, so the flash hangs off its pipe instead
+    let Some(pipe) = gate_event.pipe else {
+        return;
+    };
+    commands.entity(pipe).with_child((
+        GateFlash(Timer::from_seconds(GATE_FLASH_DURATION, TimerMode::Once)),
+        Sprite::from_color(Color::WHITE, gate_event.gate_size),
+        Transform::from_xyz(0., 0., layers.particle - layers.pipe),
+    ));
+}
+
+fn fade_gate_flashes(
+    time: Res<Time>,
+    mut commands: Commands,
+    mut query: Query<(Entity, &mut GateFlash, &mut Sprite)>,
+) {
+    for (entity, mut flash, mut sprite) in query.iter_mut() {
+        if flash.0.tick(time.delta()).just_finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        sprite.color.set_alpha(1. - flash.0.fraction());
+    }
+}
+
+// A gate that is fully behind a living bird but never scored was skipped between two checks
+#[allow(
+    clippy::type_complexity,
+    reason = "gates that were already reported are filtered out"
+)]
+fn report_missed_gates(
+    mut commands: Commands,
+    config: Res<GameConfig>,
+    player_query: Query<(&GlobalTransform, &Collider), LivingBird>,
+    gate_query: Query<
+        (Entity, &GlobalTransform, &Collider),
+        (With<PointGate>, Without<MissedGate>),
+    >,
+) {
+    for (gate, gate_transform, gate_collider) in gate_query.iter() {
+        let gate_x = gate_transform.translation().x;
+        let gate_right = gate_x + gate_collider.size.x / 2.;
+        for (player_transform, player_collider) in player_query.iter() {
+            let player_x = player_transform.translation().x;
+            let player_width =
+                player_collider.size.x * config.gate_hitbox_scale * player_transform.scale().x;
+            let player_left = player_x - player_width / 2.;
+            if gate_right < player_left {
+                warn!("point gate missed: player x {player_x:.1}, gate x {gate_x:.1}");
+                commands.entity(gate).insert(MissedGate);
+                break;
+            }
+        }
+    }
+}
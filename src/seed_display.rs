@@ -0,0 +1,66 @@
+use bevy::prelude::*;
+
+use crate::{
+    clipboard,
+    components::SeedText,
+    resources::{CurrentRunSeed, Settings},
+    AppState,
+};
+
+pub fn seed_display_plugin(app: &mut App) {
+    app.add_systems(
+        OnEnter(AppState::InGame),
+        spawn_seed_text.run_if(seed_display_enabled),
+    )
+    .add_systems(
+        Update,
+        copy_seed.run_if(in_state(AppState::InGame).and(seed_display_enabled)),
+    );
+}
+
+fn seed_display_enabled(settings: Res<Settings>) -> bool {
+    settings.show_seed
+}
+
+fn seed_label(seed: u64) -> String {
+    format!("Seed {seed:016X} (S to copy)")
+}
+
+fn spawn_seed_text(mut commands: Commands, current_seed: Res<CurrentRunSeed>) {
+    commands.spawn((
+        SeedText,
+        Text::new(seed_label(current_seed.0)),
+        TextFont {
+            font_size: 16.0,
+            ..default()
+        },
+        TextColor(Color::WHITE.with_alpha(0.7)),
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: px(5),
+            right: px(5),
+            ..default()
+        },
+        DespawnOnExit(AppState::InGame),
+    ));
+}
+
+fn copy_seed(
+    key_input: Res<ButtonInput<KeyCode>>,
+    current_seed: Res<CurrentRunSeed>,
+    mut text: Single<&mut Text, With<SeedText>>,
+) {
+    if !key_input.just_pressed(KeyCode::KeyS) {
+        return;
+    }
+
+    let seed = format!("{:016X}", current_seed.0);
+    info!("Seed: {seed}");
+    text.0 = match clipboard::set_text(seed) {
+        Ok(()) => format!("Seed {:016X} copied", current_seed.0),
+        Err(error) => {
+            warn!("Failed to copy seed: {error}");
+            format!("Could not copy seed: {error}")
+        }
+    };
+}
@@ -0,0 +1,17 @@
+use bevy::prelude::*;
+
+use crate::{persistence, resources::Settings};
+
+const SETTINGS_FILE: &str = "settings.ron";
+
+pub fn settings_plugin(app: &mut App) {
+    app.insert_resource(persistence::load::<Settings>(SETTINGS_FILE).clamped())
+        .add_systems(
+            Update,
+            save_settings.run_if(resource_changed::<Settings>.and(not(resource_added::<Settings>))),
+        );
+}
+
+fn save_settings(settings: Res<Settings>) {
+    persistence::save(SETTINGS_FILE, &*settings);
+}
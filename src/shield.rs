@@ -0,0 +1,205 @@
+use bevy::{color::palettes::css::LIGHT_SKY_BLUE, prelude::*};
+
+use crate::{
+    components::{
+        Collider, LivingBird, Player, PointGate, Shield, ShieldBubble, ShieldFlash, ShieldPickup,
+    },
+    constants::*,
+    events::ShieldBrokenEvent,
+    layers::ZLayers,
+    particles::spawn_particle_burst,
+    resources::{Settings, ShieldPickupCounter},
+    AppState, PauseState,
+};
+
+pub fn shield_plugin(app: &mut App) {
+    app.init_resource::<ShieldPickupCounter>()
+        .add_systems(OnEnter(AppState::GameStart), reset_shield_pickups)
+        .add_systems(
+            Update,
+            collect_shield_pickups.run_if(in_state(PauseState::Running)),
+        )
+        .add_systems(Update, fade_shield_flash)
+        .add_observer(place_shield_pickup)
+        .add_observer(show_shield_bubble)
+        .add_observer(hide_shield_bubble)
+        .add_observer(break_shield);
+}
+
+// Counted over every gate of the run, while a bird still has its shield the pickup is skipped
+pub fn carries_pickup(gate_index: u32) -> bool {
+    (gate_index + 1).is_multiple_of(SHIELD_PICKUP_INTERVAL)
+}
+
+fn reset_shield_pickups(mut counter: ResMut<ShieldPickupCounter>) {
+    counter.0 = 0;
+}
+
+// The pickup hangs in the gap on the pipe itself, the gate is despawned the moment it scores
+#[allow(
+    clippy::too_many_arguments,
+    reason = "whether a gate carries a pickup depends on the run, the settings and the counter"
+)]
+fn place_shield_pickup(
+    add: On<Add, PointGate>,
+    state: Res<State<AppState>>,
+    settings: Res<Settings>,
+    asset_server: Res<AssetServer>,
+    mut counter: ResMut<ShieldPickupCounter>,
+    gate_query: Query<(&Transform, &ChildOf)>,
+    shield_query: Query<(), (With<Player>, With<Shield>)>,
+    mut commands: Commands,
+) {
+    // The menu demo spawns pipes too
+    if *state.get() != AppState::InGame || !settings.shield_pickups {
+        return;
+    }
+
+    let index = counter.0;
+    counter.0 += 1;
+    if !carries_pickup(index) || !shield_query.is_empty() {
+        return;
+    }
+
+    let Ok((gate_transform, child_of)) = gate_query.get(add.entity) else {
+        return;
+    };
+    commands.entity(child_of.parent()).with_children(|parent| {
+        parent.spawn((
+            ShieldPickup,
+            Sprite {
+                image: asset_server.load(SHIELD_IMAGE),
+                custom_size: Some(Vec2::splat(SHIELD_PICKUP_SIZE)),
+                ..default()
+            },
+            Transform::from_translation(gate_transform.translation.with_z(0.2)),
+        ));
+    });
+}
+
+fn collect_shield_pickups(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    pickup_query: Query<(Entity, &GlobalTransform), With<ShieldPickup>>,
+    player_query: Query<(Entity, &GlobalTransform, &Collider), (LivingBird, Without<Shield>)>,
+) {
+    for (pickup, pickup_transform) in pickup_query.iter() {
+        let pickup_center = pickup_transform.translation().truncate();
+        let collector = player_query.iter().find(|(_, player_transform, collider)| {
+            let player_size = collider.size * player_transform.scale().truncate();
+            let reach = SHIELD_PICKUP_SIZE / 2. + player_size.min_element() / 2.;
+            player_transform
+                .translation()
+                .truncate()
+                .distance(pickup_center)
+                < reach
+        });
+        let Some((player, ..)) = collector else {
+            continue;
+        };
+
+        commands.entity(pickup).despawn();
+        commands.entity(player).insert(Shield);
+        commands.spawn((
+            AudioPlayer::new(asset_server.load(SHIELD_PICKUP_SOUND)),
+            PlaybackSettings::DESPAWN,
+        ));
+    }
+}
+
+fn show_shield_bubble(
+    add: On<Add, Shield>,
+    asset_server: Res<AssetServer>,
+    mut commands: Commands,
+) {
+    commands.entity(add.entity).with_children(|parent| {
+        parent.spawn((
+            ShieldBubble,
+            Sprite {
+                image: asset_server.load(SHIELD_IMAGE),
+                custom_size: Some(Vec2::splat(SHIELD_BUBBLE_SIZE)),
+                color: Color::WHITE.with_alpha(0.6),
+                ..default()
+            },
+            Transform::from_xyz(0., 0., 0.1),
+        ));
+    });
+}
+
+fn hide_shield_bubble(
+    remove: On<Remove, Shield>,
+    children_query: Query<&Children>,
+    bubble_query: Query<(), With<ShieldBubble>>,
+    mut commands: Commands,
+) {
+    let Ok(children) = children_query.get(remove.entity) else {
+        return;
+    };
+    for child in children
+        .iter()
+        .filter(|&child| bubble_query.contains(child))
+    {
+        commands.entity(child).despawn();
+    }
+}
+
+// detect_collision already swapped the shield for a short Invincible window, this is only the
+// feedback. Reduced motion skips the flash
+fn break_shield(
+    broken_event: On<ShieldBrokenEvent>,
+    mut commands: Commands,
+    settings: Res<Settings>,
+    asset_server: Res<AssetServer>,
+    layers: Res<ZLayers>,
+) {
+    let position = broken_event.position.extend(layers.particle);
+    spawn_particle_burst(&mut commands, position, LIGHT_SKY_BLUE.into(), 20);
+    commands.spawn((
+        AudioPlayer::new(asset_server.load(SHIELD_BREAK_SOUND)),
+        PlaybackSettings::DESPAWN,
+    ));
+
+    if !settings.reduced_motion {
+        commands
+            .entity(broken_event.player)
+            .insert(ShieldFlash(Timer::from_seconds(
+                SHIELD_FLASH_DURATION,
+                TimerMode::Once,
+            )));
+    }
+}
+
+// Only touches the color channels, the invincibility flashing owns the alpha
+fn fade_shield_flash(
+    time: Res<Time>,
+    mut commands: Commands,
+    mut query: Query<(Entity, &mut ShieldFlash, &mut Sprite)>,
+) {
+    for (entity, mut flash, mut sprite) in query.iter_mut() {
+        let alpha = sprite.color.alpha();
+        if flash.0.tick(time.delta()).just_finished() {
+            sprite.color = Color::WHITE.with_alpha(alpha);
+            commands.entity(entity).remove::<ShieldFlash>();
+            continue;
+        }
+
+        let brightness = 1. + (SHIELD_FLASH_BRIGHTNESS - 1.) * flash.0.fraction_remaining();
+        sprite.color = Color::linear_rgba(brightness, brightness, brightness, alpha);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_interval_gate_carries_a_pickup() {
+        let carrying: Vec<u32> = (0..SHIELD_PICKUP_INTERVAL * 3)
+            .filter(|&index| carries_pickup(index))
+            .collect();
+        assert_eq!(
+            carrying,
+            [1, 2, 3].map(|count| count * SHIELD_PICKUP_INTERVAL - 1)
+        );
+    }
+}
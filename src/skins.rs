@@ -0,0 +1,109 @@
+use bevy::{asset::AssetEvent, prelude::*};
+
+use crate::{
+    asset_variants::image_variant,
+    components::AnimationIndices,
+    resources::{BirdSkin, HiDpi, SkinFallback},
+};
+
+pub struct BirdSkinDef {
+    pub image: &'static str,
+    pub frame_size: UVec2,
+    pub columns: u32,
+    pub rows: u32,
+    // For sheets drawn facing left, the bird always flies to the right
+    pub flip_x: bool,
+}
+
+pub const CLASSIC_BIRD_SKIN: BirdSkinDef = BirdSkinDef {
+    image: "bird_old.png",
+    frame_size: UVec2::new(68, 48),
+    columns: 3,
+    rows: 1,
+    flip_x: false,
+};
+
+impl BirdSkinDef {
+    pub fn animation_indices(&self) -> AnimationIndices {
+        AnimationIndices {
+            first: 0,
+            last: (self.columns * self.rows) as usize - 1,
+        }
+    }
+
+    pub fn atlas_layout(&self, scale: u32) -> TextureAtlasLayout {
+        TextureAtlasLayout::from_grid(self.frame_size * scale, self.columns, self.rows, None, None)
+    }
+}
+
+pub fn skins_plugin(app: &mut App) {
+    app.init_resource::<BirdSkin>()
+        .init_resource::<SkinFallback>()
+        .add_systems(
+            Update,
+            (
+                validate_bird_skin,
+                apply_skin_fallback.run_if(skin_fallback_active),
+            )
+                .chain(),
+        );
+}
+
+fn validate_bird_skin(
+    mut image_events: MessageReader<AssetEvent<Image>>,
+    asset_server: Res<AssetServer>,
+    images: Res<Assets<Image>>,
+    skin: Res<BirdSkin>,
+    hidpi: Res<HiDpi>,
+    mut fallback: ResMut<SkinFallback>,
+) {
+    let variant = image_variant(skin.0.image, &hidpi);
+    for event in image_events.read() {
+        let AssetEvent::LoadedWithDependencies { id } = event else {
+            continue;
+        };
+        let is_skin_image = asset_server
+            .get_path(*id)
+            .is_some_and(|path| path.path().to_str() == Some(variant.path.as_str()));
+        let Some(image) = images.get(*id).filter(|_| is_skin_image) else {
+            continue;
+        };
+
+        let expected_size =
+            skin.0.frame_size * variant.scale * UVec2::new(skin.0.columns, skin.0.rows);
+        if image.size() != expected_size {
+            error!(
+                "{} is {} but the skin expects {} ({}x{} frames of {}), showing a single frame \
+                 without animation",
+                variant.path,
+                image.size(),
+                expected_size,
+                skin.0.columns,
+                skin.0.rows,
+                skin.0.frame_size * variant.scale
+            );
+            let frame = (skin.0.frame_size * variant.scale).min(image.size());
+            fallback.0 = Some(Rect::from_corners(Vec2::ZERO, frame.as_vec2()));
+        } else {
+            fallback.0 = None;
+        }
+    }
+}
+
+fn skin_fallback_active(fallback: Res<SkinFallback>) -> bool {
+    fallback.0.is_some()
+}
+
+// A garbled atlas would cut frames across cell borders, so birds show the top left cell of the
+// sheet instead. Covers birds spawned before the image finished loading as well as later ones
+fn apply_skin_fallback(
+    fallback: Res<SkinFallback>,
+    mut query: Query<&mut Sprite, With<AnimationIndices>>,
+) {
+    for mut sprite in query.iter_mut() {
+        if sprite.texture_atlas.is_some() {
+            sprite.texture_atlas = None;
+            sprite.rect = fallback.0;
+        }
+    }
+}
@@ -0,0 +1,56 @@
+use bevy::prelude::*;
+
+use crate::{
+    resources::{RunFlags, StepControl},
+    AppState, PauseState,
+};
+
+pub fn step_plugin(app: &mut App) {
+    app.init_resource::<StepControl>()
+        .add_systems(OnEnter(AppState::InGame), reset_step_ticks)
+        .add_systems(
+            Update,
+            (step_input, flag_stepped_runs).run_if(in_state(PauseState::Running)),
+        )
+        .add_systems(
+            FixedLast,
+            advance_step.run_if(in_state(PauseState::Running)),
+        );
+}
+
+pub fn physics_running(step_control: Res<StepControl>) -> bool {
+    !step_control.enabled || step_control.pending_steps > 0
+}
+
+fn reset_step_ticks(mut step_control: ResMut<StepControl>) {
+    step_control.tick = 0;
+    step_control.pending_steps = 0;
+}
+
+fn step_input(key_input: Res<ButtonInput<KeyCode>>, mut step_control: ResMut<StepControl>) {
+    if key_input.just_pressed(KeyCode::Backslash) {
+        step_control.enabled = !step_control.enabled;
+        step_control.pending_steps = 0;
+    }
+
+    if step_control.enabled && key_input.just_pressed(KeyCode::Period) {
+        step_control.pending_steps += 1;
+    }
+}
+
+// Runs after every fixed tick, so a single key press releases exactly one physics tick
+fn advance_step(mut step_control: ResMut<StepControl>) {
+    if step_control.enabled {
+        if step_control.pending_steps == 0 {
+            return;
+        }
+        step_control.pending_steps -= 1;
+    }
+    step_control.tick += 1;
+}
+
+fn flag_stepped_runs(step_control: Res<StepControl>, mut run_flags: ResMut<RunFlags>) {
+    if step_control.enabled {
+        run_flags.stepped = true;
+    }
+}
@@ -0,0 +1,22 @@
+use bevy::prelude::*;
+
+use crate::resources::{Settings, Theme};
+
+const DAY_CLEAR_COLOR: Color = Color::srgb(0.44, 0.77, 0.81);
+const NIGHT_CLEAR_COLOR: Color = Color::srgb(0.05, 0.07, 0.16);
+
+pub fn theme_plugin(app: &mut App) {
+    app.add_systems(Update, apply_theme.run_if(resource_changed::<Settings>));
+}
+
+// Also runs on the first frame, since the settings resource counts as changed when added
+fn apply_theme(settings: Res<Settings>, mut clear_color: ResMut<ClearColor>) {
+    let color = match settings.theme {
+        Theme::Day => DAY_CLEAR_COLOR,
+        Theme::Night => NIGHT_CLEAR_COLOR,
+    };
+
+    if clear_color.0 != color {
+        clear_color.0 = color;
+    }
+}
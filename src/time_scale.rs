@@ -0,0 +1,100 @@
+use bevy::prelude::*;
+
+use crate::{
+    constants::SLOW_MOTION_SPEED,
+    resources::{RunFlags, Settings, TimeScale},
+    AppState,
+};
+
+pub fn time_scale_plugin(app: &mut App) {
+    app.init_resource::<TimeScale>()
+        .add_systems(
+            Update,
+            (toggle_time_scale, apply_time_scale, flag_scaled_runs)
+                .chain()
+                .run_if(in_state(AppState::GameStart).or(in_state(AppState::InGame))),
+        )
+        .add_systems(OnExit(AppState::InGame), reset_time_scale);
+}
+
+fn toggle_time_scale(key_input: Res<ButtonInput<KeyCode>>, mut time_scale: ResMut<TimeScale>) {
+    if key_input.just_pressed(KeyCode::KeyT) {
+        time_scale.slow_motion = !time_scale.slow_motion;
+        time_scale.turbo = false;
+    }
+    if key_input.just_pressed(KeyCode::KeyF) {
+        time_scale.turbo = !time_scale.turbo;
+        time_scale.slow_motion = false;
+    }
+}
+
+// Only the relative speed changes, the next delta is scaled as a whole so there is no jump.
+fn apply_time_scale(
+    time_scale: Res<TimeScale>,
+    settings: Res<Settings>,
+    mut time: ResMut<Time<Virtual>>,
+) {
+    if !time_scale.is_changed() && !settings.is_changed() {
+        return;
+    }
+
+    let speed = if time_scale.slow_motion {
+        SLOW_MOTION_SPEED
+    } else if time_scale.turbo {
+        settings.turbo_speed
+    } else {
+        1.
+    };
+    time.set_relative_speed(speed);
+}
+
+// The menus, the death replay and the next run all start at normal speed
+fn reset_time_scale(mut time_scale: ResMut<TimeScale>, mut time: ResMut<Time<Virtual>>) {
+    *time_scale = TimeScale::default();
+    time.set_relative_speed(1.);
+}
+
+fn flag_scaled_runs(time_scale: Res<TimeScale>, mut run_flags: ResMut<RunFlags>) {
+    if time_scale.slow_motion {
+        run_flags.slow_motion = true;
+    }
+    if time_scale.turbo {
+        run_flags.turbo = true;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::state::app::StatesPlugin;
+
+    use super::*;
+
+    fn set_state(app: &mut App, state: AppState) {
+        app.world_mut()
+            .resource_mut::<NextState<AppState>>()
+            .set(state);
+        app.update();
+    }
+
+    #[test]
+    fn leaving_a_run_restores_normal_speed() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, StatesPlugin))
+            .init_state::<AppState>()
+            .init_resource::<ButtonInput<KeyCode>>()
+            .init_resource::<Settings>()
+            .init_resource::<RunFlags>()
+            .add_plugins(time_scale_plugin);
+        set_state(&mut app, AppState::GameStart);
+        set_state(&mut app, AppState::InGame);
+
+        app.world_mut().resource_mut::<TimeScale>().turbo = true;
+        app.update();
+        let speed = |app: &App| app.world().resource::<Time<Virtual>>().relative_speed();
+        assert_eq!(speed(&app), app.world().resource::<Settings>().turbo_speed);
+
+        set_state(&mut app, AppState::GameOver);
+        assert_eq!(speed(&app), 1.);
+        assert!(!app.world().resource::<TimeScale>().turbo);
+    }
+}
@@ -0,0 +1,77 @@
+use bevy::{color::palettes::css::ORANGE, prelude::*};
+
+use crate::{
+    components::CarefulPrompt,
+    constants::*,
+    events::TutorialSaveEvent,
+    persistence,
+    resources::{PlayerStats, Settings, TutorialSave},
+    AppState,
+};
+
+pub const PLAYER_STATS_FILE: &str = "stats.ron";
+
+pub fn tutorial_plugin(app: &mut App) {
+    app.insert_resource(persistence::load::<PlayerStats>(PLAYER_STATS_FILE))
+        .init_resource::<TutorialSave>()
+        .add_systems(OnEnter(AppState::GameStart), arm_tutorial_save)
+        .add_systems(OnEnter(AppState::GameOver), count_finished_game)
+        .add_systems(Update, fade_careful_prompt)
+        .add_observer(show_careful_prompt);
+}
+
+// Only the first few games get the save, after that the player is on their own
+fn arm_tutorial_save(
+    settings: Res<Settings>,
+    stats: Res<PlayerStats>,
+    mut tutorial_save: ResMut<TutorialSave>,
+) {
+    tutorial_save.0 = stats.games_played < settings.tutorial_games;
+}
+
+fn count_finished_game(mut stats: ResMut<PlayerStats>) {
+    stats.games_played += 1;
+    persistence::save(PLAYER_STATS_FILE, &*stats);
+}
+
+fn show_careful_prompt(_save_event: On<TutorialSaveEvent>, mut commands: Commands) {
+    commands.spawn((
+        CarefulPrompt(Timer::from_seconds(
+            CAREFUL_PROMPT_DURATION,
+            TimerMode::Once,
+        )),
+        Text::new("Careful!"),
+        TextFont {
+            font_size: 60.0,
+            ..default()
+        },
+        TextColor(ORANGE.into()),
+        Node {
+            position_type: PositionType::Absolute,
+            width: percent(100),
+            top: percent(35),
+            justify_content: JustifyContent::Center,
+            ..default()
+        },
+        TextLayout {
+            justify: Justify::Center,
+            ..default()
+        },
+        Pickable::IGNORE,
+        DespawnOnExit(AppState::InGame),
+    ));
+}
+
+fn fade_careful_prompt(
+    time: Res<Time<Real>>,
+    mut commands: Commands,
+    mut query: Query<(Entity, &mut CarefulPrompt, &mut TextColor)>,
+) {
+    for (entity, mut prompt, mut color) in query.iter_mut() {
+        if prompt.0.tick(time.delta()).just_finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        color.0.set_alpha(1. - prompt.0.fraction());
+    }
+}
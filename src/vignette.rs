@@ -0,0 +1,63 @@
+use bevy::prelude::*;
+
+use crate::{
+    components::Vignette,
+    constants::*,
+    resources::{Settings, Theme},
+};
+
+// A dusky blue over the day sky, plain black at night
+const DAY_VIGNETTE_COLOR: Color = Color::srgb(0.04, 0.1, 0.2);
+const NIGHT_VIGNETTE_COLOR: Color = Color::BLACK;
+
+pub fn vignette_plugin(app: &mut App) {
+    app.add_systems(Startup, spawn_vignette)
+        .add_systems(Update, update_vignette.run_if(resource_changed::<Settings>));
+}
+
+// Behind every other UI root, so the HUD and menus stay on top of it
+fn spawn_vignette(mut commands: Commands) {
+    commands.spawn((
+        Vignette,
+        Node {
+            position_type: PositionType::Absolute,
+            width: percent(100),
+            height: percent(100),
+            ..default()
+        },
+        BackgroundGradient::default(),
+        GlobalZIndex(-1),
+        Pickable::IGNORE,
+    ));
+}
+
+// Clear out to VIGNETTE_CLEAR_RADIUS, which keeps the bird's lane, the score and the gaps
+// untouched, then darkens towards the corners. It never moves, so reduced motion has nothing
+// to calm down
+fn vignette_gradient(settings: &Settings) -> BackgroundGradient {
+    if !settings.vignette {
+        return BackgroundGradient::default();
+    }
+
+    let color = match settings.theme {
+        Theme::Day => DAY_VIGNETTE_COLOR,
+        Theme::Night => NIGHT_VIGNETTE_COLOR,
+    };
+    RadialGradient::new(
+        UiPosition::CENTER,
+        RadialGradientShape::FarthestCorner,
+        vec![
+            ColorStop::percent(color.with_alpha(0.), VIGNETTE_CLEAR_RADIUS),
+            ColorStop::percent(color.with_alpha(settings.vignette_strength), 100.),
+        ],
+    )
+    .into()
+}
+
+// Also runs on the first frame, since the settings resource counts as changed when added
+fn update_vignette(
+    settings: Res<Settings>,
+    mut gradient: Single<&mut BackgroundGradient, With<Vignette>>,
+) {
+    **gradient = vignette_gradient(&settings);
+}